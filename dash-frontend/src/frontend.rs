@@ -24,7 +24,10 @@ use wlx_common::{
 
 use crate::{
 	assets,
-	tab::{Tab, TabType, apps::TabApps, games::TabGames, home::TabHome, monado::TabMonado, settings::TabSettings},
+	tab::{
+		Tab, TabType, apps::TabApps, gallery::TabGallery, games::TabGames, home::TabHome, monado::TabMonado,
+		settings::TabSettings,
+	},
 	util::{
 		popup_manager::{MountPopupParams, PopupManager, PopupManagerParams},
 		toast_manager::ToastManager,
@@ -418,6 +421,7 @@ impl<T: 'static> Frontend<T> {
 			TabType::Apps => ("APPLICATIONS", "dashboard/apps.svg"),
 			TabType::Games => ("GAMES", "dashboard/games.svg"),
 			TabType::Monado => ("MONADO_RUNTIME", "dashboard/monado.svg"),
+			TabType::Gallery => ("GALLERY.TITLE", "dashboard/gallery.svg"),
 			TabType::Settings => ("SETTINGS", "dashboard/settings.svg"),
 		};
 
@@ -428,6 +432,7 @@ impl<T: 'static> Frontend<T> {
 			TabType::Apps => Box::new(TabApps::new(self, widget_content.id, data)?),
 			TabType::Games => Box::new(TabGames::new(self, widget_content.id)?),
 			TabType::Monado => Box::new(TabMonado::new(self, widget_content.id)?),
+			TabType::Gallery => Box::new(TabGallery::new(self, widget_content.id, data)?),
 			TabType::Settings => Box::new(TabSettings::new(self, widget_content.id, data)?),
 		};
 
@@ -471,6 +476,12 @@ impl<T: 'static> Frontend<T> {
 			FrontendTask::SetTab(TabType::Monado),
 		);
 
+		// "Gallery" side button
+		self.tasks.handle_button(
+			&self.state.fetch_component_as::<ComponentButton>("btn_side_gallery")?,
+			FrontendTask::SetTab(TabType::Gallery),
+		);
+
 		// "Settings" side button
 		self.tasks.handle_button(
 			&self.state.fetch_component_as::<ComponentButton>("btn_side_settings")?,
@@ -0,0 +1,454 @@
+use std::{
+	cell::RefCell,
+	collections::HashMap,
+	marker::PhantomData,
+	path::{Path, PathBuf},
+	rc::Rc,
+	sync::mpsc,
+	time::SystemTime,
+};
+
+use image::RgbaImage;
+use wayvr_ipc::packet_client::{PositionMode, WvrProcessLaunchParams};
+use wgui::{
+	assets::AssetPath,
+	components::button::ComponentButton,
+	event::{CallbackDataCommon, StyleSetRequest},
+	globals::WguiGlobals,
+	i18n::Translation,
+	layout::WidgetID,
+	parser::{self, Fetchable, ParseDocumentParams, ParserState},
+	renderer_vk::text::custom_glyph::CustomGlyphData,
+	taffy,
+	task::Tasks,
+	widget::{image::WidgetImage, label::WidgetLabel},
+};
+use wlx_common::config_io;
+
+use crate::{
+	frontend::{Frontend, FrontendTask},
+	tab::{Tab, TabType},
+	util::popup_manager::{MountPopupParams, PopupHandle},
+};
+
+/// Must match the thumbnail size in gallery.xml
+const THUMBNAIL_SIZE: u32 = 232;
+/// Must match the image size in screenshot_viewer.xml
+const VIEWER_SIZE: [u32; 2] = [960, 540];
+
+#[derive(Clone)]
+enum Task {
+	Refresh,
+	View(PathBuf),
+	Open(PathBuf),
+	Delete(PathBuf),
+}
+
+struct Thumbnail {
+	path: PathBuf,
+	image: anyhow::Result<RgbaImage>,
+}
+
+struct Cell {
+	#[allow(dead_code)]
+	state: parser::ParserData,
+	id_root: WidgetID,
+	id_thumbnail: WidgetID,
+}
+
+struct State {
+	viewer: Option<(PopupHandle, ParserState)>,
+	/// Delete was pressed once in the viewer, the next press deletes
+	delete_armed: bool,
+}
+
+pub struct TabGallery<T> {
+	#[allow(dead_code)]
+	state: ParserState,
+	tasks: Tasks<Task>,
+
+	marker: PhantomData<T>,
+
+	globals: WguiGlobals,
+	id_list_parent: WidgetID,
+	id_label_path: WidgetID,
+	id_label_empty: WidgetID,
+
+	popup: Rc<RefCell<State>>,
+	cells: HashMap<PathBuf, Cell>,
+
+	/// Thumbnails are decoded on a separate thread, dropped on refresh
+	thumbnails: Option<mpsc::Receiver<Thumbnail>>,
+}
+
+impl<T> Tab<T> for TabGallery<T> {
+	fn get_type(&self) -> TabType {
+		TabType::Gallery
+	}
+
+	fn update(&mut self, frontend: &mut Frontend<T>, _time_ms: u32, data: &mut T) -> anyhow::Result<()> {
+		for task in self.tasks.drain() {
+			match task {
+				Task::Refresh => self.refresh(frontend, data)?,
+				Task::View(path) => self.view(frontend, path),
+				Task::Open(path) => self.open(frontend, data, &path),
+				Task::Delete(path) => self.delete(frontend, &path)?,
+			}
+		}
+
+		self.receive_thumbnails(frontend)
+	}
+}
+
+fn doc_params(globals: &'_ WguiGlobals) -> ParseDocumentParams<'_> {
+	ParseDocumentParams {
+		globals: globals.clone(),
+		path: AssetPath::BuiltIn("gui/tab/gallery.xml"),
+		extra: Default::default(),
+	}
+}
+
+fn viewer_doc_params(globals: &'_ WguiGlobals) -> ParseDocumentParams<'_> {
+	ParseDocumentParams {
+		globals: globals.clone(),
+		path: AssetPath::BuiltIn("gui/view/screenshot_viewer.xml"),
+		extra: Default::default(),
+	}
+}
+
+/// PNG files in `dir`, newest first
+fn list_screenshots(dir: &Path) -> Vec<PathBuf> {
+	let Ok(entries) = std::fs::read_dir(dir) else {
+		return Vec::new();
+	};
+
+	let mut files: Vec<(SystemTime, PathBuf)> = entries
+		.filter_map(|entry| {
+			let entry = entry.ok()?;
+			let path = entry.path();
+			if !path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("png")) {
+				return None;
+			}
+			let modified = entry
+				.metadata()
+				.and_then(|m| m.modified())
+				.unwrap_or(SystemTime::UNIX_EPOCH);
+			Some((modified, path))
+		})
+		.collect();
+
+	files.sort_by(|a, b| b.cmp(a));
+	files.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Decodes the image and scales it down to fit into `max_width` × `max_height`
+fn load_scaled(path: &Path, max_width: u32, max_height: u32) -> anyhow::Result<RgbaImage> {
+	let image = image::ImageReader::open(path)?.with_guessed_format()?.decode()?;
+	Ok(image.thumbnail(max_width, max_height).into_rgba8())
+}
+
+/// Sets the content of an image widget and resizes it to keep the aspect ratio
+fn set_image(common: &mut CallbackDataCommon, id: WidgetID, image: RgbaImage) -> anyhow::Result<()> {
+	let size = taffy::Size {
+		width: taffy::prelude::length(image.width() as f32),
+		height: taffy::prelude::length(image.height() as f32),
+	};
+	let glyph = CustomGlyphData::from_rgba(image.width(), image.height(), image.into_raw())?;
+
+	let state = common.state;
+	state
+		.widgets
+		.cast_as::<WidgetImage>(id)?
+		.set_content(common, Some(glyph));
+	common.alterables.set_style(id, StyleSetRequest::Size(size));
+	Ok(())
+}
+
+/// `args` are split at whitespace when launching, so the path is passed as an escaped URI
+fn file_uri(path: &Path) -> String {
+	let path = path.to_string_lossy();
+	let mut uri = String::from("file://");
+	for c in path.chars() {
+		match c {
+			'%' => uri.push_str("%25"),
+			c if c.is_whitespace() => {
+				let mut buf = [0u8; 4];
+				for byte in c.encode_utf8(&mut buf).bytes() {
+					uri.push_str(&format!("%{byte:02X}"));
+				}
+			}
+			c => uri.push(c),
+		}
+	}
+	uri
+}
+
+impl<T> TabGallery<T> {
+	pub fn new(frontend: &mut Frontend<T>, parent_id: WidgetID, _data: &mut T) -> anyhow::Result<Self> {
+		let globals = frontend.layout.state.globals.clone();
+		let state = wgui::parser::parse_from_assets(&doc_params(&globals), &mut frontend.layout, parent_id)?;
+
+		let id_list_parent = state.get_widget_id("list_parent")?;
+		let id_label_path = state.get_widget_id("label_path")?;
+		let id_label_empty = state.get_widget_id("label_empty")?;
+
+		let tasks = Tasks::<Task>::new();
+		tasks.handle_button(
+			&state.fetch_component_as::<ComponentButton>("btn_refresh")?,
+			Task::Refresh,
+		);
+		tasks.push(Task::Refresh);
+
+		Ok(Self {
+			state,
+			tasks,
+			marker: PhantomData,
+			globals,
+			id_list_parent,
+			id_label_path,
+			id_label_empty,
+			popup: Rc::new(RefCell::new(State {
+				viewer: None,
+				delete_armed: false,
+			})),
+			cells: HashMap::new(),
+			thumbnails: None,
+		})
+	}
+
+	fn mount_cell(&mut self, frontend: &mut Frontend<T>, path: &Path) -> anyhow::Result<()> {
+		let name = path
+			.file_stem()
+			.map(|name| name.to_string_lossy().into_owned())
+			.unwrap_or_default();
+
+		let mut par = HashMap::<Rc<str>, Rc<str>>::new();
+		par.insert("name".into(), name.into());
+
+		let state_cell = self.state.parse_template(
+			&doc_params(&self.globals),
+			"Screenshot",
+			&mut frontend.layout,
+			self.id_list_parent,
+			par,
+		)?;
+
+		let btn_view = state_cell.fetch_component_as::<ComponentButton>("btn_view")?;
+		self.tasks.handle_button(&btn_view, Task::View(path.to_path_buf()));
+
+		let cell = Cell {
+			id_root: state_cell.get_widget_id("root")?,
+			id_thumbnail: state_cell.get_widget_id("thumbnail")?,
+			state: state_cell,
+		};
+		self.cells.insert(path.to_path_buf(), cell);
+		Ok(())
+	}
+
+	fn refresh(&mut self, frontend: &mut Frontend<T>, data: &mut T) -> anyhow::Result<()> {
+		let dir = config_io::resolve_path(&frontend.interface.general_config(data).screenshot_path);
+		log::debug!("refreshing screenshots in {}", dir.display());
+
+		let mut c = frontend.layout.start_common();
+		{
+			let mut common = c.common();
+			let state = common.state;
+			let mut label = state.widgets.cast_as::<WidgetLabel>(self.id_label_path)?;
+			label.set_text(&mut common, Translation::from_raw_text(&dir.to_string_lossy()));
+		}
+		c.finish()?;
+
+		let files = list_screenshots(&dir);
+
+		frontend.layout.remove_children(self.id_list_parent);
+		self.cells.clear();
+
+		for path in &files {
+			self.mount_cell(frontend, path)?;
+		}
+		self.update_empty_label(frontend)?;
+
+		let (sender, receiver) = mpsc::channel();
+		std::thread::spawn(move || {
+			for path in files {
+				let image = load_scaled(&path, THUMBNAIL_SIZE, THUMBNAIL_SIZE);
+				if sender.send(Thumbnail { path, image }).is_err() {
+					// refreshed or tab closed
+					return;
+				}
+			}
+		});
+		self.thumbnails = Some(receiver);
+
+		Ok(())
+	}
+
+	fn receive_thumbnails(&mut self, frontend: &mut Frontend<T>) -> anyhow::Result<()> {
+		let Some(receiver) = self.thumbnails.as_ref() else {
+			return Ok(());
+		};
+
+		let mut c = frontend.layout.start_common();
+		let finished = loop {
+			match receiver.try_recv() {
+				Ok(Thumbnail { path, image }) => {
+					let Some(cell) = self.cells.get(&path) else {
+						continue;
+					};
+					match image {
+						Ok(image) => set_image(&mut c.common(), cell.id_thumbnail, image)?,
+						Err(e) => log::warn!("Could not load thumbnail of {}: {e:?}", path.display()),
+					}
+				}
+				Err(mpsc::TryRecvError::Empty) => break false,
+				Err(mpsc::TryRecvError::Disconnected) => break true,
+			}
+		};
+		c.finish()?;
+
+		if finished {
+			self.thumbnails = None;
+		}
+		Ok(())
+	}
+
+	fn update_empty_label(&self, frontend: &mut Frontend<T>) -> anyhow::Result<()> {
+		let display = if self.cells.is_empty() {
+			taffy::Display::Flex
+		} else {
+			taffy::Display::None
+		};
+
+		let mut c = frontend.layout.start_common();
+		c.common()
+			.alterables
+			.set_style(self.id_label_empty, StyleSetRequest::Display(display));
+		c.finish()
+	}
+
+	fn close_viewer(&self) {
+		if let Some((handle, _)) = self.popup.borrow_mut().viewer.take() {
+			handle.close();
+		}
+	}
+
+	fn view(&self, frontend: &mut Frontend<T>, path: PathBuf) {
+		let title = path
+			.file_name()
+			.map(|name| name.to_string_lossy().into_owned())
+			.unwrap_or_default();
+
+		frontend.tasks.push(FrontendTask::MountPopup(MountPopupParams {
+			title: Translation::from_raw_text(&title),
+			on_content: {
+				let globals = self.globals.clone();
+				let tasks = self.tasks.clone();
+				let popup = self.popup.clone();
+
+				Rc::new(move |data| {
+					let state = wgui::parser::parse_from_assets(&viewer_doc_params(&globals), data.layout, data.id_content)?;
+
+					let image = load_scaled(&path, VIEWER_SIZE[0], VIEWER_SIZE[1]);
+					let mut c = data.layout.start_common();
+					match image {
+						Ok(image) => set_image(&mut c.common(), state.get_widget_id("image")?, image)?,
+						Err(e) => log::warn!("Could not load {}: {e:?}", path.display()),
+					}
+					c.finish()?;
+
+					if let Ok(metadata) = std::fs::metadata(&path) {
+						let size_kib = metadata.len() / 1024;
+						state
+							.fetch_widget_as::<WidgetLabel>(&data.layout.state, "label_info")?
+							.set_text_simple(
+								&mut globals.get(),
+								Translation::from_raw_text_string(format!("{} ({size_kib} KiB)", path.display())),
+							);
+					}
+
+					tasks.handle_button(
+						&state.fetch_component_as::<ComponentButton>("btn_open")?,
+						Task::Open(path.clone()),
+					);
+					tasks.handle_button(
+						&state.fetch_component_as::<ComponentButton>("btn_delete")?,
+						Task::Delete(path.clone()),
+					);
+
+					let mut popup = popup.borrow_mut();
+					popup.viewer = Some((data.handle, state));
+					popup.delete_armed = false;
+					Ok(())
+				})
+			},
+		}));
+	}
+
+	/// Opens the default image viewer as a WayVR window
+	fn open(&self, frontend: &mut Frontend<T>, data: &mut T, path: &Path) {
+		let name = path
+			.file_name()
+			.map(|name| name.to_string_lossy().into_owned())
+			.unwrap_or_default();
+
+		let params = WvrProcessLaunchParams {
+			name,
+			exec: "xdg-open".into(),
+			env: Vec::new(),
+			args: file_uri(path),
+			icon: None,
+			resolution: [1920, 1080],
+			pos_mode: PositionMode::Float,
+			userdata: HashMap::new(),
+		};
+
+		if let Err(e) = frontend.interface.process_launch(data, false, params) {
+			let str_failed = self.globals.i18n().translate("GALLERY.FAILED_TO_OPEN");
+			frontend
+				.tasks
+				.push(FrontendTask::PushToast(Translation::from_raw_text_string(format!(
+					"{str_failed}: {e:?}"
+				))));
+			return;
+		}
+
+		self.close_viewer();
+	}
+
+	/// The first press only asks for confirmation
+	fn delete(&mut self, frontend: &mut Frontend<T>, path: &Path) -> anyhow::Result<()> {
+		{
+			let mut popup = self.popup.borrow_mut();
+			if !popup.delete_armed {
+				popup.delete_armed = true;
+				if let Some((_, state)) = popup.viewer.as_ref() {
+					let button = state.fetch_component_as::<ComponentButton>("btn_delete")?;
+					let mut c = frontend.layout.start_common();
+					button.set_text(
+						&mut c.common(),
+						Translation::from_translation_key("GALLERY.DELETE_CONFIRM"),
+					);
+					c.finish()?;
+				}
+				return Ok(());
+			}
+		}
+
+		if let Err(e) = std::fs::remove_file(path) {
+			let str_failed = self.globals.i18n().translate("GALLERY.FAILED_TO_DELETE");
+			frontend
+				.tasks
+				.push(FrontendTask::PushToast(Translation::from_raw_text_string(format!(
+					"{str_failed}: {e}"
+				))));
+			return Ok(());
+		}
+		log::info!("Deleted screenshot {}", path.display());
+
+		self.close_viewer();
+		if let Some(cell) = self.cells.remove(path) {
+			frontend.layout.remove_widget(cell.id_root);
+		}
+		self.update_empty_label(frontend)
+	}
+}
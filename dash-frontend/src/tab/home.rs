@@ -66,6 +66,7 @@ impl<T> TabHome<T> {
 		let btn_apps = state.fetch_component_as::<ComponentButton>("btn_apps")?;
 		let btn_games = state.fetch_component_as::<ComponentButton>("btn_games")?;
 		let btn_monado = state.fetch_component_as::<ComponentButton>("btn_monado")?;
+		let btn_gallery = state.fetch_component_as::<ComponentButton>("btn_gallery")?;
 		//let btn_processes = state.fetch_component_as::<ComponentButton>("btn_processes")?;
 		let btn_settings = state.fetch_component_as::<ComponentButton>("btn_settings")?;
 
@@ -73,6 +74,7 @@ impl<T> TabHome<T> {
 		tasks.handle_button(&btn_apps, FrontendTask::SetTab(TabType::Apps));
 		tasks.handle_button(&btn_games, FrontendTask::SetTab(TabType::Games));
 		tasks.handle_button(&btn_monado, FrontendTask::SetTab(TabType::Monado));
+		tasks.handle_button(&btn_gallery, FrontendTask::SetTab(TabType::Gallery));
 		//tasks.handle_button(&btn_processes, FrontendTask::SetTab(TabType::Processes));
 		tasks.handle_button(&btn_settings, FrontendTask::SetTab(TabType::Settings));
 
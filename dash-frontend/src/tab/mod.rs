@@ -1,6 +1,7 @@
 use crate::frontend::Frontend;
 
 pub mod apps;
+pub mod gallery;
 pub mod games;
 pub mod home;
 pub mod monado;
@@ -12,6 +13,7 @@ pub enum TabType {
 	Apps,
 	Games,
 	Monado,
+	Gallery,
 	Settings,
 }
 
@@ -61,6 +61,7 @@ enum Task {
 	UpdateBool(SettingType, bool),
 	UpdateFloat(SettingType, f32),
 	UpdateInt(SettingType, i32),
+	UpdateUint(SettingType, u32),
 	SettingUpdated(SettingType),
 	OpenContextMenu(Vec2, Vec<context_menu::Cell>),
 	ClearPipewireTokens,
@@ -121,6 +122,15 @@ impl<T> Tab<T> for TabSettings<T> {
 					*setting.mut_i32(config) = n;
 					changed = true;
 				}
+				Task::UpdateUint(setting, n) => {
+					self.tasks.push(Task::SettingUpdated(setting));
+					if let Some(task) = setting.get_frontend_task() {
+						frontend.tasks.push(task)
+					}
+					let config = frontend.interface.general_config(data);
+					*setting.mut_u32(config) = n;
+					changed = true;
+				}
 				Task::ClearPipewireTokens => {
 					let _ = std::fs::remove_file(ConfigRoot::Generic.get_conf_d_path().join("pw_tokens.yaml"))
 						.log_err("Could not remove pw_tokens.yaml");
@@ -226,12 +236,19 @@ enum SettingType {
 	HideUsername,
 	InvertScrollDirectionX,
 	InvertScrollDirectionY,
+	KeyboardAutocorrect,
+	KeyboardLearnWords,
 	KeyboardMiddleClick,
+	KeyboardRepeatDelayMs,
+	KeyboardRepeatRate,
 	KeyboardSoundEnabled,
+	KeyboardSplit,
+	KeyboardSuggestions,
 	Language,
 	LeftHandedMouse,
 	LongPressDuration,
 	NotificationsEnabled,
+	NotificationsServer,
 	NotificationsSoundEnabled,
 	OpaqueBackground,
 	PointerLerpFactor,
@@ -247,6 +264,17 @@ enum SettingType {
 	UprightScreenFix,
 	UsePassthrough,
 	UseSkybox,
+	WatchHand,
+	WatchOffsetX,
+	WatchOffsetY,
+	WatchOffsetZ,
+	WatchRotationX,
+	WatchRotationY,
+	WatchRotationZ,
+	WatchViewAngleMaxLeft,
+	WatchViewAngleMaxRight,
+	WatchViewAngleMinLeft,
+	WatchViewAngleMinRight,
 	XrClickSensitivity,
 	XrClickSensitivityRelease,
 	XwaylandByDefault,
@@ -259,7 +287,12 @@ impl SettingType {
 			Self::InvertScrollDirectionY => &mut config.invert_scroll_direction_y,
 			Self::NotificationsEnabled => &mut config.notifications_enabled,
 			Self::NotificationsSoundEnabled => &mut config.notifications_sound_enabled,
+			Self::NotificationsServer => &mut config.notifications_server,
 			Self::KeyboardSoundEnabled => &mut config.keyboard_sound_enabled,
+			Self::KeyboardSuggestions => &mut config.keyboard_suggestions,
+			Self::KeyboardLearnWords => &mut config.keyboard_learn_words,
+			Self::KeyboardAutocorrect => &mut config.keyboard_autocorrect,
+			Self::KeyboardSplit => &mut config.keyboard_split,
 			Self::UprightScreenFix => &mut config.upright_screen_fix,
 			Self::DoubleCursorFix => &mut config.double_cursor_fix,
 			Self::SetsOnWatch => &mut config.sets_on_watch,
@@ -294,6 +327,16 @@ impl SettingType {
 			Self::XrClickSensitivityRelease => &mut config.xr_click_sensitivity_release,
 			Self::SpaceDragMultiplier => &mut config.space_drag_multiplier,
 			Self::PointerLerpFactor => &mut config.pointer_lerp_factor,
+			Self::WatchOffsetX => &mut config.watch_offset[0],
+			Self::WatchOffsetY => &mut config.watch_offset[1],
+			Self::WatchOffsetZ => &mut config.watch_offset[2],
+			Self::WatchRotationX => &mut config.watch_rotation[0],
+			Self::WatchRotationY => &mut config.watch_rotation[1],
+			Self::WatchRotationZ => &mut config.watch_rotation[2],
+			Self::WatchViewAngleMaxLeft => &mut config.watch_view_angle_max_left,
+			Self::WatchViewAngleMaxRight => &mut config.watch_view_angle_max_right,
+			Self::WatchViewAngleMinLeft => &mut config.watch_view_angle_min_left,
+			Self::WatchViewAngleMinRight => &mut config.watch_view_angle_min_right,
 			_ => panic!("Requested f32 for non-f32 SettingType"),
 		}
 	}
@@ -305,6 +348,14 @@ impl SettingType {
 		}
 	}
 
+	pub fn mut_u32(self, config: &mut GeneralConfig) -> &mut u32 {
+		match self {
+			Self::KeyboardRepeatDelayMs => &mut config.keyboard_repeat_delay_ms,
+			Self::KeyboardRepeatRate => &mut config.keyboard_repeat_rate,
+			_ => panic!("Requested u32 for non-u32 SettingType"),
+		}
+	}
+
 	pub fn set_enum(self, config: &mut GeneralConfig, value: &str) {
 		match self {
 			Self::CaptureMethod => {
@@ -320,6 +371,9 @@ impl SettingType {
 			Self::Language => {
 				config.language = Some(wlx_common::locale::Language::from_str(value).expect("Invalid enum value!"))
 			}
+			Self::WatchHand => {
+				config.watch_hand = wlx_common::common::LeftRight::from_str(value).expect("Invalid enum value!")
+			}
 			_ => panic!("Requested enum for non-enum SettingType"),
 		}
 	}
@@ -333,6 +387,7 @@ impl SettingType {
 				Some(lang) => Self::get_enum_title_inner(*lang),
 				None => Translation::from_translation_key("APP_SETTINGS.OPTION.AUTO"),
 			},
+			Self::WatchHand => Self::get_enum_title_inner(config.watch_hand),
 			_ => panic!("Requested enum for non-enum SettingType"),
 		}
 	}
@@ -373,12 +428,19 @@ impl SettingType {
 			Self::HideUsername => Ok("APP_SETTINGS.HIDE_USERNAME"),
 			Self::InvertScrollDirectionX => Ok("APP_SETTINGS.INVERT_SCROLL_DIRECTION_X"),
 			Self::InvertScrollDirectionY => Ok("APP_SETTINGS.INVERT_SCROLL_DIRECTION_Y"),
+			Self::KeyboardAutocorrect => Ok("APP_SETTINGS.KEYBOARD_AUTOCORRECT"),
+			Self::KeyboardLearnWords => Ok("APP_SETTINGS.KEYBOARD_LEARN_WORDS"),
 			Self::KeyboardMiddleClick => Ok("APP_SETTINGS.KEYBOARD_MIDDLE_CLICK"),
+			Self::KeyboardRepeatDelayMs => Ok("APP_SETTINGS.KEYBOARD_REPEAT_DELAY_MS"),
+			Self::KeyboardRepeatRate => Ok("APP_SETTINGS.KEYBOARD_REPEAT_RATE"),
 			Self::KeyboardSoundEnabled => Ok("APP_SETTINGS.KEYBOARD_SOUND_ENABLED"),
+			Self::KeyboardSplit => Ok("APP_SETTINGS.KEYBOARD_SPLIT"),
+			Self::KeyboardSuggestions => Ok("APP_SETTINGS.KEYBOARD_SUGGESTIONS"),
 			Self::Language => Ok("APP_SETTINGS.LANGUAGE"),
 			Self::LeftHandedMouse => Ok("APP_SETTINGS.LEFT_HANDED_MOUSE"),
 			Self::LongPressDuration => Ok("APP_SETTINGS.LONG_PRESS_DURATION"),
 			Self::NotificationsEnabled => Ok("APP_SETTINGS.NOTIFICATIONS_ENABLED"),
+			Self::NotificationsServer => Ok("APP_SETTINGS.NOTIFICATIONS_SERVER"),
 			Self::NotificationsSoundEnabled => Ok("APP_SETTINGS.NOTIFICATIONS_SOUND_ENABLED"),
 			Self::OpaqueBackground => Ok("APP_SETTINGS.OPAQUE_BACKGROUND"),
 			Self::PointerLerpFactor => Ok("APP_SETTINGS.POINTER_LERP_FACTOR"),
@@ -394,6 +456,17 @@ impl SettingType {
 			Self::UprightScreenFix => Ok("APP_SETTINGS.UPRIGHT_SCREEN_FIX"),
 			Self::UsePassthrough => Ok("APP_SETTINGS.USE_PASSTHROUGH"),
 			Self::UseSkybox => Ok("APP_SETTINGS.USE_SKYBOX"),
+			Self::WatchHand => Ok("APP_SETTINGS.WATCH_HAND"),
+			Self::WatchOffsetX => Ok("APP_SETTINGS.WATCH_OFFSET_X"),
+			Self::WatchOffsetY => Ok("APP_SETTINGS.WATCH_OFFSET_Y"),
+			Self::WatchOffsetZ => Ok("APP_SETTINGS.WATCH_OFFSET_Z"),
+			Self::WatchRotationX => Ok("APP_SETTINGS.WATCH_ROTATION_X"),
+			Self::WatchRotationY => Ok("APP_SETTINGS.WATCH_ROTATION_Y"),
+			Self::WatchRotationZ => Ok("APP_SETTINGS.WATCH_ROTATION_Z"),
+			Self::WatchViewAngleMaxLeft => Ok("APP_SETTINGS.WATCH_VIEW_ANGLE_MAX_LEFT"),
+			Self::WatchViewAngleMaxRight => Ok("APP_SETTINGS.WATCH_VIEW_ANGLE_MAX_RIGHT"),
+			Self::WatchViewAngleMinLeft => Ok("APP_SETTINGS.WATCH_VIEW_ANGLE_MIN_LEFT"),
+			Self::WatchViewAngleMinRight => Ok("APP_SETTINGS.WATCH_VIEW_ANGLE_MIN_RIGHT"),
 			Self::XrClickSensitivity => Ok("APP_SETTINGS.XR_CLICK_SENSITIVITY"),
 			Self::XrClickSensitivityRelease => Ok("APP_SETTINGS.XR_CLICK_SENSITIVITY_RELEASE"),
 			Self::XwaylandByDefault => Ok("APP_SETTINGS.XWAYLAND_BY_DEFAULT"),
@@ -409,12 +482,22 @@ impl SettingType {
 			Self::CaptureMethod => Some("APP_SETTINGS.CAPTURE_METHOD_HELP"),
 			Self::DoubleCursorFix => Some("APP_SETTINGS.DOUBLE_CURSOR_FIX_HELP"),
 			Self::HandsfreePointer => Some("APP_SETTINGS.HANDSFREE_POINTER_HELP"),
+			Self::KeyboardAutocorrect => Some("APP_SETTINGS.KEYBOARD_AUTOCORRECT_HELP"),
+			Self::KeyboardLearnWords => Some("APP_SETTINGS.KEYBOARD_LEARN_WORDS_HELP"),
 			Self::KeyboardMiddleClick => Some("APP_SETTINGS.KEYBOARD_MIDDLE_CLICK_HELP"),
+			Self::KeyboardRepeatRate => Some("APP_SETTINGS.KEYBOARD_REPEAT_RATE_HELP"),
+			Self::KeyboardSplit => Some("APP_SETTINGS.KEYBOARD_SPLIT_HELP"),
+			Self::KeyboardSuggestions => Some("APP_SETTINGS.KEYBOARD_SUGGESTIONS_HELP"),
 			Self::LeftHandedMouse => Some("APP_SETTINGS.LEFT_HANDED_MOUSE_HELP"),
+			Self::NotificationsServer => Some("APP_SETTINGS.NOTIFICATIONS_SERVER_HELP"),
 			Self::ScreenRenderDown => Some("APP_SETTINGS.SCREEN_RENDER_DOWN_HELP"),
 			Self::UprightScreenFix => Some("APP_SETTINGS.UPRIGHT_SCREEN_FIX_HELP"),
 			Self::UsePassthrough => Some("APP_SETTINGS.USE_PASSTHROUGH_HELP"),
 			Self::UseSkybox => Some("APP_SETTINGS.USE_SKYBOX_HELP"),
+			Self::WatchOffsetX | Self::WatchOffsetY | Self::WatchOffsetZ => Some("APP_SETTINGS.WATCH_OFFSET_HELP"),
+			Self::WatchRotationX | Self::WatchRotationY | Self::WatchRotationZ => Some("APP_SETTINGS.WATCH_ROTATION_HELP"),
+			Self::WatchViewAngleMaxLeft | Self::WatchViewAngleMaxRight => Some("APP_SETTINGS.WATCH_VIEW_ANGLE_MAX_HELP"),
+			Self::WatchViewAngleMinLeft | Self::WatchViewAngleMinRight => Some("APP_SETTINGS.WATCH_VIEW_ANGLE_MIN_HELP"),
 			Self::XrClickSensitivity => Some("APP_SETTINGS.XR_CLICK_SENSITIVITY_HELP"),
 			Self::XrClickSensitivityRelease => Some("APP_SETTINGS.XR_CLICK_SENSITIVITY_RELEASE_HELP"),
 			_ => None,
@@ -429,7 +512,9 @@ impl SettingType {
 			| Self::DoubleCursorFix
 			| Self::ScreenRenderDown
 			| Self::Language
-			| Self::CaptureMethod => true,
+			| Self::CaptureMethod
+			| Self::KeyboardSplit
+			| Self::NotificationsServer => true,
 			_ => false,
 		}
 	}
@@ -626,6 +711,50 @@ macro_rules! slider_i32 {
 	};
 }
 
+macro_rules! slider_u32 {
+	($mp:expr, $root:expr, $setting:expr, $min:expr, $max:expr, $step:expr) => {
+		let id = $mp.idx.to_string();
+		$mp.idx += 1;
+
+		let mut params: HashMap<Rc<str>, Rc<str>> = HashMap::new();
+		params.insert(Rc::from("id"), Rc::from(id.as_ref()));
+
+		match $setting.get_translation() {
+			Ok(translation) => params.insert(Rc::from("translation"), translation.into()),
+			Err(raw_text) => params.insert(Rc::from("text"), raw_text.into()),
+		};
+
+		if let Some(tooltip) = $setting.get_tooltip() {
+			params.insert(Rc::from("tooltip"), Rc::from(tooltip));
+		}
+
+		let id_cell = horiz_cell($mp.layout, $root)?;
+
+		let value = $setting.mut_u32($mp.config).to_string();
+		params.insert(Rc::from("value"), Rc::from(value));
+		params.insert(Rc::from("min"), Rc::from($min.to_string()));
+		params.insert(Rc::from("max"), Rc::from($max.to_string()));
+		params.insert(Rc::from("step"), Rc::from($step.to_string()));
+
+		$mp
+			.parser_state
+			.instantiate_template($mp.doc_params, "SliderSetting", $mp.layout, id_cell, params)?;
+
+		if $setting.requires_restart() {
+			mount_requires_restart($mp.layout, id_cell)?;
+		}
+
+		let slider = $mp.parser_state.fetch_component_as::<ComponentSlider>(&id)?;
+		slider.on_value_changed(Box::new({
+			let tasks = $mp.tasks.clone();
+			move |_common, e| {
+				tasks.push(Task::UpdateUint($setting, e.value as u32));
+				Ok(())
+			}
+		}));
+	};
+}
+
 macro_rules! dropdown {
 	($mp:expr /* `MacroParams` struct */, $root:expr, $setting:expr, $options:expr) => {
 		let id = $mp.idx.to_string();
@@ -802,7 +931,12 @@ impl<T> TabSettings<T> {
 				let c = category!(mp, root, "APP_SETTINGS.FEATURES", "dashboard/options.svg")?;
 				checkbox!(mp, c, SettingType::NotificationsEnabled);
 				checkbox!(mp, c, SettingType::NotificationsSoundEnabled);
+				checkbox!(mp, c, SettingType::NotificationsServer);
 				checkbox!(mp, c, SettingType::KeyboardSoundEnabled);
+				checkbox!(mp, c, SettingType::KeyboardSuggestions);
+				checkbox!(mp, c, SettingType::KeyboardLearnWords);
+				checkbox!(mp, c, SettingType::KeyboardAutocorrect);
+				checkbox!(mp, c, SettingType::KeyboardSplit);
 				checkbox!(mp, c, SettingType::SpaceDragUnlocked);
 				checkbox!(mp, c, SettingType::SpaceRotateUnlocked);
 				slider_f32!(mp, c, SettingType::SpaceDragMultiplier, -10.0, 10.0, 0.5);
@@ -835,6 +969,21 @@ impl<T> TabSettings<T> {
 				slider_f32!(mp, c, SettingType::XrClickSensitivity, 0.1, 1.0, 0.1);
 				slider_f32!(mp, c, SettingType::XrClickSensitivityRelease, 0.1, 1.0, 0.1);
 				slider_i32!(mp, c, SettingType::ClickFreezeTimeMs, 0, 500, 50);
+				slider_u32!(mp, c, SettingType::KeyboardRepeatDelayMs, 100, 1000, 50);
+				slider_u32!(mp, c, SettingType::KeyboardRepeatRate, 0, 50, 5);
+
+				let c = category!(mp, root, "APP_SETTINGS.WATCH", "dashboard/vr.svg")?;
+				dropdown!(mp, c, SettingType::WatchHand, wlx_common::common::LeftRight::VARIANTS);
+				slider_f32!(mp, c, SettingType::WatchOffsetX, -0.1, 0.1, 0.005);
+				slider_f32!(mp, c, SettingType::WatchOffsetY, -0.1, 0.1, 0.005);
+				slider_f32!(mp, c, SettingType::WatchOffsetZ, -0.1, 0.1, 0.005);
+				slider_f32!(mp, c, SettingType::WatchRotationX, -90.0, 90.0, 5.0);
+				slider_f32!(mp, c, SettingType::WatchRotationY, -90.0, 90.0, 5.0);
+				slider_f32!(mp, c, SettingType::WatchRotationZ, -90.0, 90.0, 5.0);
+				slider_f32!(mp, c, SettingType::WatchViewAngleMinLeft, 0.0, 1.0, 0.05);
+				slider_f32!(mp, c, SettingType::WatchViewAngleMaxLeft, 0.0, 1.0, 0.05);
+				slider_f32!(mp, c, SettingType::WatchViewAngleMinRight, 0.0, 1.0, 0.05);
+				slider_f32!(mp, c, SettingType::WatchViewAngleMaxRight, 0.0, 1.0, 0.05);
 			}
 			TabNameEnum::Misc => {
 				let c = category!(mp, root, "APP_SETTINGS.MISC", "dashboard/blocks.svg")?;
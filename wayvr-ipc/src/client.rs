@@ -427,6 +427,54 @@ impl WayVRClient {
 		send_only!(client, &PacketClient::WlxModifyPanel(params));
 		Ok(())
 	}
+
+	pub async fn fn_wlx_panel_get_value(
+		client: WayVRClientMutex,
+		serial: Serial,
+		params: packet_client::WlxPanelGetValueParams,
+	) -> anyhow::Result<String> {
+		send_and_wait!(
+			client,
+			serial,
+			&PacketClient::WlxPanelGetValue(serial, params),
+			WlxPanelGetValueResponse
+		)
+		.map_err(|e| anyhow::anyhow!("{}", e))
+	}
+
+	pub async fn fn_wlx_alarm_add(
+		client: WayVRClientMutex,
+		serial: Serial,
+		params: packet_client::WlxAlarmAddParams,
+	) -> anyhow::Result<u32> {
+		send_and_wait!(
+			client,
+			serial,
+			&PacketClient::WlxAlarmAdd(serial, params),
+			WlxAlarmAddResponse
+		)
+		.map_err(|e| anyhow::anyhow!("{}", e))
+	}
+
+	pub async fn fn_wlx_alarm_list(
+		client: WayVRClientMutex,
+		serial: Serial,
+	) -> anyhow::Result<Vec<packet_server::WlxAlarm>> {
+		Ok(
+			send_and_wait!(
+				client,
+				serial,
+				&PacketClient::WlxAlarmList(serial),
+				WlxAlarmListResponse
+			)
+			.list,
+		)
+	}
+
+	pub async fn fn_wlx_alarm_remove(client: WayVRClientMutex, id: u32) -> anyhow::Result<()> {
+		send_only!(client, &PacketClient::WlxAlarmRemove(id));
+		Ok(())
+	}
 }
 
 impl Drop for WayVRClient {
@@ -46,6 +46,7 @@ pub enum WlxModifyPanelCommand {
 	SetImage(String),
 	SetVisible(bool),
 	SetStickyState(bool),
+	SetValue(String),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -55,6 +56,28 @@ pub struct WlxModifyPanelParams {
 	pub command: WlxModifyPanelCommand,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WlxPanelGetValueParams {
+	pub overlay: String,
+	pub element: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum WlxAlarmWhen {
+	/// Once, this many seconds from now
+	In(u32),
+	/// Once, at the next occurrence of this local time
+	At { hour: u32, minute: u32 },
+	/// Every this many seconds, until removed. At least 60.
+	Every(u32),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WlxAlarmAddParams {
+	pub when: WlxAlarmWhen,
+	pub label: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum PacketClient {
 	Handshake(Handshake),
@@ -69,4 +92,8 @@ pub enum PacketClient {
 	WlxDeviceHaptics(usize, WlxHapticsParams),
 	WlxShowHide,
 	WlxSwitchSet(Option<usize>),
+	WlxPanelGetValue(Serial, WlxPanelGetValueParams),
+	WlxAlarmAdd(Serial, WlxAlarmAddParams),
+	WlxAlarmList(Serial),
+	WlxAlarmRemove(u32),
 }
@@ -93,6 +93,27 @@ pub struct WlxInputState {
 	pub right: WlxInputStatePointer,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WlxAlarmKind {
+	Countdown,
+	Alarm,
+	Reminder,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WlxAlarm {
+	pub id: u32,
+	pub kind: WlxAlarmKind,
+	pub label: String,
+	pub due: i64,              // unix timestamp, in seconds
+	pub interval: Option<u32>, // seconds between repeats of a reminder
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WlxAlarmList {
+	pub list: Vec<WlxAlarm>,
+}
+
 // "Wvr" prefixes are WayVR-specific
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -105,6 +126,9 @@ pub enum PacketServer {
 	WvrProcessLaunchResponse(Serial, Result<WvrProcessHandle, String>),
 	WvrProcessListResponse(Serial, WvrProcessList),
 	WvrStateChanged(WvrStateChanged),
+	WlxPanelGetValueResponse(Serial, Result<String, String>),
+	WlxAlarmAddResponse(Serial, Result<u32, String>),
+	WlxAlarmListResponse(Serial, WlxAlarmList),
 }
 
 impl PacketServer {
@@ -118,6 +142,9 @@ impl PacketServer {
 			PacketServer::WvrProcessLaunchResponse(serial, _) => Some(serial),
 			PacketServer::WvrProcessListResponse(serial, _) => Some(serial),
 			PacketServer::WvrStateChanged(_) => None,
+			PacketServer::WlxPanelGetValueResponse(serial, _) => Some(serial),
+			PacketServer::WlxAlarmAddResponse(serial, _) => Some(serial),
+			PacketServer::WlxAlarmListResponse(serial, _) => Some(serial),
 		}
 	}
 }
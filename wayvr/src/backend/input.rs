@@ -13,14 +13,13 @@ use wlx_common::windowing::{OverlayWindowState, Positioning};
 
 use crate::backend::task::{InputTask, OverlayTask};
 use crate::overlays::anchor::{ANCHOR_NAME, GRAB_HELP_NAME};
-use crate::overlays::keyboard::KEYBOARD_NAME;
-use crate::overlays::watch::WATCH_NAME;
+use crate::overlays::watch::{self, WATCH_NAME};
 use crate::state::{AppSession, AppState};
 use crate::subsystem::hid::WheelDelta;
-use crate::subsystem::input::KeyboardFocus;
+use crate::subsystem::input::{HidWrapper, KeyboardFocus};
 use crate::windowing::backend::OverlayEventData;
 use crate::windowing::manager::OverlayWindowManager;
-use crate::windowing::window::{self, OverlayWindowData, realign};
+use crate::windowing::window::{self, OverlayCategory, OverlayWindowData, realign};
 use crate::windowing::{OverlayID, OverlaySelector};
 
 use super::task::TaskType;
@@ -36,6 +35,30 @@ pub struct TrackedDevice {
     pub soc: Option<f32>,
     pub charging: bool,
     pub role: TrackedDeviceRole,
+    /// Model name as reported by the runtime, may be empty
+    pub name: String,
+    /// May be empty
+    pub serial: String,
+}
+
+impl TrackedDevice {
+    /// Tells devices apart across device list refreshes.
+    pub fn key(&self) -> String {
+        if self.serial.is_empty() {
+            format!("{}/{}", self.role.as_ref(), self.name)
+        } else {
+            self.serial.clone()
+        }
+    }
+
+    /// The model name, or the role if there is none.
+    pub fn display_name(&self) -> &str {
+        if self.name.is_empty() {
+            self.role.as_ref()
+        } else {
+            &self.name
+        }
+    }
 }
 
 #[repr(u8)]
@@ -368,12 +391,22 @@ fn populate_lines(
     }
 }
 
-fn update_focus(focus: &mut KeyboardFocus, overlay_keyboard_focus: Option<KeyboardFocus>) {
-    if let Some(f) = &overlay_keyboard_focus
-        && *focus != *f
-    {
-        log::debug!("Setting keyboard focus to {:?}", *f);
-        *focus = *f;
+fn update_focus(
+    hid: &mut HidWrapper,
+    overlay_id: OverlayID,
+    overlay_keyboard_focus: Option<KeyboardFocus>,
+) {
+    let Some(f) = overlay_keyboard_focus else {
+        return;
+    };
+
+    if f == KeyboardFocus::Panel {
+        hid.panel_focus = Some(overlay_id);
+    }
+
+    if hid.keyboard_focus != f {
+        log::debug!("Setting keyboard focus to {f:?}");
+        hid.keyboard_focus = f;
     }
 }
 
@@ -407,6 +440,9 @@ where
     [hits[0].1, hits[1].1]
 }
 
+/// Meters from the tip of a controller at which it presses what it touches
+const TOUCH_DISTANCE: f32 = 0.02;
+
 fn interact_hand<O>(
     idx: usize,
     overlays: &mut OverlayWindowManager<O>,
@@ -483,7 +519,7 @@ where
 
         pointer.interaction.should_block_poses = state.block_input
             && app.session.config.block_poses_on_kbd_interaction
-            && hovered.config.name.as_ref() == KEYBOARD_NAME;
+            && hovered.config.category == OverlayCategory::Keyboard;
     } else {
         pointer.interaction.should_block_input = false;
         pointer.interaction.should_block_poses = false;
@@ -497,7 +533,8 @@ where
     // grab
     if pointer.now.grab && !pointer.before.grab && hovered_state.grabbable {
         update_focus(
-            &mut app.hid_provider.keyboard_focus,
+            &mut app.hid_provider,
+            hit.overlay,
             hovered.config.keyboard_focus,
         );
         start_grab(
@@ -524,10 +561,18 @@ where
 
     // click / release
     let pointer = &mut app.input_state.pointers[hit.pointer];
+    if hit.dist < TOUCH_DISTANCE
+        && hovered.config.category == OverlayCategory::Keyboard
+        && matches!(hovered_state.positioning, Positioning::FollowHand { .. })
+    {
+        // keys of a split keyboard can be pressed by touching them
+        pointer.now.click = true;
+    }
     if pointer.now.click && !pointer.before.click {
         pointer.interaction.clicked_id = Some(hit.overlay);
         update_focus(
-            &mut app.hid_provider.keyboard_focus,
+            &mut app.hid_provider,
+            hit.overlay,
             hovered.config.keyboard_focus,
         );
         hovered.config.backend.on_pointer(app, &hit, true);
@@ -651,6 +696,7 @@ where
     let pointer = &mut app.input_state.pointers[pointer_idx];
     let ray_origin = pointer.pose;
     let mode = pointer.interaction.mode;
+    let hand_of_pointer = pointer.hand();
     let edit_mode = overlays.get_edit_mode();
 
     let mut hits: SmallVec<[RayHit; 8]> = smallvec!();
@@ -662,6 +708,13 @@ where
         if !overlay_state.interactable && !edit_mode {
             continue;
         }
+        // the half of a split keyboard on this hand is typed on by the other one
+        if overlay.config.category == OverlayCategory::Keyboard
+            && let Positioning::FollowHand { hand, .. } = overlay_state.positioning
+            && Some(hand) == hand_of_pointer
+        {
+            continue;
+        }
 
         if let Some(hit) = ray_test(
             &ray_origin,
@@ -863,33 +916,19 @@ where
         } else {
             // single grab released
             if &*overlay.config.name == WATCH_NAME {
-                // watch special: when dropped, follow the hand that wasn't grabbing
-                if let Some(overlay_state) = overlay.config.active_state.as_mut() {
-                    overlay_state.positioning = match overlay_state.positioning {
-                        Positioning::FollowHand {
-                            hand,
-                            lerp,
-                            align_to_hmd,
-                        } => match pointer.hand() {
-                            Some(LeftRight::Left) => Positioning::FollowHand {
-                                hand: LeftRight::Right,
-                                lerp,
-                                align_to_hmd,
-                            },
-                            Some(LeftRight::Right) => Positioning::FollowHand {
-                                hand: LeftRight::Left,
-                                lerp,
-                                align_to_hmd,
-                            },
-                            _ => Positioning::FollowHand {
-                                hand,
-                                lerp,
-                                align_to_hmd,
-                            },
-                        },
-                        x => x,
-                    };
+                // watch special: when dropped, follow the hand that wasn't grabbing.
+                // the hand is remembered; the placement is kept if it stays on the same hand.
+                let hand = pointer.hand().map(|grabbing| match grabbing {
+                    LeftRight::Left => LeftRight::Right,
+                    LeftRight::Right => LeftRight::Left,
+                });
+                if let Some(hand) = hand
+                    && hand != app.session.config.watch_hand
+                {
+                    app.session.config.watch_hand = hand;
+                    app.session.config_dirty = true;
                 }
+                watch::apply_watch_placement(app, &mut overlay.config);
             } else if overlay.config.global
                 && let Some(active_state) = overlay.config.active_state.as_ref()
             {
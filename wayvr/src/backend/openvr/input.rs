@@ -350,10 +350,19 @@ fn get_tracked_device(
         return None;
     }
 
+    let name: String = system
+        .get_tracked_device_property(index, ETrackedDeviceProperty::Prop_ModelNumber_String)
+        .unwrap_or_default();
+    let serial: String = system
+        .get_tracked_device_property(index, ETrackedDeviceProperty::Prop_SerialNumber_String)
+        .unwrap_or_default();
+
     Some(TrackedDevice {
         soc,
         charging,
         role,
+        name,
+        serial,
     })
 }
 
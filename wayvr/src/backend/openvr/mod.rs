@@ -32,7 +32,10 @@ use crate::{
     graphics::{GpuFutures, init_openvr_graphics},
     overlays::toast::Toast,
     state::AppState,
-    subsystem::notifications::NotificationManager,
+    subsystem::{
+        alarms, battery, calendar, clipboard, hot_reload::HotReload,
+        notifications::NotificationManager, screenshot,
+    },
     windowing::{
         backend::{RenderResources, RenderTarget, ShouldRender},
         manager::OverlayWindowManager,
@@ -109,9 +112,14 @@ pub fn openvr_run(show_by_default: bool, headless: bool) -> Result<(), BackendEr
     let _ = install_manifest(&mut app_mgr);
 
     let mut overlays = OverlayWindowManager::<OpenVrOverlayData>::new(&mut app, headless)?;
-    let mut notifications = NotificationManager::new();
-    notifications.run_dbus(&mut app.dbus);
+    let mut notifications = NotificationManager::new(&app.session.config);
+    notifications.run_dbus(&mut app.dbus, app.session.config.notifications_server);
     notifications.run_udp();
+    notifications.run_ovrt();
+
+    let mut hot_reload = HotReload::new(&app.session.config)
+        .inspect_err(|e| log::warn!("Will not hot-reload config files: {e:?}"))
+        .ok();
 
     let mut playspace = playspace::PlayspaceMover::new();
     playspace.playspace_changed(&mut compositor_mgr, &mut chaperone_mgr);
@@ -202,11 +210,19 @@ pub fn openvr_run(show_by_default: bool, headless: bool) -> Result<(), BackendEr
             if changed {
                 overlays.devices_changed(&mut app)?;
             }
+            battery::check(&mut app);
             next_device_update = Instant::now() + Duration::from_secs(30);
         }
 
         app.dbus.tick();
         notifications.submit_pending(&mut app);
+        if let Some(hot_reload) = hot_reload.as_mut() {
+            hot_reload.poll(&mut app);
+        }
+        alarms::tick(&mut app);
+        calendar::tick(&mut app);
+        clipboard::tick(&mut app);
+        screenshot::tick(&mut app);
 
         app.tasks.retrieve_due(&mut due_tasks);
 
@@ -260,6 +260,8 @@ impl OpenXrInputSource {
                 soc: Some(status.charge),
                 charging: status.charging,
                 role,
+                name: device.name.clone(),
+                serial: device.serial().unwrap_or_default(),
             });
             log::debug!(
                 "Device {} role {:#?}: {:.0}% (charging {})",
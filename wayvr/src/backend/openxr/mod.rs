@@ -24,7 +24,10 @@ use crate::{
     graphics::{GpuFutures, init_openxr_graphics},
     overlays::{toast::Toast, watch::WATCH_NAME},
     state::AppState,
-    subsystem::notifications::NotificationManager,
+    subsystem::{
+        alarms, battery, calendar, clipboard, hot_reload::HotReload,
+        notifications::NotificationManager, screenshot,
+    },
     windowing::{
         backend::{RenderResources, RenderTarget, ShouldRender},
         manager::OverlayWindowManager,
@@ -79,9 +82,14 @@ pub fn openxr_run(show_by_default: bool, headless: bool) -> Result<(), BackendEr
     let mut lines = LinePool::new(&app)?;
     let mut current_lines = Vec::with_capacity(2);
 
-    let mut notifications = NotificationManager::new();
-    notifications.run_dbus(&mut app.dbus);
+    let mut notifications = NotificationManager::new(&app.session.config);
+    notifications.run_dbus(&mut app.dbus, app.session.config.notifications_server);
     notifications.run_udp();
+    notifications.run_ovrt();
+
+    let mut hot_reload = HotReload::new(&app.session.config)
+        .inspect_err(|e| log::warn!("Will not hot-reload config files: {e:?}"))
+        .ok();
 
     let mut delete_queue = vec![];
 
@@ -216,6 +224,7 @@ pub fn openxr_run(show_by_default: bool, headless: bool) -> Result<(), BackendEr
             if changed {
                 overlays.devices_changed(&mut app)?;
             }
+            battery::check(&mut app);
             next_device_update = Instant::now() + Duration::from_secs(30);
         }
 
@@ -456,6 +465,13 @@ pub fn openxr_run(show_by_default: bool, headless: bool) -> Result<(), BackendEr
 
         app.dbus.tick();
         notifications.submit_pending(&mut app);
+        if let Some(hot_reload) = hot_reload.as_mut() {
+            hot_reload.poll(&mut app);
+        }
+        alarms::tick(&mut app);
+        calendar::tick(&mut app);
+        clipboard::tick(&mut app);
+        screenshot::tick(&mut app);
 
         app.tasks.retrieve_due(&mut due_tasks);
         while let Some(task) = due_tasks.pop_front() {
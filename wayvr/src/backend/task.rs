@@ -1,7 +1,11 @@
 use std::{
     cmp,
     collections::{BinaryHeap, VecDeque},
-    sync::atomic::{self, AtomicUsize},
+    sync::{
+        Arc,
+        atomic::{self, AtomicUsize},
+        mpsc,
+    },
     time::Instant,
 };
 
@@ -64,6 +68,9 @@ pub enum PlayspaceTask {
     FixFloor,
 }
 
+/// Receives the value of a panel element, or an error message.
+pub type PanelValueReply = mpsc::Sender<Result<String, String>>;
+
 #[derive(Debug, Clone)]
 #[allow(clippy::enum_variant_names)]
 pub enum ModifyPanelCommand {
@@ -71,7 +78,7 @@ pub enum ModifyPanelCommand {
     SetColor(String),
     SetImage(String),
     SetVisible(bool),
-    GetValue,
+    GetValue(PanelValueReply),
     SetValue(String),
     SetStickyState(bool),
 }
@@ -83,6 +90,14 @@ pub struct ModifyPanelTask {
     pub command: ModifyPanelCommand,
 }
 
+/// Panels to rebuild after their files changed on disk.
+pub enum PanelReload {
+    /// Panels built from `gui/{name}.xml`
+    Named(Vec<Arc<str>>),
+    /// Watch, keyboard and all custom panels, e.g. after the theme colors changed
+    All,
+}
+
 pub enum ToggleMode {
     EnsureOn,
     EnsureOff,
@@ -107,7 +122,10 @@ pub enum OverlayTask {
     Modify(OverlaySelector, Box<ModifyOverlayTask>),
     Create(OverlaySelector, Box<CreateOverlayTask>),
     ModifyPanel(ModifyPanelTask),
+    ReloadPanels(PanelReload),
     Drop(OverlaySelector),
+    /// Saves the overlay as PNG, or all visible overlays side by side if `None`
+    Screenshot(Option<OverlaySelector>),
 }
 
 pub enum TaskType {
@@ -209,6 +209,13 @@ impl WayVRCompositor {
             )
             .context("Failed to set keymap")
     }
+
+    pub fn set_repeat_info(&mut self, delay_ms: u32, rate: u32) {
+        self.seat_keyboard.change_repeat_info(
+            i32::try_from(rate).unwrap_or(i32::MAX),
+            i32::try_from(delay_ms).unwrap_or(i32::MAX),
+        );
+    }
 }
 
 const STARTING_WAYLAND_ADDR_IDX: u32 = 20;
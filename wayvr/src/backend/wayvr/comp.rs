@@ -42,7 +42,7 @@ use smithay::wayland::compositor::{self, BufferAssignment, SurfaceAttributes, se
 
 use smithay::wayland::selection::data_device::{
     ClientDndGrabHandler, DataDeviceHandler, DataDeviceState, ServerDndGrabHandler,
-    set_data_device_focus,
+    request_data_device_client_selection, set_data_device_focus,
 };
 use smithay::wayland::selection::{self, SelectionHandler};
 use smithay::wayland::shell::xdg::{
@@ -55,6 +55,9 @@ use wayland_server::protocol::wl_surface::WlSurface;
 use crate::backend::wayvr::image_importer::ImageImporter;
 use crate::backend::wayvr::{SurfaceBufWithImage, time};
 use crate::ipc::event_queue::SyncEventQueue;
+use crate::subsystem::clipboard::{
+    IMAGE_MIME_TYPE, PASSWORD_HINT_MIME_TYPE, SelectionReader, TEXT_MIME_TYPES,
+};
 
 use super::WayVRTask;
 
@@ -64,6 +67,7 @@ pub struct Application {
     pub compositor: compositor::CompositorState,
     pub xdg_shell: XdgShellState,
     pub seat_state: SeatState<Application>,
+    pub seat: Seat<Application>,
     pub shm: ShmState,
     pub data_device: DataDeviceState,
     pub primary_selection_state: PrimarySelectionState,
@@ -75,6 +79,7 @@ pub struct Application {
     pub redraw_requests: HashSet<wayland_server::backend::ObjectId>,
     pub popup_manager: PopupManager,
     pub display_handle: DisplayHandle,
+    pub clipboard_reader: SelectionReader,
 }
 
 impl Application {
@@ -282,10 +287,41 @@ impl SelectionHandler for Application {
 
     fn new_selection(
         &mut self,
-        _ty: selection::SelectionTarget,
-        _source: Option<selection::SelectionSource>,
-        _seat: Seat<Self>,
+        ty: selection::SelectionTarget,
+        source: Option<selection::SelectionSource>,
+        seat: Seat<Self>,
     ) {
+        // read what apps copy, for the clipboard history
+        let (selection::SelectionTarget::Clipboard, Some(source)) = (ty, source) else {
+            return;
+        };
+
+        let offered = source.mime_types();
+        if offered.iter().any(|m| m == PASSWORD_HINT_MIME_TYPE) {
+            return;
+        }
+        let Some(mime_type) = TEXT_MIME_TYPES
+            .into_iter()
+            .chain([IMAGE_MIME_TYPE])
+            .find(|m| offered.iter().any(|o| o == m))
+        else {
+            return;
+        };
+
+        let (reader, writer) = match std::io::pipe() {
+            Ok(pipe) => pipe,
+            Err(err) => {
+                log::warn!("error creating pipe for selection: {err:?}");
+                return;
+            }
+        };
+        if let Err(err) =
+            request_data_device_client_selection(&seat, mime_type.to_string(), writer.into())
+        {
+            log::warn!("error requesting selection: {err:?}");
+            return;
+        }
+        self.clipboard_reader.read(reader, mime_type.to_string());
     }
 }
 
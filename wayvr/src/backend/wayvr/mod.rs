@@ -23,8 +23,10 @@ use smithay::{
         compositor::{self, SurfaceData, with_states},
         dmabuf::{DmabufFeedbackBuilder, DmabufState},
         selection::{
-            data_device::DataDeviceState, ext_data_control as selection_ext,
-            primary_selection::PrimarySelectionState, wlr_data_control as selection_wlr,
+            data_device::{DataDeviceState, set_data_device_selection},
+            ext_data_control as selection_ext,
+            primary_selection::PrimarySelectionState,
+            wlr_data_control as selection_wlr,
         },
         shell::{
             kde::decoration::KdeDecorationState,
@@ -48,7 +50,7 @@ use vulkano::image::view::ImageView;
 use wayvr_ipc::{packet_client::PositionMode, packet_server};
 use wgui::gfx::WGfx;
 use wlx_capture::frame::Transform;
-use wlx_common::desktop_finder::DesktopFinder;
+use wlx_common::{config::GeneralConfig, desktop_finder::DesktopFinder};
 use xkbcommon::xkb;
 
 use crate::{
@@ -64,7 +66,10 @@ use crate::{
     ipc::{event_queue::SyncEventQueue, ipc_server, signal::WayVRSignal},
     overlays::wayvr::create_wl_window_overlay,
     state::AppState,
-    subsystem::hid::{MODS_TO_KEYS, WheelDelta},
+    subsystem::{
+        clipboard::{ClipboardContent, IMAGE_MIME_TYPE, SelectionReader, TEXT_MIME_TYPES},
+        hid::{MODS_TO_KEYS, WheelDelta},
+    },
     windowing::{OverlayID, OverlaySelector},
 };
 
@@ -144,14 +149,13 @@ pub enum TickTask {
     NewExternalProcess(ExternalProcessRequest), // Call WayVRCompositor::add_client after receiving this message
 }
 
-const KEY_REPEAT_DELAY: i32 = 200;
-const KEY_REPEAT_RATE: i32 = 50;
-
 impl WvrServerState {
     pub fn new(
         gfx: Arc<WGfx>,
         gfx_extras: &WGfxExtras,
         signals: SyncEventQueue<WayVRSignal>,
+        clipboard_reader: SelectionReader,
+        config: &GeneralConfig,
     ) -> anyhow::Result<Self> {
         log::info!("Initializing WayVR server");
         let display: wayland_server::Display<Application> = wayland_server::Display::new()?;
@@ -236,8 +240,12 @@ impl WvrServerState {
                 },
             );
 
-        let seat_keyboard =
-            seat.add_keyboard(XkbConfig::default(), KEY_REPEAT_DELAY, KEY_REPEAT_RATE)?;
+        // clients repeat held keys on their own, as told by wl_keyboard.repeat_info
+        let seat_keyboard = seat.add_keyboard(
+            XkbConfig::default(),
+            i32::try_from(config.keyboard_repeat_delay_ms).unwrap_or(i32::MAX),
+            i32::try_from(config.keyboard_repeat_rate).unwrap_or(i32::MAX),
+        )?;
         let seat_pointer = seat.add_pointer();
 
         let tasks = SyncEventQueue::new();
@@ -250,6 +258,7 @@ impl WvrServerState {
             compositor,
             xdg_shell,
             seat_state,
+            seat,
             shm,
             data_device,
             primary_selection_state,
@@ -261,6 +270,7 @@ impl WvrServerState {
             redraw_requests: HashSet::new(),
             dmabuf_state,
             popup_manager: PopupManager::default(),
+            clipboard_reader,
         };
 
         let time_start = get_millis();
@@ -291,6 +301,7 @@ impl WvrServerState {
         app.ipc_server.tick(&mut ipc_server::TickParams {
             wvr_server,
             input_state: &app.input_state,
+            alarms: &mut app.alarms,
             tasks: &mut tasks,
             signals: &app.wayvr_signals,
         });
@@ -640,6 +651,10 @@ impl WvrServerState {
         self.manager.set_keymap(keymap)
     }
 
+    pub fn set_repeat_info(&mut self, delay_ms: u32, rate: u32) {
+        self.manager.set_repeat_info(delay_ms, rate);
+    }
+
     pub fn set_modifiers(&mut self, modifiers: u8) {
         let changed = self.cur_modifiers ^ modifiers;
         for i in 0..8 {
@@ -653,6 +668,23 @@ impl WvrServerState {
         self.cur_modifiers = modifiers;
     }
 
+    /// Offers the content to WayVR apps, as if one of them had copied it.
+    pub fn set_clipboard(&mut self, content: &ClipboardContent) -> anyhow::Result<()> {
+        let (mime_types, data): (Vec<String>, Arc<[u8]>) = match content {
+            ClipboardContent::Text(text) => (
+                TEXT_MIME_TYPES.iter().map(|m| (*m).to_string()).collect(),
+                text.as_bytes().into(),
+            ),
+            ClipboardContent::Image(image) => {
+                (vec![IMAGE_MIME_TYPE.to_string()], image.to_png()?.into())
+            }
+        };
+
+        let state = &self.manager.state;
+        set_data_device_selection(&state.display_handle, &state.seat, mime_types, data);
+        Ok(())
+    }
+
     // Check if process with given arguments already exists
     pub fn process_query(
         &self,
@@ -1,10 +1,12 @@
-use config::{Config, File};
+use anyhow::Context;
+use config::{Config, File, Value};
 use log::error;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use wayvr_ipc::packet_client::WvrProcessLaunchParams;
 use wlx_common::{
     astr_containers::AStrMap,
+    common::LeftRight,
     config::{
         AltModifier, CaptureMethod, GeneralConfig, HandsfreePointer, SerializedWindowSet,
         SerializedWindowStates,
@@ -28,6 +30,18 @@ pub enum ConfigType {
 }
 
 pub fn load_known_yaml<T>(config_type: ConfigType) -> T
+where
+    T: for<'de> Deserialize<'de>,
+{
+    load_checked_yaml(config_type, |_| Ok(()))
+}
+
+/// Like `load_known_yaml`, but also falls back to defaults if `check` fails.
+/// `check` may fill in anything derived from the file.
+pub fn load_checked_yaml<T>(
+    config_type: ConfigType,
+    check: impl Fn(&mut T) -> anyhow::Result<()>,
+) -> T
 where
     T: for<'de> Deserialize<'de>,
 {
@@ -37,7 +51,13 @@ where
 
     for yaml in [maybe_override.as_deref(), Some(fallback)].iter().flatten() {
         match serde_yaml::from_str::<T>(yaml) {
-            Ok(d) => return d,
+            Ok(mut d) => match check(&mut d) {
+                Ok(()) => return d,
+                Err(e) => {
+                    error!("Invalid {file_name}, falling back to defaults.");
+                    error!("{e:#}");
+                }
+            },
             Err(e) => {
                 error!("Failed to parse {file_name}, falling back to defaults.");
                 error!("{e}");
@@ -48,13 +68,11 @@ where
     panic!("No usable config found.");
 }
 
-pub fn load_config_with_conf_d<ConfigData>(
+/// The root config file with everything in conf.d layered on top, before deserializing.
+fn merge_config_with_conf_d(
     root_config_filename: &str,
     ctype: config_io::ConfigRoot,
-) -> ConfigData
-where
-    ConfigData: for<'de> Deserialize<'de>,
-{
+) -> anyhow::Result<Config> {
     let mut settings_builder = Config::builder();
 
     // Add files from conf.d directory
@@ -86,21 +104,52 @@ where
         }
     }
 
-    match settings_builder.build() {
-        Ok(settings) => match settings.try_deserialize::<ConfigData>() {
-            Ok(config) => config,
-            Err(e) => {
-                panic!("Failed to deserialize settings: {e}");
-            }
-        },
-        Err(e) => {
-            panic!("Failed to build settings: {e}");
-        }
-    }
+    settings_builder.build().context("Failed to build settings")
 }
 
 pub fn load_general_config() -> GeneralConfig {
-    load_config_with_conf_d::<GeneralConfig>("config.yaml", config_io::ConfigRoot::Generic)
+    try_load_general_config().unwrap_or_else(|e| panic!("{e:#}"))
+}
+
+/// Like `load_general_config`, but returns an error instead of panicking on invalid files.
+pub fn try_load_general_config() -> anyhow::Result<GeneralConfig> {
+    let merged = merge_config_with_conf_d("config.yaml", config_io::ConfigRoot::Generic)?;
+    let config = merged
+        .clone()
+        .try_deserialize::<GeneralConfig>()
+        .context("Failed to deserialize settings")?;
+    Ok(post_load(config, &merged))
+}
+
+/// Fixes up what serde attributes alone can't.
+/// `merged` is what `config` was deserialized from.
+fn post_load(mut config: GeneralConfig, merged: &Config) -> GeneralConfig {
+    // the old keys predate per-hand angles, they're meant for whichever hand has the watch.
+    // once a per-hand angle is set (e.g. saved from the dashboard), it wins over the old key.
+    let (hand, min, max) = match config.watch_hand {
+        LeftRight::Left => (
+            "left",
+            &mut config.watch_view_angle_min_left,
+            &mut config.watch_view_angle_max_left,
+        ),
+        LeftRight::Right => (
+            "right",
+            &mut config.watch_view_angle_min_right,
+            &mut config.watch_view_angle_max_right,
+        ),
+    };
+    let is_set = |key: &str| merged.get::<Value>(&format!("{key}_{hand}")).is_ok();
+    if let Some(angle) = config.watch_view_angle_min.take()
+        && !is_set("watch_view_angle_min")
+    {
+        *min = angle;
+    }
+    if let Some(angle) = config.watch_view_angle_max.take()
+        && !is_set("watch_view_angle_max")
+    {
+        *max = angle;
+    }
+    config
 }
 
 #[derive(Serialize)]
@@ -115,7 +164,15 @@ pub struct AutoSettings {
     pub long_press_duration: f32,
     pub notifications_enabled: bool,
     pub notifications_sound_enabled: bool,
+    pub notifications_server: bool,
+    pub notifications_dnd: bool,
     pub keyboard_sound_enabled: bool,
+    pub keyboard_suggestions: bool,
+    pub keyboard_learn_words: bool,
+    pub keyboard_autocorrect: bool,
+    pub keyboard_repeat_delay_ms: u32,
+    pub keyboard_repeat_rate: u32,
+    pub keyboard_split: bool,
     pub upright_screen_fix: bool,
     pub double_cursor_fix: bool,
     pub sets_on_watch: bool,
@@ -136,6 +193,13 @@ pub struct AutoSettings {
     pub space_drag_unlocked: bool,
     pub space_rotate_unlocked: bool,
     pub clock_12h: bool,
+    pub watch_hand: LeftRight,
+    pub watch_offset: [f32; 3],
+    pub watch_rotation: [f32; 3],
+    pub watch_view_angle_min_left: f32,
+    pub watch_view_angle_max_left: f32,
+    pub watch_view_angle_min_right: f32,
+    pub watch_view_angle_max_right: f32,
     pub hide_username: bool,
     pub opaque_background: bool,
     pub xwayland_by_default: bool,
@@ -165,7 +229,15 @@ pub fn save_settings(config: &GeneralConfig) -> anyhow::Result<()> {
         long_press_duration: config.long_press_duration,
         notifications_enabled: config.notifications_enabled,
         notifications_sound_enabled: config.notifications_sound_enabled,
+        notifications_server: config.notifications_server,
+        notifications_dnd: config.notifications_dnd,
         keyboard_sound_enabled: config.keyboard_sound_enabled,
+        keyboard_suggestions: config.keyboard_suggestions,
+        keyboard_learn_words: config.keyboard_learn_words,
+        keyboard_autocorrect: config.keyboard_autocorrect,
+        keyboard_repeat_delay_ms: config.keyboard_repeat_delay_ms,
+        keyboard_repeat_rate: config.keyboard_repeat_rate,
+        keyboard_split: config.keyboard_split,
         upright_screen_fix: config.upright_screen_fix,
         double_cursor_fix: config.double_cursor_fix,
         sets_on_watch: config.sets_on_watch,
@@ -186,6 +258,13 @@ pub fn save_settings(config: &GeneralConfig) -> anyhow::Result<()> {
         space_drag_unlocked: config.space_drag_unlocked,
         space_rotate_unlocked: config.space_rotate_unlocked,
         clock_12h: config.clock_12h,
+        watch_hand: config.watch_hand,
+        watch_offset: config.watch_offset,
+        watch_rotation: config.watch_rotation,
+        watch_view_angle_min_left: config.watch_view_angle_min_left,
+        watch_view_angle_max_left: config.watch_view_angle_max_left,
+        watch_view_angle_min_right: config.watch_view_angle_min_right,
+        watch_view_angle_max_right: config.watch_view_angle_max_right,
         hide_username: config.hide_username,
         opaque_background: config.opaque_background,
         xwayland_by_default: config.xwayland_by_default,
@@ -9,8 +9,9 @@ use std::{
 };
 
 use anyhow::Context;
+use chrono::NaiveTime;
 use wgui::{
-    components::button::ComponentButton,
+    components::{button::ComponentButton, editbox::ComponentEditBox},
     event::{
         CallbackData, CallbackMetadata, EventCallback, EventListenerKind, MouseButtonIndex,
         StyleSetRequest,
@@ -30,10 +31,13 @@ use crate::{
         task::{OverlayTask, PlayspaceTask, TaskType, ToggleMode},
         wayvr::process::KillSignal,
     },
-    gui::panel::{log_cmd_invalid_arg, log_cmd_missing_arg},
-    overlays::{custom::create_custom, toast::Toast, wayvr::WvrCommand},
+    gui::panel::{log_cmd_invalid_arg, log_cmd_missing_arg, vars::PanelVarsRef},
+    overlays::{
+        custom::create_custom, notifications::notify_history_changed, toast::Toast,
+        wayvr::WvrCommand,
+    },
     state::AppState,
-    subsystem::hid::VirtualKey,
+    subsystem::{clipboard, dbus::mpris::MediaCommand, hid::VirtualKey},
     windowing::{OverlaySelector, backend::OverlayEventData, window::OverlayCategory},
 };
 
@@ -193,6 +197,7 @@ pub(super) fn setup_custom_button<S: 'static>(
     attribs: &CustomAttribsInfoOwned,
     context_menu: &Rc<RefCell<ContextMenu>>,
     on_custom_attribs: &parser::OnCustomAttribsFunc,
+    vars: &PanelVarsRef,
     button: Rc<ComponentButton>,
 ) {
     const TAG: &str = "Button";
@@ -298,6 +303,64 @@ pub(super) fn setup_custom_button<S: 'static>(
                         Ok(EventResult::Consumed)
                     })
                 }
+                "::VarSet" => {
+                    let Some(var) = args.next() else {
+                        log_cmd_missing_arg(parser_state, TAG, name, command);
+                        return;
+                    };
+                    let var: Rc<str> = var.into();
+                    // empty if omitted
+                    let value: Rc<str> = args.collect::<Vec<_>>().join(" ").into();
+                    let vars = vars.clone();
+
+                    Box::new(move |_common, data, app, _| {
+                        if !test_button(data) || !test_duration(&button, app) {
+                            return Ok(EventResult::Pass);
+                        }
+
+                        vars.borrow_mut().set(&var, &value);
+                        Ok(EventResult::Consumed)
+                    })
+                }
+                "::VarToggle" => {
+                    let Some(var) = args.next() else {
+                        log_cmd_missing_arg(parser_state, TAG, name, command);
+                        return;
+                    };
+                    let var: Rc<str> = var.into();
+                    let vars = vars.clone();
+
+                    Box::new(move |_common, data, app, _| {
+                        if !test_button(data) || !test_duration(&button, app) {
+                            return Ok(EventResult::Pass);
+                        }
+
+                        vars.borrow_mut().toggle(&var);
+                        Ok(EventResult::Consumed)
+                    })
+                }
+                "::VarCycle" => {
+                    let Some(var) = args.next() else {
+                        log_cmd_missing_arg(parser_state, TAG, name, command);
+                        return;
+                    };
+                    let var: Rc<str> = var.into();
+                    let values: Vec<Rc<str>> = args.map(Into::into).collect();
+                    if values.is_empty() {
+                        log_cmd_missing_arg(parser_state, TAG, name, command);
+                        return;
+                    }
+                    let vars = vars.clone();
+
+                    Box::new(move |_common, data, app, _| {
+                        if !test_button(data) || !test_duration(&button, app) {
+                            return Ok(EventResult::Pass);
+                        }
+
+                        vars.borrow_mut().cycle(&var, &values);
+                        Ok(EventResult::Consumed)
+                    })
+                }
                 "::DashToggle" => Box::new(move |_common, data, app, _| {
                     if !test_button(data) || !test_duration(&button, app) {
                         return Ok(EventResult::Pass);
@@ -662,6 +725,272 @@ pub(super) fn setup_custom_button<S: 'static>(
                         Ok(EventResult::Consumed)
                     })
                 }
+                "::NotificationAction" => {
+                    let arg = args.next().unwrap_or_default();
+                    let Ok(entry_id) = arg.parse::<usize>() else {
+                        let msg = format!("expected integer, found \"{arg}\"");
+                        log_cmd_invalid_arg(parser_state, TAG, name, command, &msg);
+                        return;
+                    };
+                    let action_key: Arc<str> = args.collect::<Vec<_>>().join(" ").into();
+                    if action_key.len() < 1 {
+                        log_cmd_missing_arg(parser_state, TAG, name, command);
+                        return;
+                    };
+
+                    Box::new(move |_common, data, app, _| {
+                        if !test_button(data) || !test_duration(&button, app) {
+                            return Ok(EventResult::Pass);
+                        }
+
+                        let _ = app
+                            .notification_history
+                            .invoke_action(&app.dbus, entry_id, &action_key)
+                            .log_warn("Could not invoke notification action");
+                        notify_history_changed(app);
+                        Ok(EventResult::Consumed)
+                    })
+                }
+                "::NotificationReply" => {
+                    let arg = args.next().unwrap_or_default();
+                    let Ok(entry_id) = arg.parse::<usize>() else {
+                        let msg = format!("expected integer, found \"{arg}\"");
+                        log_cmd_invalid_arg(parser_state, TAG, name, command, &msg);
+                        return;
+                    };
+                    let Some(editbox_id) = args.next() else {
+                        log_cmd_missing_arg(parser_state, TAG, name, command);
+                        return;
+                    };
+                    let Ok(editbox) =
+                        parser_state.fetch_component_as::<ComponentEditBox>(editbox_id)
+                    else {
+                        let msg = format!("no <EditBox> with ID \"{editbox_id}\"");
+                        log_cmd_invalid_arg(parser_state, TAG, name, command, &msg);
+                        return;
+                    };
+
+                    Box::new(move |common, data, app, _| {
+                        if !test_button(data) || !test_duration(&button, app) {
+                            return Ok(EventResult::Pass);
+                        }
+
+                        let text = editbox.get_text().clone();
+                        if text.is_empty() {
+                            return Ok(EventResult::Consumed);
+                        }
+
+                        if app
+                            .notification_history
+                            .reply(&app.dbus, entry_id, &text)
+                            .log_warn("Could not send notification reply")
+                            .is_ok()
+                        {
+                            editbox.set_text(common, "");
+                            notify_history_changed(app);
+                        }
+                        Ok(EventResult::Consumed)
+                    })
+                }
+                "::NotificationDismiss" => {
+                    let arg = args.next().unwrap_or_default();
+                    let Ok(entry_id) = arg.parse::<usize>() else {
+                        let msg = format!("expected integer, found \"{arg}\"");
+                        log_cmd_invalid_arg(parser_state, TAG, name, command, &msg);
+                        return;
+                    };
+
+                    Box::new(move |_common, data, app, _| {
+                        if !test_button(data) || !test_duration(&button, app) {
+                            return Ok(EventResult::Pass);
+                        }
+
+                        app.notification_history.dismiss(&app.dbus, entry_id);
+                        notify_history_changed(app);
+                        Ok(EventResult::Consumed)
+                    })
+                }
+                "::NotificationClearAll" => Box::new(move |_common, data, app, _| {
+                    if !test_button(data) || !test_duration(&button, app) {
+                        return Ok(EventResult::Pass);
+                    }
+
+                    app.notification_history.clear(&app.dbus);
+                    notify_history_changed(app);
+                    Ok(EventResult::Consumed)
+                }),
+                "::NotificationDndToggle" => Box::new(move |_common, data, app, _| {
+                    if !test_button(data) || !test_duration(&button, app) {
+                        return Ok(EventResult::Pass);
+                    }
+
+                    let config = &mut app.session.config;
+                    config.notifications_dnd = !config.notifications_dnd;
+                    app.session.config_dirty = true;
+
+                    let body = if app.session.config.notifications_dnd {
+                        "TOAST.DND_ENABLED"
+                    } else {
+                        "TOAST.DND_DISABLED"
+                    };
+                    Toast::new(ToastTopic::System, "TOAST.DND".into(), body.into())
+                        .with_timeout(2.)
+                        .submit(app);
+                    Ok(EventResult::Consumed)
+                }),
+                "::ClipboardCopy" | "::ClipboardPaste" => {
+                    let arg = args.next().unwrap_or_default();
+                    let Ok(entry_id) = arg.parse::<usize>() else {
+                        let msg = format!("expected integer, found \"{arg}\"");
+                        log_cmd_invalid_arg(parser_state, TAG, name, command, &msg);
+                        return;
+                    };
+                    let paste = command == "::ClipboardPaste";
+
+                    Box::new(move |_common, data, app, _| {
+                        if !test_button(data) || !test_duration(&button, app) {
+                            return Ok(EventResult::Pass);
+                        }
+
+                        let _ = clipboard::select(app, entry_id, paste)
+                            .log_warn("Could not select clipboard entry");
+                        Ok(EventResult::Consumed)
+                    })
+                }
+                "::ClipboardRemove" => {
+                    let arg = args.next().unwrap_or_default();
+                    let Ok(entry_id) = arg.parse::<usize>() else {
+                        let msg = format!("expected integer, found \"{arg}\"");
+                        log_cmd_invalid_arg(parser_state, TAG, name, command, &msg);
+                        return;
+                    };
+
+                    Box::new(move |_common, data, app, _| {
+                        if !test_button(data) || !test_duration(&button, app) {
+                            return Ok(EventResult::Pass);
+                        }
+
+                        clipboard::remove(app, entry_id);
+                        Ok(EventResult::Consumed)
+                    })
+                }
+                "::ClipboardClear" => Box::new(move |_common, data, app, _| {
+                    if !test_button(data) || !test_duration(&button, app) {
+                        return Ok(EventResult::Pass);
+                    }
+
+                    clipboard::clear(app);
+                    Ok(EventResult::Consumed)
+                }),
+                "::Screenshot" => {
+                    let arg = args.collect::<Vec<_>>().join(" ");
+                    let selector = (!arg.is_empty()).then(|| OverlaySelector::Name(arg.into()));
+
+                    Box::new(move |_common, data, app, _| {
+                        if !test_button(data) || !test_duration(&button, app) {
+                            return Ok(EventResult::Pass);
+                        }
+
+                        app.tasks
+                            .enqueue(TaskType::Overlay(OverlayTask::Screenshot(selector.clone())));
+                        Ok(EventResult::Consumed)
+                    })
+                }
+                "::MediaPlayPause" | "::MediaNext" | "::MediaPrevious" => {
+                    let media_command = match command {
+                        "::MediaPlayPause" => MediaCommand::PlayPause,
+                        "::MediaNext" => MediaCommand::Next,
+                        _ => MediaCommand::Previous,
+                    };
+                    Box::new(move |_common, data, app, _| {
+                        if !test_button(data) || !test_duration(&button, app) {
+                            return Ok(EventResult::Pass);
+                        }
+
+                        app.dbus.mpris().send(media_command);
+                        Ok(EventResult::Consumed)
+                    })
+                }
+                "::MediaSeek" => {
+                    let arg = args.next().unwrap_or_default();
+                    let Ok(seconds) = arg.parse::<f64>() else {
+                        let msg = format!("expected number, found \"{arg}\"");
+                        log_cmd_invalid_arg(parser_state, TAG, name, command, &msg);
+                        return;
+                    };
+                    let offset = (seconds * 1_000_000.) as i64;
+                    Box::new(move |_common, data, app, _| {
+                        if !test_button(data) || !test_duration(&button, app) {
+                            return Ok(EventResult::Pass);
+                        }
+
+                        app.dbus.mpris().send(MediaCommand::Seek(offset));
+                        Ok(EventResult::Consumed)
+                    })
+                }
+                "::AlarmCountdown" | "::AlarmReminder" => {
+                    let arg = args.next().unwrap_or_default();
+                    let secs = match arg.parse::<f32>() {
+                        Ok(minutes) if minutes > 0. => (minutes * 60.).round() as u32,
+                        _ => {
+                            let msg = format!("expected positive number, found \"{arg}\"");
+                            log_cmd_invalid_arg(parser_state, TAG, name, command, &msg);
+                            return;
+                        }
+                    };
+                    let label = args.collect::<Vec<_>>().join(" ");
+                    let reminder = command == "::AlarmReminder";
+
+                    Box::new(move |_common, data, app, _| {
+                        if !test_button(data) || !test_duration(&button, app) {
+                            return Ok(EventResult::Pass);
+                        }
+
+                        if reminder {
+                            app.alarms.add_reminder(secs, label.clone());
+                        } else {
+                            app.alarms.add_countdown(secs, label.clone());
+                        }
+                        Ok(EventResult::Consumed)
+                    })
+                }
+                "::AlarmAt" => {
+                    let arg = args.next().unwrap_or_default();
+                    let Ok(time) = NaiveTime::parse_from_str(arg, "%H:%M") else {
+                        let msg = format!("expected HH:MM, found \"{arg}\"");
+                        log_cmd_invalid_arg(parser_state, TAG, name, command, &msg);
+                        return;
+                    };
+                    let label = args.collect::<Vec<_>>().join(" ");
+
+                    Box::new(move |_common, data, app, _| {
+                        if !test_button(data) || !test_duration(&button, app) {
+                            return Ok(EventResult::Pass);
+                        }
+
+                        let _ = app
+                            .alarms
+                            .add_alarm_at(time, label.clone())
+                            .inspect_err(|e| log::warn!("Could not set alarm: {e:?}"));
+                        Ok(EventResult::Consumed)
+                    })
+                }
+                "::AlarmCancel" => Box::new(move |_common, data, app, _| {
+                    if !test_button(data) || !test_duration(&button, app) {
+                        return Ok(EventResult::Pass);
+                    }
+
+                    app.alarms.remove_next();
+                    Ok(EventResult::Consumed)
+                }),
+                "::AlarmClear" => Box::new(move |_common, data, app, _| {
+                    if !test_button(data) || !test_duration(&button, app) {
+                        return Ok(EventResult::Pass);
+                    }
+
+                    app.alarms.clear();
+                    Ok(EventResult::Consumed)
+                }),
                 "::ShellExec" => {
                     let state = Rc::new(ShellButtonState {
                         button: button.clone(),
@@ -0,0 +1,368 @@
+use std::{
+    cell::{Cell, RefCell},
+    process::{Child, Command, Stdio},
+    rc::Rc,
+};
+
+use anyhow::Context;
+use wgui::{
+    components::{
+        checkbox::ComponentCheckbox, editbox::ComponentEditBox, radio_group::ComponentRadioGroup,
+        slider::ComponentSlider,
+    },
+    event::{CallbackDataCommon, EventAlterables, EventListenerKind},
+    layout::Layout,
+    parser::{CustomAttribsInfoOwned, ParserState},
+    widget::EventResult,
+};
+use wlx_common::config::GeneralConfig;
+
+use crate::{
+    backend::task::{OverlayTask, TaskType},
+    gui::panel::{log_cmd_invalid_arg, log_cmd_missing_arg, log_invalid_attrib},
+    state::AppState,
+};
+
+/// Input components that can be bound to a command with `_on_change`.
+#[derive(Clone)]
+pub(super) enum CustomControl {
+    Slider(Rc<ComponentSlider>),
+    Checkbox(Rc<ComponentCheckbox>),
+    EditBox(Rc<ComponentEditBox>),
+    RadioGroup(Rc<ComponentRadioGroup>),
+}
+
+impl CustomControl {
+    pub(super) fn fetch(
+        parser_state: &ParserState,
+        attribs: &CustomAttribsInfoOwned,
+    ) -> Option<Self> {
+        let id = attribs.widget_id;
+        if let Ok(c) = parser_state.fetch_component_from_widget_id_as::<ComponentSlider>(id) {
+            Some(Self::Slider(c))
+        } else if let Ok(c) =
+            parser_state.fetch_component_from_widget_id_as::<ComponentCheckbox>(id)
+        {
+            Some(Self::Checkbox(c))
+        } else if let Ok(c) = parser_state.fetch_component_from_widget_id_as::<ComponentEditBox>(id)
+        {
+            Some(Self::EditBox(c))
+        } else if let Ok(c) =
+            parser_state.fetch_component_from_widget_id_as::<ComponentRadioGroup>(id)
+        {
+            Some(Self::RadioGroup(c))
+        } else {
+            None
+        }
+    }
+
+    const fn tag(&self) -> &'static str {
+        match self {
+            Self::Slider(_) => "Slider",
+            Self::Checkbox(_) => "CheckBox",
+            Self::EditBox(_) => "EditBox",
+            Self::RadioGroup(_) => "RadioGroup",
+        }
+    }
+
+    fn set_value(
+        &self,
+        common: &mut CallbackDataCommon,
+        value: &ControlValue,
+    ) -> anyhow::Result<()> {
+        match (self, value) {
+            (Self::Slider(c), ControlValue::Number(n)) => c.set_value(common, *n),
+            (Self::Checkbox(c), ControlValue::Bool(b)) => c.set_checked(common, *b),
+            (Self::EditBox(c), ControlValue::Text(t)) => c.set_text(common, t),
+            (Self::RadioGroup(c), ControlValue::Text(t)) => c.set_value(common, t)?,
+            _ => anyhow::bail!("value {value:?} does not fit this control"),
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum ControlValue {
+    Bool(bool),
+    Number(f32),
+    Text(String),
+}
+
+impl ControlValue {
+    fn to_json(&self, current: &serde_json::Value) -> serde_json::Value {
+        match self {
+            Self::Bool(b) => serde_json::Value::Bool(*b),
+            // integer config fields don't accept floats
+            Self::Number(n) if current.is_i64() || current.is_u64() => {
+                serde_json::Value::from(n.round() as i64)
+            }
+            Self::Number(n) => serde_json::Value::from(f64::from(*n)),
+            Self::Text(t) => serde_json::Value::String(t.clone()),
+        }
+    }
+
+    fn from_json(value: &serde_json::Value) -> Option<Self> {
+        match value {
+            serde_json::Value::Bool(b) => Some(Self::Bool(*b)),
+            serde_json::Value::Number(n) => n.as_f64().map(|n| Self::Number(n as f32)),
+            serde_json::Value::String(s) => Some(Self::Text(s.clone())),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ControlValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bool(b) => write!(f, "{}", u8::from(*b)),
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Text(t) => write!(f, "{t}"),
+        }
+    }
+}
+
+enum ControlAction {
+    /// The value is passed to the script as `$WAYVR_VALUE`
+    ShellExec(String),
+    #[cfg(feature = "osc")]
+    OscSend(String),
+    ConfigSet {
+        key: String,
+        /// Re-synced when the config was changed elsewhere
+        control: CustomControl,
+        /// `config_revision` that the control was last synced to
+        revision: Cell<u64>,
+    },
+}
+
+struct ControlState {
+    action: ControlAction,
+    /// Latest value that has not been acted upon yet
+    pending: RefCell<Option<ControlValue>>,
+    child: RefCell<Option<Child>>,
+}
+
+pub(super) fn setup_custom_control<S: 'static>(
+    layout: &mut Layout,
+    parser_state: &ParserState,
+    attribs: &CustomAttribsInfoOwned,
+    app: &AppState,
+    control: &CustomControl,
+) {
+    const ATTRIB: &str = "_on_change";
+    let tag = control.tag();
+
+    let Some(action) = attribs.get_value(ATTRIB) else {
+        return;
+    };
+
+    let mut args = action.split_whitespace();
+    let Some(command) = args.next() else {
+        log_invalid_attrib(parser_state, tag, ATTRIB, action);
+        return;
+    };
+
+    let action = match command {
+        "::ShellExec" => ControlAction::ShellExec(args.collect::<Vec<_>>().join(" ")),
+        #[cfg(feature = "osc")]
+        "::OscSend" => {
+            let Some(address) = args.next() else {
+                log_cmd_missing_arg(parser_state, tag, ATTRIB, action);
+                return;
+            };
+            ControlAction::OscSend(address.to_string())
+        }
+        "::ConfigSet" => {
+            let Some(key) = args.next() else {
+                log_cmd_missing_arg(parser_state, tag, ATTRIB, action);
+                return;
+            };
+
+            // start out with the value from the config
+            let value = config_get(&app.session.config, key);
+            let Some(value) = value.as_ref().and_then(ControlValue::from_json) else {
+                let msg = format!("no such config key \"{key}\"");
+                log_cmd_invalid_arg(parser_state, tag, ATTRIB, action, &msg);
+                return;
+            };
+
+            let mut alterables = EventAlterables::default();
+            let mut common = CallbackDataCommon {
+                alterables: &mut alterables,
+                state: &layout.state,
+            };
+            let _ = control.set_value(&mut common, &value).inspect_err(|e| {
+                log_cmd_invalid_arg(parser_state, tag, ATTRIB, action, &e.to_string())
+            });
+            let _ = layout.process_alterables(alterables);
+
+            ControlAction::ConfigSet {
+                key: key.to_string(),
+                control: control.clone(),
+                revision: Cell::new(app.session.config_revision),
+            }
+        }
+        unk => {
+            log_invalid_attrib(parser_state, tag, ATTRIB, unk);
+            return;
+        }
+    };
+
+    let state = Rc::new(ControlState {
+        action,
+        pending: RefCell::new(None),
+        child: RefCell::new(None),
+    });
+
+    let set_pending = {
+        let state = state.clone();
+        move |value: ControlValue| {
+            *state.pending.borrow_mut() = Some(value);
+        }
+    };
+
+    match control {
+        CustomControl::Slider(c) => c.on_value_changed(Box::new(move |_, e| {
+            set_pending(ControlValue::Number(e.value));
+            Ok(())
+        })),
+        CustomControl::Checkbox(c) => c.on_toggle(Box::new(move |_, e| {
+            set_pending(ControlValue::Bool(e.checked));
+            Ok(())
+        })),
+        CustomControl::EditBox(c) => c.on_submit(Box::new(move |_, e| {
+            set_pending(ControlValue::Text(e.text));
+            Ok(())
+        })),
+        CustomControl::RadioGroup(c) => c.on_value_changed(Box::new(move |_, e| {
+            set_pending(ControlValue::Text(
+                e.value.as_deref().unwrap_or_default().to_string(),
+            ));
+            Ok(())
+        })),
+    }
+
+    // component callbacks have no access to AppState, so act on the value in the next tick.
+    // rapid changes (e.g. dragging a slider) only act on the latest value.
+    layout.add_event_listener::<AppState, S>(
+        attribs.widget_id,
+        EventListenerKind::InternalStateChange,
+        Box::new(move |common, _, app, _| {
+            control_on_tick(&state, common, app);
+            Ok(EventResult::Pass)
+        }),
+    );
+}
+
+fn control_on_tick(state: &ControlState, common: &mut CallbackDataCommon, app: &mut AppState) {
+    let mut child = state.child.borrow_mut();
+    if let Some(c) = child.as_mut() {
+        if matches!(c.try_wait(), Ok(None)) {
+            // wait for the previous run to finish
+            return;
+        }
+        *child = None;
+    }
+
+    let Some(value) = state.pending.borrow_mut().take() else {
+        if let ControlAction::ConfigSet {
+            key,
+            control,
+            revision,
+        } = &state.action
+        {
+            config_sync(key, control, revision, common, app);
+        }
+        return;
+    };
+
+    let result = match &state.action {
+        ControlAction::ShellExec(exec) => Command::new("sh")
+            .arg("-c")
+            .arg(exec)
+            .env("WAYVR_VALUE", value.to_string())
+            .stdin(Stdio::null())
+            .spawn()
+            .map(|c| *child = Some(c))
+            .with_context(|| format!("Failed to run shell script: '{exec}'")),
+        #[cfg(feature = "osc")]
+        ControlAction::OscSend(address) => osc_send(app, address, &value),
+        ControlAction::ConfigSet { key, .. } => config_set(app, key, &value),
+    };
+
+    let _ = result.inspect_err(|e| log::warn!("_on_change: {e:?}"));
+}
+
+#[cfg(feature = "osc")]
+fn osc_send(app: &AppState, address: &str, value: &ControlValue) -> anyhow::Result<()> {
+    use rosc::OscType;
+
+    let sender = app
+        .osc_sender
+        .as_ref()
+        .context("OscSend: sender is not available.")?;
+
+    let arg = match value {
+        ControlValue::Bool(b) => OscType::Bool(*b),
+        ControlValue::Number(n) => OscType::Float(*n),
+        ControlValue::Text(t) => OscType::String(t.clone()),
+    };
+    sender.send_message(address.to_string(), vec![arg])
+}
+
+fn config_get(config: &GeneralConfig, key: &str) -> Option<serde_json::Value> {
+    serde_json::to_value(config)
+        .ok()?
+        .get_mut(key)
+        .map(serde_json::Value::take)
+}
+
+/// Shows the current config value, if it was changed since the last sync.
+fn config_sync(
+    key: &str,
+    control: &CustomControl,
+    revision: &Cell<u64>,
+    common: &mut CallbackDataCommon,
+    app: &AppState,
+) {
+    if revision.get() == app.session.config_revision {
+        return;
+    }
+    revision.set(app.session.config_revision);
+
+    let value = config_get(&app.session.config, key);
+    let Some(value) = value.as_ref().and_then(ControlValue::from_json) else {
+        return;
+    };
+    let _ = control
+        .set_value(common, &value)
+        .inspect_err(|e| log::warn!("ConfigSet {key}: {e:?}"));
+}
+
+/// Sets a single `GeneralConfig` field by its serialized name.
+fn config_set(app: &mut AppState, key: &str, value: &ControlValue) -> anyhow::Result<()> {
+    let mut config = serde_json::to_value(&app.session.config)?;
+    let field = config
+        .get_mut(key)
+        .with_context(|| format!("ConfigSet: no such config key \"{key}\""))?;
+
+    let new_value = value.to_json(field);
+    if *field == new_value {
+        return Ok(());
+    }
+    *field = new_value;
+
+    app.session.config = serde_json::from_value(config)
+        .with_context(|| format!("ConfigSet: invalid value for \"{key}\": {value}"))?;
+    app.session.config_dirty = true;
+
+    #[cfg(feature = "openxr")]
+    {
+        use crate::backend::task::OpenXrTask;
+        app.tasks
+            .enqueue(TaskType::OpenXR(OpenXrTask::SettingsChanged));
+    }
+    app.tasks
+        .enqueue(TaskType::Overlay(OverlayTask::SettingsChanged));
+    Ok(())
+}
@@ -13,6 +13,7 @@ use crate::{
 #[derive(Default)]
 /// Helper for managing a list of overlays
 /// Populates `id="devices_root"` with `<Hmd>`, `<LeftHand>`, `<RightHand>`, `<Tracker>` templates
+/// Populates `id="trackers_root"` with a `<TrackerInfo>` template for each tracker
 pub struct DeviceList;
 
 impl DeviceList {
@@ -24,40 +25,75 @@ impl DeviceList {
         event_data: &OverlayEventData,
         doc_params: &ParseDocumentParams,
     ) -> anyhow::Result<bool> {
-        let mut elements_changed = false;
         match event_data {
             OverlayEventData::DevicesChanged => {
-                let devices_root = parser_state
-                    .get_widget_id("devices_root")
-                    .unwrap_or_default();
+                self.populate(app, layout, parser_state, doc_params)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Returns true if any elements were added or removed.
+    pub fn populate(
+        &mut self,
+        app: &AppState,
+        layout: &mut Layout,
+        parser_state: &mut ParserState,
+        doc_params: &ParseDocumentParams,
+    ) -> anyhow::Result<bool> {
+        let mut elements_changed = false;
+
+        let devices_root = parser_state
+            .get_widget_id("devices_root")
+            .unwrap_or_default();
+
+        if !devices_root.is_null() {
+            layout.remove_children(devices_root);
 
-                if devices_root.is_null() {
-                    return Ok(false);
+            for (i, device) in app.input_state.devices.iter().enumerate() {
+                let mut params = HashMap::new();
+
+                if matches!(device.role, TrackedDeviceRole::None) {
+                    continue;
                 }
 
-                layout.remove_children(devices_root);
+                let template = device.role.as_ref();
 
-                for (i, device) in app.input_state.devices.iter().enumerate() {
-                    let mut params = HashMap::new();
+                params.insert("idx".into(), i.to_string().into());
+                parser_state.instantiate_template(
+                    &doc_params,
+                    template,
+                    layout,
+                    devices_root,
+                    params,
+                )?;
+            }
+            elements_changed = true;
+        }
 
-                    if matches!(device.role, TrackedDeviceRole::None) {
-                        continue;
-                    }
+        let trackers_root = parser_state
+            .get_widget_id("trackers_root")
+            .unwrap_or_default();
 
-                    let template = device.role.as_ref();
+        if !trackers_root.is_null() {
+            layout.remove_children(trackers_root);
 
-                    params.insert("idx".into(), i.to_string().into());
-                    parser_state.instantiate_template(
-                        &doc_params,
-                        template,
-                        layout,
-                        devices_root,
-                        params,
-                    )?;
+            for (i, device) in app.input_state.devices.iter().enumerate() {
+                if !matches!(device.role, TrackedDeviceRole::Tracker) {
+                    continue;
                 }
-                elements_changed = true;
+
+                let mut params = HashMap::new();
+                params.insert("idx".into(), i.to_string().into());
+                parser_state.instantiate_template(
+                    &doc_params,
+                    "TrackerInfo",
+                    layout,
+                    trackers_root,
+                    params,
+                )?;
             }
-            _ => {}
+            elements_changed = true;
         }
 
         Ok(elements_changed)
@@ -0,0 +1,204 @@
+use std::{cell::RefCell, collections::VecDeque, io::Read, sync::mpsc, time::Duration};
+
+use anyhow::bail;
+
+use wgui::{
+    assets::AssetPath,
+    event::{self, EventCallback},
+    layout::Layout,
+    parser::{CustomAttribsInfoOwned, ParserState},
+    renderer_vk::text::custom_glyph::CustomGlyphData,
+    widget::{EventResult, image::WidgetImage},
+};
+
+use crate::{
+    gui::panel::{log_invalid_attrib, log_missing_attrib},
+    state::AppState,
+};
+
+/// Larger remote album art is not shown
+const MAX_ART_BYTES: u64 = 8 * 1024 * 1024;
+const ART_TIMEOUT: Duration = Duration::from_secs(10);
+/// Remote album art kept around, for players that switch between a few tracks
+const MAX_CACHED_ART: usize = 8;
+
+pub(super) fn setup_custom_image<S: 'static>(
+    layout: &mut Layout,
+    parser_state: &ParserState,
+    attribs: &CustomAttribsInfoOwned,
+) {
+    const TAG: &str = "image";
+
+    let Some(source) = attribs.get_value("_source") else {
+        log_missing_attrib(parser_state, TAG, "_source");
+        return;
+    };
+
+    let callback: EventCallback<AppState, S> = match source {
+        "media_art" => {
+            // shown while there's no album art
+            let fallback = layout
+                .state
+                .widgets
+                .get_as::<WidgetImage>(attribs.widget_id)
+                .and_then(|i| i.get_content());
+
+            let state = MediaArtState {
+                fallback,
+                art_url: RefCell::new(None),
+                remote: RefCell::new(RemoteArt::default()),
+            };
+
+            Box::new(move |common, data, app, _| {
+                media_art_on_tick(&state, common, data, app);
+                Ok(EventResult::Pass)
+            })
+        }
+        unk => {
+            log_invalid_attrib(parser_state, TAG, "_source", unk);
+            return;
+        }
+    };
+
+    layout.add_event_listener(
+        attribs.widget_id,
+        wgui::event::EventListenerKind::InternalStateChange,
+        callback,
+    );
+}
+
+struct MediaArtState {
+    fallback: Option<CustomGlyphData>,
+    art_url: RefCell<Option<String>>,
+    remote: RefCell<RemoteArt>,
+}
+
+fn media_art_on_tick(
+    state: &MediaArtState,
+    common: &mut event::CallbackDataCommon,
+    data: &mut event::CallbackData,
+    app: &mut AppState,
+) {
+    let art_url = app.dbus.mpris().info().and_then(|i| i.art_url);
+
+    let mut remote = state.remote.borrow_mut();
+    let fetched = remote.poll(app);
+
+    let mut last_url = state.art_url.borrow_mut();
+    if *last_url == art_url && !fetched {
+        return;
+    }
+
+    // the placeholder is shown until remote art is fetched
+    let glyph = match art_url.as_deref() {
+        Some(url) if url.starts_with("http://") || url.starts_with("https://") => remote.get(url),
+        Some(url) => url.strip_prefix("file://").and_then(|path| {
+            CustomGlyphData::from_assets(&app.wgui_globals, AssetPath::File(&percent_decode(path)))
+                .inspect_err(|e| log::debug!("Could not load album art: {e:?}"))
+                .ok()
+        }),
+        None => None,
+    }
+    .or_else(|| state.fallback.clone());
+
+    let image = data.obj.get_as_mut::<WidgetImage>().unwrap();
+    image.set_content(common, glyph);
+    *last_url = art_url;
+}
+
+/// Fetches http(s) album art on a background thread, one at a time.
+#[derive(Default)]
+struct RemoteArt {
+    /// By URL, most recently used last. None if it could not be fetched.
+    cache: VecDeque<(String, Option<CustomGlyphData>)>,
+    pending: Option<(String, mpsc::Receiver<anyhow::Result<Vec<u8>>>)>,
+}
+
+impl RemoteArt {
+    /// The art at `url` if it was fetched, otherwise starts fetching it.
+    fn get(&mut self, url: &str) -> Option<CustomGlyphData> {
+        if let Some(idx) = self.cache.iter().position(|(u, _)| u == url) {
+            let entry = self.cache.remove(idx)?;
+            let glyph = entry.1.clone();
+            self.cache.push_back(entry);
+            return glyph;
+        }
+        if self.pending.as_ref().is_some_and(|(u, _)| u == url) {
+            return None;
+        }
+
+        // a fetch still running for an earlier track finishes on its own
+        let (sender, receiver) = mpsc::channel();
+        let thread_url = url.to_string();
+        std::thread::spawn(move || {
+            let _ = sender.send(fetch_art(&thread_url));
+        });
+        self.pending = Some((url.to_string(), receiver));
+        None
+    }
+
+    /// Returns true once the pending fetch is done, successful or not.
+    fn poll(&mut self, app: &AppState) -> bool {
+        let Some((url, receiver)) = self.pending.as_ref() else {
+            return false;
+        };
+        let glyph = match receiver.try_recv() {
+            Ok(result) => result
+                .and_then(|data| CustomGlyphData::from_bytes_raster(&app.wgui_globals, url, &data)),
+            Err(mpsc::TryRecvError::Empty) => return false,
+            Err(mpsc::TryRecvError::Disconnected) => Err(anyhow::anyhow!("fetch thread died")),
+        }
+        .inspect_err(|e| log::debug!("Could not fetch album art from {url}: {e:?}"))
+        .ok();
+
+        let url = url.clone();
+        self.pending = None;
+        if self.cache.len() >= MAX_CACHED_ART {
+            self.cache.pop_front();
+        }
+        self.cache.push_back((url, glyph));
+        true
+    }
+}
+
+fn fetch_art(url: &str) -> anyhow::Result<Vec<u8>> {
+    let response = reqwest::blocking::Client::builder()
+        .timeout(ART_TIMEOUT)
+        .build()?
+        .get(url)
+        .send()?
+        .error_for_status()?;
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_ART_BYTES)
+    {
+        bail!("larger than {MAX_ART_BYTES} bytes");
+    }
+
+    let mut data = Vec::new();
+    response.take(MAX_ART_BYTES + 1).read_to_end(&mut data)?;
+    if data.len() as u64 > MAX_ART_BYTES {
+        bail!("larger than {MAX_ART_BYTES} bytes");
+    }
+    Ok(data)
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(b) = s
+                .get(i + 1..i + 3)
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+        {
+            out.push(b);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
@@ -1,8 +1,18 @@
-use std::rc::Rc;
+use std::{
+    cell::RefCell,
+    io::{BufRead, BufReader, Read},
+    os::unix::process::CommandExt,
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    rc::Rc,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
 
 use chrono::DateTime;
 use chrono::Local;
 use chrono_tz::Tz;
+use serde::Deserialize;
 use wgui::{
     drawing,
     event::{self, EventCallback},
@@ -15,6 +25,10 @@ use wgui::{
 use crate::{
     gui::panel::{log_invalid_attrib, log_missing_attrib},
     state::AppState,
+    subsystem::{
+        alarms,
+        sysmon::{Metric, MetricValue},
+    },
 };
 
 #[allow(clippy::too_many_lines)]
@@ -67,6 +81,32 @@ pub(super) fn setup_custom_label<S: 'static>(
                 Ok(EventResult::Pass)
             })
         }
+        "device" => {
+            let Some(device) = attribs.get_value("_device") else {
+                log_missing_attrib(parser_state, TAG, "_device");
+                return;
+            };
+            let Ok(device) = device.parse::<usize>() else {
+                let msg = format!("expected integer, found \"{device}\"");
+                log_invalid_attrib(parser_state, TAG, "_device", &msg);
+                return;
+            };
+
+            let display = match attribs.get_value("_display").unwrap_or("name") {
+                "name" => DeviceDisplay::Name,
+                "serial" => DeviceDisplay::Serial,
+                "role" => DeviceDisplay::Role,
+                unk => {
+                    log_invalid_attrib(parser_state, TAG, "_display", unk);
+                    return;
+                }
+            };
+
+            Box::new(move |common, data, app, _| {
+                device_on_tick(device, display, common, data, app);
+                Ok(EventResult::Pass)
+            })
+        }
         "clock" => {
             let Some(display) = attribs.get_value("_display") else {
                 log_missing_attrib(parser_state, TAG, "_display");
@@ -150,10 +190,161 @@ pub(super) fn setup_custom_label<S: 'static>(
                 Ok(EventResult::Pass)
             })
         }
+        "alarm" => {
+            let display = match attribs.get_value("_display").unwrap_or("label") {
+                "label" => AlarmDisplay::Label,
+                "time" => AlarmDisplay::Time,
+                "remaining" => AlarmDisplay::Remaining,
+                unk => {
+                    log_invalid_attrib(parser_state, TAG, "_display", unk);
+                    return;
+                }
+            };
+
+            Box::new(move |common, data, app, _| {
+                alarm_on_tick(display, common, data, app);
+                Ok(EventResult::Pass)
+            })
+        }
+        "calendar" => {
+            let display = match attribs.get_value("_display").unwrap_or("summary") {
+                "summary" => CalendarDisplay::Summary,
+                "time" => CalendarDisplay::Time,
+                "date" => CalendarDisplay::Date,
+                "location" => CalendarDisplay::Location,
+                unk => {
+                    log_invalid_attrib(parser_state, TAG, "_display", unk);
+                    return;
+                }
+            };
+
+            let index = match attribs.get_value("_index").map(str::parse::<usize>) {
+                None => 0,
+                Some(Ok(index)) => index,
+                Some(Err(_)) => {
+                    let msg = "expected non-negative integer";
+                    log_invalid_attrib(parser_state, TAG, "_index", msg);
+                    return;
+                }
+            };
+
+            Box::new(move |common, data, app, _| {
+                calendar_on_tick(display, index, common, data, app);
+                Ok(EventResult::Pass)
+            })
+        }
         "ipd" => Box::new(|common, data, app, _| {
             ipd_on_tick(common, data, app);
             Ok(EventResult::Pass)
         }),
+        "media" => {
+            let display = match attribs.get_value("_display").unwrap_or("title") {
+                "title" => MediaDisplay::Title,
+                "artist" => MediaDisplay::Artist,
+                "album" => MediaDisplay::Album,
+                "player" => MediaDisplay::Player,
+                "position" => MediaDisplay::Position,
+                "length" => MediaDisplay::Length,
+                unk => {
+                    log_invalid_attrib(parser_state, TAG, "_display", unk);
+                    return;
+                }
+            };
+
+            Box::new(move |common, data, app, _| {
+                media_on_tick(display, common, data, app);
+                Ok(EventResult::Pass)
+            })
+        }
+        "cpu" | "ram" | "gpu" | "net" | "disk" | "temp" => {
+            let Some(metric) = parse_metric(source, parser_state, attribs) else {
+                return;
+            };
+
+            let unit = match attribs.get_value("_unit") {
+                None => None,
+                Some(unit) => {
+                    if let Some(idx) = BYTE_UNITS.iter().position(|u| *u == unit) {
+                        Some(idx)
+                    } else {
+                        log_invalid_attrib(parser_state, TAG, "_unit", unit);
+                        None
+                    }
+                }
+            };
+
+            let high_threshold = attribs.get_value("_high_threshold").and_then(|s| {
+                s.parse::<f64>()
+                    .inspect_err(|_| {
+                        let msg = format!("expected number, found \"{s}\"");
+                        log_invalid_attrib(parser_state, TAG, "_high_threshold", &msg);
+                    })
+                    .ok()
+            });
+
+            let precision = attribs.get_value("_precision").and_then(|s| {
+                s.parse::<usize>()
+                    .inspect_err(|_| {
+                        let msg = format!("expected number of decimals, found \"{s}\"");
+                        log_invalid_attrib(parser_state, TAG, "_precision", &msg);
+                    })
+                    .ok()
+            });
+
+            let state = SysmonLabelState {
+                metric,
+                format: attribs.get_value("_format").unwrap_or("%v").into(),
+                precision,
+                unit,
+                high_threshold,
+                high_color: attribs
+                    .get_value("_high_color")
+                    .and_then(parse_color_hex)
+                    .unwrap_or(BAT_LOW),
+                normal_color: attribs
+                    .get_value("_normal_color")
+                    .and_then(parse_color_hex)
+                    .unwrap_or_else(|| layout.state.globals.defaults().text_color),
+            };
+
+            Box::new(move |common, data, app, _| {
+                sysmon_on_tick(&state, common, data, app);
+                Ok(EventResult::Pass)
+            })
+        }
+        "exec" => {
+            let Some(exec) = attribs.get_value("_exec") else {
+                log_missing_attrib(parser_state, TAG, "_exec");
+                return;
+            };
+
+            let interval = match attribs.get_value("_interval") {
+                None => None,
+                Some(interval) => match interval.parse::<f32>() {
+                    Ok(secs) if secs > 0. => Some(Duration::from_secs_f32(secs)),
+                    _ => {
+                        let msg = format!("expected positive number, found \"{interval}\"");
+                        log_invalid_attrib(parser_state, TAG, "_interval", &msg);
+                        return;
+                    }
+                },
+            };
+
+            let state = ExecLabelState {
+                exec: exec.into(),
+                interval,
+                json: attribs
+                    .get_value("_json")
+                    .is_some_and(|v| v == "1" || v == "true"),
+                default_color: layout.state.globals.defaults().text_color,
+                mut_state: RefCell::new(ExecLabelMutableState::default()),
+            };
+
+            Box::new(move |common, data, _, _| {
+                exec_on_tick(&state, common, data);
+                Ok(EventResult::Pass)
+            })
+        }
         unk => {
             log_invalid_attrib(parser_state, TAG, "_source", unk);
             return;
@@ -210,6 +401,37 @@ fn battery_on_tick(
     label.set_text(common, Translation::default());
 }
 
+#[derive(Clone, Copy)]
+enum DeviceDisplay {
+    Name,
+    Serial,
+    Role,
+}
+
+fn device_on_tick(
+    device: usize,
+    display: DeviceDisplay,
+    common: &mut event::CallbackDataCommon,
+    data: &mut event::CallbackData,
+    app: &AppState,
+) {
+    let text = app
+        .input_state
+        .devices
+        .get(device)
+        .map(|device| match display {
+            DeviceDisplay::Name => device.display_name(),
+            DeviceDisplay::Serial => device.serial.as_str(),
+            DeviceDisplay::Role => device.role.as_ref(),
+        });
+
+    let label = data.obj.get_as_mut::<WidgetLabel>().unwrap();
+    label.set_text(
+        common,
+        text.map(Translation::from_raw_text).unwrap_or_default(),
+    );
+}
+
 struct ClockLabelState {
     timezone: Option<Tz>,
     format: Rc<str>,
@@ -253,6 +475,79 @@ fn timer_on_tick(
     label.set_text(common, Translation::from_raw_text(&time));
 }
 
+#[derive(Clone, Copy)]
+enum AlarmDisplay {
+    Label,
+    Time,
+    Remaining,
+}
+
+fn alarm_on_tick(
+    display: AlarmDisplay,
+    common: &mut event::CallbackDataCommon,
+    data: &mut event::CallbackData,
+    app: &AppState,
+) {
+    let text = app.alarms.next_due().map(|alarm| match display {
+        AlarmDisplay::Label if alarm.label.is_empty() => {
+            Translation::from_translation_key(alarm.kind.title_key())
+        }
+        AlarmDisplay::Label => Translation::from_raw_text(&alarm.label),
+        AlarmDisplay::Time => Translation::from_raw_text(&alarms::format_due_time(alarm, app)),
+        AlarmDisplay::Remaining => Translation::from_raw_text(&format_duration(
+            Duration::from_secs(alarm.remaining_secs()),
+        )),
+    });
+
+    let label = data.obj.get_as_mut::<WidgetLabel>().unwrap();
+    label.set_text(common, text.unwrap_or_default());
+}
+
+#[derive(Clone, Copy)]
+enum CalendarDisplay {
+    Summary,
+    Time,
+    Date,
+    Location,
+}
+
+fn calendar_on_tick(
+    display: CalendarDisplay,
+    index: usize,
+    common: &mut event::CallbackDataCommon,
+    data: &mut event::CallbackData,
+    app: &AppState,
+) {
+    let text = app
+        .calendar
+        .upcoming()
+        .nth(index)
+        .map(|event| match display {
+            CalendarDisplay::Summary => Translation::from_raw_text(&event.summary),
+            CalendarDisplay::Time if event.all_day => {
+                Translation::from_translation_key("CALENDAR.ALL_DAY")
+            }
+            CalendarDisplay::Time => {
+                Translation::from_raw_text(&event.format_time(app.session.config.clock_12h))
+            }
+            CalendarDisplay::Date => {
+                let today = Local::now().date_naive();
+                let date = event.start.date_naive();
+                if date <= today {
+                    Translation::from_translation_key("CALENDAR.TODAY")
+                } else if today.succ_opt() == Some(date) {
+                    Translation::from_translation_key("CALENDAR.TOMORROW")
+                } else {
+                    Translation::from_raw_text(&event.start.format("%a %x").to_string())
+                }
+            }
+            CalendarDisplay::Location => Translation::from_raw_text(&event.location),
+        });
+
+    let label = data.obj.get_as_mut::<WidgetLabel>().unwrap();
+    label.set_text(common, text.unwrap_or_default());
+}
+
 fn ipd_on_tick(
     common: &mut event::CallbackDataCommon,
     data: &mut event::CallbackData,
@@ -262,3 +557,355 @@ fn ipd_on_tick(
     let label = data.obj.get_as_mut::<WidgetLabel>().unwrap();
     label.set_text(common, Translation::from_raw_text(&text));
 }
+
+#[derive(Clone, Copy)]
+enum MediaDisplay {
+    Title,
+    Artist,
+    Album,
+    Player,
+    Position,
+    Length,
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, (secs / 60) % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+fn media_on_tick(
+    display: MediaDisplay,
+    common: &mut event::CallbackDataCommon,
+    data: &mut event::CallbackData,
+    app: &mut AppState,
+) {
+    let text = app.dbus.mpris().info().map(|info| match display {
+        MediaDisplay::Title => info.title,
+        MediaDisplay::Artist => info.artist,
+        MediaDisplay::Album => info.album,
+        MediaDisplay::Player => info.player,
+        MediaDisplay::Position => format_duration(info.position()),
+        MediaDisplay::Length => info.length.map(format_duration).unwrap_or_default(),
+    });
+
+    let label = data.obj.get_as_mut::<WidgetLabel>().unwrap();
+    label.set_text(
+        common,
+        Translation::from_raw_text(text.as_deref().unwrap_or_default()),
+    );
+}
+
+fn parse_metric(
+    source: &str,
+    parser_state: &ParserState,
+    attribs: &CustomAttribsInfoOwned,
+) -> Option<Metric> {
+    const TAG: &str = "label";
+
+    let display = attribs.get_value("_display").unwrap_or_default();
+    let string_attrib = |name: &str| attribs.get_value(name).map(String::from);
+
+    let metric = match source {
+        "cpu" => match display {
+            "" | "usage" => Metric::CpuUsage,
+            unk => {
+                log_invalid_attrib(parser_state, TAG, "_display", unk);
+                return None;
+            }
+        },
+        "ram" => match display {
+            "" | "percent" => Metric::RamPercent,
+            "used" => Metric::RamUsed,
+            "total" => Metric::RamTotal,
+            unk => {
+                log_invalid_attrib(parser_state, TAG, "_display", unk);
+                return None;
+            }
+        },
+        "gpu" => {
+            let device = attribs.get_value("_device").unwrap_or("0");
+            let Ok(device) = device.parse::<usize>() else {
+                let msg = format!("expected integer, found \"{device}\"");
+                log_invalid_attrib(parser_state, TAG, "_device", &msg);
+                return None;
+            };
+            match display {
+                "" | "usage" => Metric::GpuUsage(device),
+                "vram_used" => Metric::GpuVramUsed(device),
+                "vram_total" => Metric::GpuVramTotal(device),
+                "vram_percent" => Metric::GpuVramPercent(device),
+                "temp" => Metric::GpuTemperature(device),
+                "freq" => Metric::GpuFrequency(device),
+                unk => {
+                    log_invalid_attrib(parser_state, TAG, "_display", unk);
+                    return None;
+                }
+            }
+        }
+        "net" => match display {
+            "" | "down" => Metric::NetDown(string_attrib("_interface")),
+            "up" => Metric::NetUp(string_attrib("_interface")),
+            unk => {
+                log_invalid_attrib(parser_state, TAG, "_display", unk);
+                return None;
+            }
+        },
+        "disk" => {
+            let mount = PathBuf::from(attribs.get_value("_mount").unwrap_or("/"));
+            match display {
+                "" | "percent" => Metric::DiskPercent(mount),
+                "used" => Metric::DiskUsed(mount),
+                "free" => Metric::DiskFree(mount),
+                "total" => Metric::DiskTotal(mount),
+                unk => {
+                    log_invalid_attrib(parser_state, TAG, "_display", unk);
+                    return None;
+                }
+            }
+        }
+        _ => Metric::Temperature(string_attrib("_sensor")),
+    };
+    Some(metric)
+}
+
+const BYTE_UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+struct SysmonLabelState {
+    metric: Metric,
+    format: Rc<str>,
+    precision: Option<usize>,
+    /// Index into `BYTE_UNITS`, picked automatically if `None`
+    unit: Option<usize>,
+    high_threshold: Option<f64>,
+    high_color: drawing::Color,
+    normal_color: drawing::Color,
+}
+
+impl SysmonLabelState {
+    fn format_bytes(&self, bytes: f64) -> String {
+        let unit = self.unit.unwrap_or_else(|| {
+            let mut unit = 0;
+            let mut value = bytes;
+            while value >= 1024. && unit < BYTE_UNITS.len() - 1 {
+                value /= 1024.;
+                unit += 1;
+            }
+            unit
+        });
+        let value = bytes / 1024f64.powi(unit as i32);
+        let precision = self.precision.unwrap_or(if unit == 0 { 0 } else { 1 });
+        format!("{value:.precision$} {}", BYTE_UNITS[unit])
+    }
+
+    fn format_value(&self, value: MetricValue) -> String {
+        let precision = self.precision.unwrap_or(0);
+        match value {
+            MetricValue::Percent(p) => format!("{p:.precision$}%"),
+            MetricValue::Bytes(b) => self.format_bytes(b as f64),
+            MetricValue::BytesPerSec(b) => format!("{}/s", self.format_bytes(b)),
+            MetricValue::Celsius(c) => format!("{c:.precision$}°C"),
+            MetricValue::MegaHertz(mhz) => format!("{mhz} MHz"),
+        }
+    }
+}
+
+fn sysmon_on_tick(
+    state: &SysmonLabelState,
+    common: &mut event::CallbackDataCommon,
+    data: &mut event::CallbackData,
+    app: &mut AppState,
+) {
+    let label = data.obj.get_as_mut::<WidgetLabel>().unwrap();
+
+    let Some(value) = app.sysmon.read(&state.metric) else {
+        label.set_text(common, Translation::default());
+        return;
+    };
+
+    if let Some(threshold) = state.high_threshold {
+        let color = if value.as_f64() >= threshold {
+            state.high_color
+        } else {
+            state.normal_color
+        };
+        label.set_color(common, color, false);
+    }
+
+    let text = state.format.replace("%v", &state.format_value(value));
+    label.set_text(common, Translation::from_raw_text(&text));
+}
+
+/// Delay before restarting a long-running `exec` process that has exited
+const EXEC_RESPAWN_DELAY: Duration = Duration::from_secs(5);
+/// How long an `exec` command run on an interval may take before it's killed
+const EXEC_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct ExecLabelState {
+    exec: Rc<str>,
+    /// Run once per interval if set, otherwise keep a long-running process and read its stdout line by line
+    interval: Option<Duration>,
+    json: bool,
+    default_color: drawing::Color,
+    mut_state: RefCell<ExecLabelMutableState>,
+}
+
+#[derive(Default)]
+struct ExecLabelMutableState {
+    child: Option<Child>,
+    started: Option<Instant>,
+    receiver: Option<mpsc::Receiver<String>>,
+    next_run: Option<Instant>,
+}
+
+impl Drop for ExecLabelMutableState {
+    fn drop(&mut self) {
+        if let Some(child) = self.child.take() {
+            kill_group(child);
+        }
+    }
+}
+
+/// Kills the shell along with anything it started.
+fn kill_group(mut child: Child) {
+    // the shell leads its own process group, see `exec_spawn`
+    unsafe {
+        libc::kill(-(child.id() as i32), libc::SIGKILL);
+    }
+    let _ = child.wait();
+}
+
+#[derive(Deserialize)]
+struct ExecLabelOutput {
+    text: Option<String>,
+    color: Option<String>,
+}
+
+fn exec_spawn(state: &ExecLabelState, mut_state: &mut ExecLabelMutableState) -> anyhow::Result<()> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(&*state.exec)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .process_group(0)
+        .spawn()?;
+
+    let mut stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| anyhow::anyhow!("no stdout"))?;
+
+    let (sender, receiver) = mpsc::channel();
+    let streaming = state.interval.is_none();
+
+    std::thread::spawn(move || {
+        if streaming {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        } else {
+            let mut output = String::new();
+            if stdout.read_to_string(&mut output).is_ok() {
+                let _ = sender.send(output.trim_end().to_string());
+            }
+        }
+    });
+
+    mut_state.child = Some(child);
+    mut_state.started = Some(Instant::now());
+    mut_state.receiver = Some(receiver);
+    Ok(())
+}
+
+fn exec_on_tick(
+    state: &ExecLabelState,
+    common: &mut event::CallbackDataCommon,
+    data: &mut event::CallbackData,
+) {
+    let mut mut_state = state.mut_state.borrow_mut();
+
+    let mut output = None;
+    let mut disconnected = false;
+    if let Some(receiver) = mut_state.receiver.as_ref() {
+        loop {
+            match receiver.try_recv() {
+                Ok(line) => output = Some(line),
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    disconnected = true;
+                    break;
+                }
+            }
+        }
+    }
+    if disconnected {
+        mut_state.receiver = None;
+    }
+
+    // stdout is closed; reap the child once it exits
+    if mut_state.receiver.is_none()
+        && let Some(child) = mut_state.child.as_mut()
+        && !matches!(child.try_wait(), Ok(None))
+    {
+        mut_state.child = None;
+        mut_state.next_run = Some(Instant::now() + state.interval.unwrap_or(EXEC_RESPAWN_DELAY));
+    }
+
+    if let Some(interval) = state.interval
+        && mut_state
+            .started
+            .is_some_and(|started| started.elapsed() >= EXEC_TIMEOUT)
+        && let Some(child) = mut_state.child.take()
+    {
+        log::warn!("Label command '{}' timed out", state.exec);
+        kill_group(child);
+        mut_state.receiver = None;
+        mut_state.next_run = Some(Instant::now() + interval);
+    }
+
+    if mut_state.child.is_none()
+        && mut_state.receiver.is_none()
+        && mut_state.next_run.is_none_or(|t| t <= Instant::now())
+        && let Err(e) = exec_spawn(state, &mut mut_state)
+    {
+        log::warn!("Could not run label command '{}': {e:?}", state.exec);
+        mut_state.next_run = Some(Instant::now() + state.interval.unwrap_or(EXEC_RESPAWN_DELAY));
+    }
+
+    let Some(output) = output else {
+        return;
+    };
+
+    let label = data.obj.get_as_mut::<WidgetLabel>().unwrap();
+
+    if !state.json {
+        label.set_text(common, Translation::from_raw_text(&output));
+        return;
+    }
+
+    match serde_json::from_str::<ExecLabelOutput>(&output) {
+        Ok(output) => {
+            if let Some(text) = output.text {
+                label.set_text(common, Translation::from_raw_text(&text));
+            }
+            let color = output
+                .color
+                .as_deref()
+                .and_then(parse_color_hex)
+                .unwrap_or(state.default_color);
+            label.set_color(common, color, false);
+        }
+        Err(e) => {
+            log::warn!("Label command '{}' returned invalid JSON: {e}", state.exec);
+            label.set_text(common, Translation::from_raw_text(&output));
+        }
+    }
+}
@@ -2,20 +2,23 @@ use std::{cell::RefCell, rc::Rc};
 
 use anyhow::Context;
 use button::setup_custom_button;
+use control::{CustomControl, setup_custom_control};
 use glam::{Affine2, Vec2, vec2};
 use idmap::IdMap;
+use image::setup_custom_image;
 use label::setup_custom_label;
+use vars::{PanelVars, PanelVarsRef, setup_custom_bindings};
 use wgui::{
     assets::AssetPath,
     components::{
-        button::ComponentButton, checkbox::ComponentCheckbox, radio_group::ComponentRadioGroup,
-        slider::ComponentSlider,
+        button::ComponentButton, checkbox::ComponentCheckbox, editbox::ComponentEditBox,
+        radio_group::ComponentRadioGroup, slider::ComponentSlider,
     },
     drawing,
     event::{
         CallbackDataCommon, Event as WguiEvent, EventAlterables, EventCallback, EventListenerID,
         EventListenerKind, InternalStateChangeEvent, MouseButtonEvent, MouseButtonIndex,
-        MouseLeaveEvent, MouseMotionEvent, MouseWheelEvent,
+        MouseLeaveEvent, MouseMotionEvent, MouseWheelEvent, TextInputEvent,
     },
     gfx::cmd::WGfxClearMode,
     i18n::Translation,
@@ -40,18 +43,25 @@ use crate::{
     backend::task::ModifyPanelCommand,
     state::AppState,
     subsystem::hid::WheelDelta,
-    windowing::backend::{
-        FrameMeta, OverlayBackend, OverlayEventData, RenderResources, ShouldRender, ui_transform,
+    windowing::{
+        OverlayID,
+        backend::{
+            FrameMeta, OverlayBackend, OverlayEventData, RenderResources, ShouldRender,
+            ui_transform,
+        },
     },
 };
 
 use super::timer::GuiTimer;
 
 pub mod button;
+mod control;
 pub mod device_list;
+mod image;
 mod label;
 pub mod overlay_list;
 pub mod set_list;
+mod vars;
 
 const DEFAULT_MAX_SIZE: f32 = 2048.0;
 
@@ -72,12 +82,14 @@ pub struct GuiPanel<S> {
     pub doc_extra: Option<ParseDocumentExtra>,
     pub extra_attribs: IdMap<BackendAttrib, BackendAttribValue>,
     interaction_transform: Option<Affine2>,
+    overlay_id: Option<OverlayID>,
     context: WguiContext,
     timestep: Timestep,
     has_focus: [bool; 2],
     last_content_size: Vec2,
     custom_elems: Rc<RefCell<Vec<CustomAttribsInfoOwned>>>,
     context_menu: Rc<RefCell<ContextMenu>>,
+    vars: PanelVarsRef,
     on_custom_attrib: Option<OnCustomAttribFunc>,
     on_custom_attrib_inner: parser::OnCustomAttribsFunc,
 }
@@ -169,6 +181,7 @@ impl<S: 'static> GuiPanel<S> {
 
         let context = WguiContext::new(&mut app.wgui_shared, 1.0)?;
         let timestep = Timestep::new(60.0);
+        let vars = Rc::new(RefCell::new(PanelVars::from_parser(&parser_state)));
 
         let mut me = Self {
             layout,
@@ -179,6 +192,7 @@ impl<S: 'static> GuiPanel<S> {
             max_size: vec2(DEFAULT_MAX_SIZE as _, DEFAULT_MAX_SIZE as _),
             timers: vec![],
             interaction_transform: None,
+            overlay_id: None,
             on_notify: None,
             gui_scale: params.gui_scale,
             initialized: false,
@@ -188,6 +202,7 @@ impl<S: 'static> GuiPanel<S> {
             custom_elems,
             extra_attribs: Default::default(),
             context_menu: Default::default(),
+            vars,
             on_custom_attrib: params.on_custom_attrib,
             on_custom_attrib_inner,
         };
@@ -200,6 +215,21 @@ impl<S: 'static> GuiPanel<S> {
     pub fn process_custom_elems(&mut self, app: &mut AppState) {
         let mut elems = self.custom_elems.borrow_mut();
         for elem in elems.iter() {
+            let button = self
+                .parser_state
+                .fetch_component_from_widget_id_as::<ComponentButton>(elem.widget_id)
+                .ok();
+
+            let has_bindings = setup_custom_bindings::<S>(
+                &mut self.layout,
+                &self.parser_state,
+                elem,
+                &self.vars,
+                button.as_ref(),
+            );
+            // labels and images may have bindings only
+            let wants_source = !has_bindings || elem.get_value("_source").is_some();
+
             if self
                 .layout
                 .state
@@ -207,19 +237,37 @@ impl<S: 'static> GuiPanel<S> {
                 .get_as::<WidgetLabel>(elem.widget_id)
                 .is_some()
             {
-                setup_custom_label::<S>(&mut self.layout, &self.parser_state, elem, app);
-            } else if let Ok(button) = self
-                .parser_state
-                .fetch_component_from_widget_id_as::<ComponentButton>(elem.widget_id)
+                if wants_source {
+                    setup_custom_label::<S>(&mut self.layout, &self.parser_state, elem, app);
+                }
+            } else if self
+                .layout
+                .state
+                .widgets
+                .get_as::<WidgetImage>(elem.widget_id)
+                .is_some()
             {
+                if wants_source {
+                    setup_custom_image::<S>(&mut self.layout, &self.parser_state, elem);
+                }
+            } else if let Some(button) = button {
                 setup_custom_button::<S>(
                     &mut self.layout,
                     &self.parser_state,
                     elem,
                     &self.context_menu,
                     &self.on_custom_attrib_inner,
+                    &self.vars,
                     button,
                 );
+            } else if let Some(control) = CustomControl::fetch(&self.parser_state, elem) {
+                setup_custom_control::<S>(
+                    &mut self.layout,
+                    &self.parser_state,
+                    elem,
+                    app,
+                    &control,
+                );
             }
 
             if let Some(on_custom_attrib) = &self.on_custom_attrib {
@@ -292,6 +340,20 @@ impl<S: 'static> OverlayBackend for GuiPanel<S> {
             );
         }
 
+        if let Some(overlay_id) = self.overlay_id
+            && let Some(text) = app.hid_provider.take_panel_text(overlay_id)
+        {
+            // editbox only looks at a single character at a time
+            for ch in text.chars() {
+                self.push_event(
+                    app,
+                    &WguiEvent::TextInput(TextInputEvent {
+                        text: Some(ch.to_string().into()),
+                    }),
+                );
+            }
+        }
+
         while self.timestep.on_tick() {
             self.layout.tick()?;
         }
@@ -361,6 +423,10 @@ impl<S: 'static> OverlayBackend for GuiPanel<S> {
     }
 
     fn notify(&mut self, app: &mut AppState, data: OverlayEventData) -> anyhow::Result<()> {
+        if let OverlayEventData::IdAssigned(overlay_id) = &data {
+            self.overlay_id = Some(*overlay_id);
+        }
+
         let Some(on_notify) = self.on_notify.take() else {
             return Ok(());
         };
@@ -600,9 +666,19 @@ pub fn apply_custom_command<T>(
                 .fetch_component_as::<ComponentRadioGroup>(element)
             {
                 radio.set_value(&mut com, &value_str)?;
+            } else if let Ok(editbox) = panel
+                .parser_state
+                .fetch_component_as::<ComponentEditBox>(element)
+            {
+                editbox.set_text(&mut com, value_str);
+            } else {
+                anyhow::bail!("No <CheckBox>, <Slider>, <RadioGroup> or <EditBox> with such id.");
             }
         }
-        ModifyPanelCommand::GetValue => todo!(),
+        ModifyPanelCommand::GetValue(reply) => {
+            let value = get_element_value(&panel.parser_state, element);
+            let _ = reply.send(value.map_err(|e| e.to_string()));
+        }
         ModifyPanelCommand::SetStickyState(sticky_down) => {
             let button = panel
                 .parser_state
@@ -615,3 +691,17 @@ pub fn apply_custom_command<T>(
     panel.layout.process_alterables(alterables)?;
     Ok(())
 }
+
+fn get_element_value(parser_state: &ParserState, element: &str) -> anyhow::Result<String> {
+    if let Ok(cb) = parser_state.fetch_component_as::<ComponentCheckbox>(element) {
+        Ok(u8::from(cb.get_checked()).to_string())
+    } else if let Ok(slider) = parser_state.fetch_component_as::<ComponentSlider>(element) {
+        Ok(slider.get_value().to_string())
+    } else if let Ok(radio) = parser_state.fetch_component_as::<ComponentRadioGroup>(element) {
+        Ok(radio.get_value().as_deref().unwrap_or_default().to_string())
+    } else if let Ok(editbox) = parser_state.fetch_component_as::<ComponentEditBox>(element) {
+        Ok(editbox.get_text().clone())
+    } else {
+        anyhow::bail!("No <CheckBox>, <Slider>, <RadioGroup> or <EditBox> with such id.");
+    }
+}
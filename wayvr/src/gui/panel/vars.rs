@@ -0,0 +1,323 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
+
+use wgui::{
+    components::button::ComponentButton,
+    drawing,
+    event::{self, EventCallback, EventListenerKind, StyleSetRequest},
+    i18n::Translation,
+    layout::Layout,
+    parser::{CustomAttribsInfoOwned, ParserState, parse_color_hex},
+    taffy,
+    widget::{EventResult, label::WidgetLabel, rectangle::WidgetRectangle, sprite::WidgetSprite},
+};
+
+use crate::{
+    gui::panel::{log_invalid_attrib, log_missing_attrib},
+    state::AppState,
+};
+
+/// Attributes that bind an element to a condition.
+const BINDING_ATTRIBS: [&str; 4] = ["_show_if", "_color_if", "_text_if", "_sticky_if"];
+
+/// Panel-local variables, changed by `::VarSet`, `::VarToggle` and `::VarCycle`.
+/// Each starts out with the value of the `<var>` of the same name, or empty.
+#[derive(Default)]
+pub struct PanelVars {
+    values: HashMap<Rc<str>, Rc<str>>,
+}
+
+pub type PanelVarsRef = Rc<RefCell<PanelVars>>;
+
+impl PanelVars {
+    pub fn from_parser(parser_state: &ParserState) -> Self {
+        Self {
+            values: parser_state.data.var_map.clone(),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> &str {
+        self.values.get(name).map_or("", |v| v)
+    }
+
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.values.insert(name.into(), value.into());
+    }
+
+    /// Flips between "1" and "0".
+    pub fn toggle(&mut self, name: &str) {
+        let value = if is_truthy(self.get(name)) { "0" } else { "1" };
+        self.set(name, value);
+    }
+
+    /// Moves on to the value after the current one, wrapping around.
+    /// Starts at the first value if the current one is not in the list.
+    pub fn cycle(&mut self, name: &str, values: &[Rc<str>]) {
+        if values.is_empty() {
+            return;
+        }
+        let current = self.get(name);
+        let next = values
+            .iter()
+            .position(|v| **v == *current)
+            .map_or(0, |i| (i + 1) % values.len());
+        self.set(name, &values[next]);
+    }
+}
+
+fn is_truthy(value: &str) -> bool {
+    !matches!(value, "" | "0" | "false")
+}
+
+enum Operand {
+    Var(Rc<str>),
+    EditMode,
+    ActiveSet,
+    KeyboardVisible,
+}
+
+enum Comparison {
+    Truthy,
+    Eq(Rc<str>),
+    Ne(Rc<str>),
+}
+
+/// `name`, `!name`, `name==value` or `name!=value`.
+/// Names starting with `@` refer to app state instead of panel variables.
+struct Condition {
+    operand: Operand,
+    comparison: Comparison,
+    negate: bool,
+}
+
+impl Condition {
+    fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        let (negate, s) = s
+            .strip_prefix('!')
+            .map_or((false, s), |rest| (true, rest.trim_start()));
+
+        let (name, comparison) = if let Some((name, value)) = s.split_once("!=") {
+            (name, Comparison::Ne(value.trim().into()))
+        } else if let Some((name, value)) = s.split_once("==") {
+            (name, Comparison::Eq(value.trim().into()))
+        } else {
+            (s, Comparison::Truthy)
+        };
+
+        let operand = match name.trim() {
+            "" => return Err(format!("missing variable name in \"{s}\"")),
+            "@edit_mode" => Operand::EditMode,
+            "@set" => Operand::ActiveSet,
+            "@keyboard" => Operand::KeyboardVisible,
+            name if name.starts_with('@') => return Err(format!("unknown app state \"{name}\"")),
+            name => Operand::Var(name.into()),
+        };
+
+        Ok(Self {
+            operand,
+            comparison,
+            negate,
+        })
+    }
+
+    fn eval(&self, vars: &PanelVars, app: &AppState) -> bool {
+        let flag = |b: bool| if b { "1" } else { "0" }.to_string();
+
+        let value = match &self.operand {
+            Operand::Var(name) => vars.get(name).to_string(),
+            Operand::EditMode => flag(app.windowing.edit_mode),
+            Operand::ActiveSet => app
+                .windowing
+                .active_set
+                .map(|s| s.to_string())
+                .unwrap_or_default(),
+            Operand::KeyboardVisible => flag(app.windowing.keyboard_visible),
+        };
+
+        let result = match &self.comparison {
+            Comparison::Truthy => is_truthy(&value),
+            Comparison::Eq(expected) => value == **expected,
+            Comparison::Ne(expected) => value != **expected,
+        };
+        result != self.negate
+    }
+}
+
+enum BindingAction {
+    Show,
+    Color(drawing::Color),
+    Text(Rc<str>),
+    Sticky(Rc<ComponentButton>),
+}
+
+struct BindingState {
+    condition: Condition,
+    action: BindingAction,
+    /// Restored while the condition is false
+    original_color: Option<drawing::Color>,
+    original_text: Option<Translation>,
+    last: Cell<Option<bool>>,
+}
+
+/// Sets up `_show_if`, `_color_if`, `_text_if` and `_sticky_if` on any element.
+/// Returns true if the element has any of these.
+pub(super) fn setup_custom_bindings<S: 'static>(
+    layout: &mut Layout,
+    parser_state: &ParserState,
+    attribs: &CustomAttribsInfoOwned,
+    vars: &PanelVarsRef,
+    button: Option<&Rc<ComponentButton>>,
+) -> bool {
+    const TAG: &str = "element";
+
+    let mut any = false;
+    for attrib in BINDING_ATTRIBS {
+        let Some(value) = attribs.get_value(attrib) else {
+            continue;
+        };
+        any = true;
+
+        let condition = match Condition::parse(value) {
+            Ok(c) => c,
+            Err(msg) => {
+                log_invalid_attrib(parser_state, TAG, attrib, &msg);
+                continue;
+            }
+        };
+
+        let widgets = &layout.state.widgets;
+        let id = attribs.widget_id;
+        let mut original_color = None;
+        let mut original_text = None;
+
+        let action = match attrib {
+            "_show_if" => BindingAction::Show,
+            "_color_if" => {
+                let Some(color) = attribs.get_value("_color") else {
+                    log_missing_attrib(parser_state, TAG, "_color");
+                    continue;
+                };
+                let Some(color) = parse_color_hex(color) else {
+                    log_invalid_attrib(parser_state, TAG, "_color", color);
+                    continue;
+                };
+                original_color = if let Some(rect) = widgets.get_as::<WidgetRectangle>(id) {
+                    Some(rect.params.color)
+                } else if let Some(label) = widgets.get_as::<WidgetLabel>(id) {
+                    label.get_color()
+                } else {
+                    // uncolored sprites are drawn white
+                    widgets
+                        .get_as::<WidgetSprite>(id)
+                        .map(|s| s.get_color().unwrap_or(drawing::Color::new(1., 1., 1., 1.)))
+                };
+                if original_color.is_none() {
+                    let msg = "only <rectangle>, <label> and <sprite> can be recolored";
+                    log_invalid_attrib(parser_state, TAG, attrib, msg);
+                    continue;
+                }
+                BindingAction::Color(color)
+            }
+            "_text_if" => {
+                let Some(text) = attribs.get_value("_text") else {
+                    log_missing_attrib(parser_state, TAG, "_text");
+                    continue;
+                };
+                original_text = widgets
+                    .get_as::<WidgetLabel>(id)
+                    .map(|l| l.get_text().clone());
+                if original_text.is_none() {
+                    log_invalid_attrib(parser_state, TAG, attrib, "only <label> has text");
+                    continue;
+                }
+                BindingAction::Text(text.into())
+            }
+            "_sticky_if" => {
+                let Some(button) = button else {
+                    log_invalid_attrib(parser_state, TAG, attrib, "only <Button> can be sticky");
+                    continue;
+                };
+                BindingAction::Sticky(button.clone())
+            }
+            _ => unreachable!(),
+        };
+
+        let state = BindingState {
+            condition,
+            action,
+            original_color,
+            original_text,
+            last: Cell::new(None),
+        };
+
+        let vars = vars.clone();
+        let callback: EventCallback<AppState, S> = Box::new(move |common, data, app, _| {
+            binding_on_tick(&state, &vars.borrow(), common, data, app);
+            Ok(EventResult::Pass)
+        });
+
+        layout.add_event_listener(
+            attribs.widget_id,
+            EventListenerKind::InternalStateChange,
+            callback,
+        );
+    }
+    any
+}
+
+fn binding_on_tick(
+    state: &BindingState,
+    vars: &PanelVars,
+    common: &mut event::CallbackDataCommon,
+    data: &mut event::CallbackData,
+    app: &AppState,
+) {
+    let active = state.condition.eval(vars, app);
+    if state.last.replace(Some(active)) == Some(active) {
+        return;
+    }
+
+    match &state.action {
+        BindingAction::Show => {
+            let display = if active {
+                taffy::Display::Flex
+            } else {
+                taffy::Display::None
+            };
+            common
+                .alterables
+                .set_style(data.widget_id, StyleSetRequest::Display(display));
+            common.alterables.mark_redraw();
+        }
+        BindingAction::Color(color) => {
+            let color = if active {
+                *color
+            } else {
+                state.original_color.unwrap_or(*color)
+            };
+            if let Some(rect) = data.obj.get_as_mut::<WidgetRectangle>() {
+                rect.set_color(common, color);
+            } else if let Some(label) = data.obj.get_as_mut::<WidgetLabel>() {
+                label.set_color(common, color, true);
+            } else if let Some(sprite) = data.obj.get_as_mut::<WidgetSprite>() {
+                sprite.set_color(common, color);
+            }
+        }
+        BindingAction::Text(text) => {
+            let Some(label) = data.obj.get_as_mut::<WidgetLabel>() else {
+                return;
+            };
+            let text = if active {
+                Translation::from_raw_text(text)
+            } else {
+                state.original_text.clone().unwrap_or_default()
+            };
+            label.set_text(common, text);
+        }
+        BindingAction::Sticky(button) => button.set_sticky_state(common, active),
+    }
+}
@@ -3,12 +3,16 @@ use crate::backend::wayvr::{self, WvrServerState};
 use crate::{
     backend::input::InputState,
     ipc::{event_queue::SyncEventQueue, signal::WayVRSignal},
+    subsystem::alarms::{self, AlarmKind, AlarmManager},
 };
 use bytes::BufMut;
 use glam::Vec3A;
 use interprocess::local_socket::{self, ToNsName, traits::Listener};
 use smallvec::SmallVec;
-use std::io::{Read, Write};
+use std::{
+    io::{Read, Write},
+    sync::mpsc,
+};
 use wayvr_ipc::{
     ipc::{self},
     packet_client::{self, PacketClient},
@@ -25,6 +29,8 @@ pub struct Connection {
     conn: local_socket::Stream,
     next_packet: Option<u32>,
     auth: Option<AuthInfo>,
+    /// `WlxPanelGetValue` requests waiting for the panel to respond
+    pending_values: Vec<(ipc::Serial, mpsc::Receiver<Result<String, String>>)>,
 }
 
 pub fn send_packet(conn: &mut local_socket::Stream, data: &[u8]) -> anyhow::Result<()> {
@@ -78,6 +84,7 @@ pub struct TickParams<'a> {
     pub tasks: &'a mut Vec<wayvr::TickTask>,
     pub signals: &'a SyncEventQueue<WayVRSignal>,
     pub input_state: &'a InputState,
+    pub alarms: &'a mut AlarmManager,
 }
 
 pub fn gen_args_vec(input: &str) -> Vec<&str> {
@@ -98,6 +105,7 @@ impl Connection {
             alive: true,
             auth: None,
             next_packet: None,
+            pending_values: Vec::new(),
         }
     }
 
@@ -376,10 +384,129 @@ impl Connection {
                     packet_client::WlxModifyPanelCommand::SetColor(color) => {
                         ModifyPanelCommand::SetColor(color)
                     }
+                    packet_client::WlxModifyPanelCommand::SetValue(value) => {
+                        ModifyPanelCommand::SetValue(value)
+                    }
                 },
             }));
     }
 
+    fn handle_wlx_panel_get_value(
+        &mut self,
+        params: &mut TickParams,
+        serial: ipc::Serial,
+        value_params: packet_client::WlxPanelGetValueParams,
+    ) {
+        use crate::backend::task::{ModifyPanelCommand, ModifyPanelTask};
+
+        let (sender, receiver) = mpsc::channel();
+        params
+            .signals
+            .send(WayVRSignal::CustomTask(ModifyPanelTask {
+                overlay: value_params.overlay,
+                element: value_params.element,
+                command: ModifyPanelCommand::GetValue(sender),
+            }));
+        self.pending_values.push((serial, receiver));
+    }
+
+    fn handle_wlx_alarm_add(
+        &mut self,
+        params: &mut TickParams,
+        serial: ipc::Serial,
+        add_params: packet_client::WlxAlarmAddParams,
+    ) -> anyhow::Result<()> {
+        use packet_client::WlxAlarmWhen;
+
+        let label = add_params.label;
+        let result = match add_params.when {
+            WlxAlarmWhen::In(secs) => Ok(params.alarms.add_countdown(secs, label)),
+            WlxAlarmWhen::Every(secs) if secs < alarms::MIN_REMINDER_SECS => Err(format!(
+                "Reminders can't be more frequent than every {} seconds",
+                alarms::MIN_REMINDER_SECS
+            )),
+            WlxAlarmWhen::Every(secs) => Ok(params.alarms.add_reminder(secs, label)),
+            WlxAlarmWhen::At { hour, minute } => chrono::NaiveTime::from_hms_opt(hour, minute, 0)
+                .ok_or_else(|| format!("Invalid time {hour}:{minute:02}"))
+                .and_then(|time| {
+                    params
+                        .alarms
+                        .add_alarm_at(time, label)
+                        .map_err(|e| format!("{e:#}"))
+                }),
+        };
+
+        send_packet(
+            &mut self.conn,
+            &ipc::data_encode(&PacketServer::WlxAlarmAddResponse(serial, result)),
+        )?;
+
+        Ok(())
+    }
+
+    fn handle_wlx_alarm_list(
+        &mut self,
+        params: &TickParams,
+        serial: ipc::Serial,
+    ) -> anyhow::Result<()> {
+        let list = params
+            .alarms
+            .list()
+            .iter()
+            .map(|alarm| packet_server::WlxAlarm {
+                id: alarm.id,
+                kind: match alarm.kind {
+                    AlarmKind::Countdown => packet_server::WlxAlarmKind::Countdown,
+                    AlarmKind::Alarm => packet_server::WlxAlarmKind::Alarm,
+                    AlarmKind::Reminder => packet_server::WlxAlarmKind::Reminder,
+                },
+                label: alarm.label.clone(),
+                due: alarm.due,
+                interval: alarm.interval,
+            })
+            .collect();
+
+        send_packet(
+            &mut self.conn,
+            &ipc::data_encode(&PacketServer::WlxAlarmListResponse(
+                serial,
+                packet_server::WlxAlarmList { list },
+            )),
+        )?;
+
+        Ok(())
+    }
+
+    fn handle_wlx_alarm_remove(params: &mut TickParams, id: u32) {
+        if !params.alarms.remove(id) {
+            log::warn!("No alarm with id {id}");
+        }
+    }
+
+    fn send_pending_values(&mut self) -> anyhow::Result<()> {
+        let mut i = 0;
+        while i < self.pending_values.len() {
+            let result = match self.pending_values[i].1.try_recv() {
+                Ok(result) => result,
+                Err(mpsc::TryRecvError::Empty) => {
+                    i += 1;
+                    continue;
+                }
+                // the task was dropped without a reply
+                Err(mpsc::TryRecvError::Disconnected) => Err(String::from(
+                    "No such overlay, or it does not support commands.",
+                )),
+            };
+
+            let (serial, _) = self.pending_values.swap_remove(i);
+            send_packet(
+                &mut self.conn,
+                &ipc::data_encode(&PacketServer::WlxPanelGetValueResponse(serial, result)),
+            )?;
+        }
+        Ok(())
+    }
+
     // FIXME: we should probably respond an error to the client in case if wayland server feature is disabled
     //        fix this after we're done with the webkit-based wayvr-dashboard
     #[allow(unused_variables)]
@@ -426,6 +553,18 @@ impl Connection {
             PacketClient::WlxModifyPanel(custom_params) => {
                 Self::handle_wlx_panel(params, custom_params);
             }
+            PacketClient::WlxPanelGetValue(serial, value_params) => {
+                self.handle_wlx_panel_get_value(params, serial, value_params);
+            }
+            PacketClient::WlxAlarmAdd(serial, add_params) => {
+                self.handle_wlx_alarm_add(params, serial, add_params)?;
+            }
+            PacketClient::WlxAlarmList(serial) => {
+                self.handle_wlx_alarm_list(params, serial)?;
+            }
+            PacketClient::WlxAlarmRemove(id) => {
+                Self::handle_wlx_alarm_remove(params, id);
+            }
         }
 
         Ok(())
@@ -491,6 +630,11 @@ impl Connection {
 
     fn tick(&mut self, params: &mut TickParams) {
         while self.read_packet(params) {}
+
+        if let Err(e) = self.send_pending_values() {
+            log::error!("Could not send panel value: {e}");
+            self.alive = false;
+        }
     }
 }
 
@@ -0,0 +1,174 @@
+use std::{collections::HashMap, rc::Rc};
+
+use glam::{Affine3A, Quat, Vec3, vec3};
+use wgui::{
+    assets::AssetPath,
+    event::{CallbackDataCommon, EventAlterables, StyleSetRequest},
+    i18n::Translation,
+    log::LogErr,
+    parser::{Fetchable, ParseDocumentParams},
+    renderer_vk::text::custom_glyph::CustomGlyphData,
+    taffy,
+    widget::{image::WidgetImage, label::WidgetLabel},
+};
+use wlx_common::windowing::{OverlayWindowState, Positioning};
+
+use crate::{
+    backend::task::{OverlayTask, TaskType},
+    gui::panel::{GuiPanel, NewGuiPanelParams},
+    state::AppState,
+    subsystem::clipboard::ClipboardContent,
+    windowing::{OverlaySelector, backend::OverlayEventData, window::OverlayWindowConfig},
+};
+
+pub const CLIPBOARD_NAME: &str = "clipboard";
+
+const CLIPBOARD_XML: &str = "gui/clipboard.xml";
+
+/// Longer texts are cut off in the list
+const PREVIEW_CHARS: usize = 200;
+
+pub fn create_clipboard(app: &mut AppState) -> anyhow::Result<OverlayWindowConfig> {
+    let mut panel =
+        GuiPanel::new_from_template(app, CLIPBOARD_XML, (), NewGuiPanelParams::default())?;
+
+    let doc_params = ParseDocumentParams {
+        globals: panel.layout.state.globals.clone(),
+        path: AssetPath::FileOrBuiltIn(CLIPBOARD_XML),
+        extra: panel.doc_extra.take().unwrap_or_default(),
+    };
+
+    populate_history(&mut panel, app, &doc_params)?;
+
+    panel.on_notify = Some(Box::new(move |panel, app, event_data| {
+        if matches!(event_data, OverlayEventData::ClipboardChanged) {
+            populate_history(panel, app, &doc_params)?;
+        }
+        Ok(())
+    }));
+
+    panel.update_layout(app)?;
+
+    Ok(OverlayWindowConfig {
+        name: CLIPBOARD_NAME.into(),
+        default_state: OverlayWindowState {
+            interactable: true,
+            grabbable: true,
+            positioning: Positioning::Floating,
+            transform: Affine3A::from_scale_rotation_translation(
+                Vec3::ONE * 0.5,
+                Quat::IDENTITY,
+                vec3(0., -0.3, -0.6),
+            ),
+            ..OverlayWindowState::default()
+        },
+        global: true,
+        ..OverlayWindowConfig::from_backend(Box::new(panel))
+    })
+}
+
+/// Let the clipboard panel know that it needs to rebuild its list.
+pub fn notify_history_changed(app: &mut AppState) {
+    app.tasks.enqueue(TaskType::Overlay(OverlayTask::Modify(
+        OverlaySelector::Name(CLIPBOARD_NAME.into()),
+        Box::new(|app, owc| {
+            let _ = owc
+                .backend
+                .notify(app, OverlayEventData::ClipboardChanged)
+                .log_warn("Could not refresh clipboard history");
+        }),
+    )));
+}
+
+fn preview_text(text: &str) -> String {
+    let mut chars = text.trim().chars();
+    let mut preview: String = chars.by_ref().take(PREVIEW_CHARS).collect();
+    if chars.next().is_some() {
+        preview.push('…');
+    }
+    preview
+}
+
+fn populate_history(
+    panel: &mut GuiPanel<()>,
+    app: &mut AppState,
+    doc_params: &ParseDocumentParams,
+) -> anyhow::Result<()> {
+    let mut alterables = EventAlterables::default();
+
+    let root = panel.parser_state.get_widget_id("clipboard_root")?;
+    panel.layout.remove_children(root);
+
+    let time_format = if app.session.config.clock_12h {
+        "%I:%M %p"
+    } else {
+        "%H:%M"
+    };
+
+    let mut num_entries = 0;
+    for entry in app.clipboard.entries() {
+        num_entries += 1;
+        let id: Rc<str> = entry.id.to_string().into();
+
+        let template = match entry.content {
+            ClipboardContent::Text(_) => "TextEntry",
+            ClipboardContent::Image(_) => "ImageEntry",
+        };
+
+        let mut params: HashMap<Rc<str>, Rc<str>> = HashMap::new();
+        params.insert("id".into(), id.clone());
+        panel.parser_state.instantiate_template(
+            doc_params,
+            template,
+            &mut panel.layout,
+            root,
+            params,
+        )?;
+
+        let mut com = CallbackDataCommon {
+            alterables: &mut alterables,
+            state: &panel.layout.state,
+        };
+
+        let text = match &entry.content {
+            ClipboardContent::Text(text) => preview_text(text),
+            ClipboardContent::Image(image) => {
+                let glyph =
+                    CustomGlyphData::from_rgba(image.width, image.height, image.pixels.to_vec())?;
+                let mut widget = panel.parser_state.fetch_widget_as::<WidgetImage>(
+                    &panel.layout.state,
+                    &format!("clip_image_{id}"),
+                )?;
+                widget.set_content(&mut com, Some(glyph));
+
+                format!("{} × {}", image.width, image.height)
+            }
+        };
+
+        for (label_id, text) in [
+            (format!("clip_text_{id}"), text),
+            (
+                format!("clip_time_{id}"),
+                entry.copied.format(time_format).to_string(),
+            ),
+        ] {
+            let mut label = panel
+                .parser_state
+                .fetch_widget_as::<WidgetLabel>(&panel.layout.state, &label_id)?;
+            label.set_text(&mut com, Translation::from_raw_text(&text));
+        }
+    }
+
+    let empty_label = panel.parser_state.get_widget_id("clipboard_empty")?;
+    let display = if num_entries == 0 {
+        taffy::Display::Flex
+    } else {
+        taffy::Display::None
+    };
+    alterables.set_style(empty_label, StyleSetRequest::Display(display));
+    alterables.mark_redraw();
+
+    panel.process_custom_elems(app);
+    panel.layout.process_alterables(alterables)?;
+    Ok(())
+}
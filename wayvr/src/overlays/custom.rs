@@ -1,7 +1,8 @@
 use std::{sync::Arc, time::Duration};
 
+use anyhow::Context;
 use glam::{Affine3A, Quat, Vec3, vec3};
-use wgui::globals::expand_env_vars;
+use wgui::{assets::AssetPath, globals::expand_env_vars, parser::ParseDocumentParams};
 use wlx_common::{
     overlays::{BackendAttrib, BackendAttribValue},
     windowing::OverlayWindowState,
@@ -9,7 +10,7 @@ use wlx_common::{
 
 use crate::{
     gui::{
-        panel::{GuiPanel, NewGuiPanelParams, apply_custom_command},
+        panel::{GuiPanel, NewGuiPanelParams, apply_custom_command, device_list::DeviceList},
         timer::GuiTimer,
     },
     state::AppState,
@@ -19,18 +20,34 @@ use crate::{
     },
 };
 
-struct CustomPanelState;
+/// Panels bundled with WayVR that may be used as custom panels.
+/// Others, such as `watch`, are only ever loaded from the config directory.
+const BUILTIN_PANELS: [&str; 3] = ["media", "agenda", "trackers"];
+
+#[derive(Default)]
+struct CustomPanelState {
+    device_list: DeviceList,
+}
 
 pub fn create_custom(app: &mut AppState, name: Arc<str>) -> Option<OverlayWindowConfig> {
+    try_create_custom(app, name.clone())
+        .inspect_err(|e| log::warn!("Error creating '{name}': {e:?}"))
+        .ok()
+}
+
+pub fn try_create_custom(
+    app: &mut AppState,
+    name: Arc<str>,
+) -> anyhow::Result<OverlayWindowConfig> {
+    // a file in the config directory takes precedence over the bundled one
     let params = NewGuiPanelParams {
-        external_xml: true,
+        external_xml: !BUILTIN_PANELS.contains(&&*name),
         ..NewGuiPanelParams::default()
     };
+    let xml_path = format!("gui/{name}.xml");
 
     let mut panel =
-        GuiPanel::new_from_template(app, &format!("gui/{name}.xml"), CustomPanelState, params)
-            .inspect_err(|e| log::warn!("Error creating '{name}': {e:?}"))
-            .ok()?;
+        GuiPanel::new_from_template(app, &xml_path, CustomPanelState::default(), params)?;
 
     if let Some(icon) = panel.parser_state.data.var_map.get("_panel_icon") {
         let icon = expand_env_vars(&icon);
@@ -39,10 +56,9 @@ pub fn create_custom(app: &mut AppState, name: Arc<str>) -> Option<OverlayWindow
             .insert(BackendAttrib::Icon, BackendAttribValue::Icon(icon.into()));
     }
 
-    panel
-        .update_layout(app)
-        .inspect_err(|e| log::warn!("Error layouting '{name}': {e:?}"))
-        .ok()?;
+    populate_devices(&mut panel, app, &xml_path)?;
+
+    panel.update_layout(app).context("Error layouting")?;
 
     panel
         .timers
@@ -52,20 +68,23 @@ pub fn create_custom(app: &mut AppState, name: Arc<str>) -> Option<OverlayWindow
 
     panel.on_notify = Some(Box::new({
         let name = name.clone();
+        let xml_path = xml_path.clone();
         move |panel, app, event_data| {
-            let OverlayEventData::CustomCommand { element, command } = event_data else {
-                return Ok(());
-            };
-
-            if let Err(e) = apply_custom_command(panel, app, &element, &command) {
-                log::warn!("Could not apply {command:?} on {name}/{element}: {e:?}");
+            match event_data {
+                OverlayEventData::CustomCommand { element, command } => {
+                    if let Err(e) = apply_custom_command(panel, app, &element, &command) {
+                        log::warn!("Could not apply {command:?} on {name}/{element}: {e:?}");
+                    }
+                }
+                OverlayEventData::DevicesChanged => populate_devices(panel, app, &xml_path)?,
+                _ => {}
             }
 
             Ok(())
         }
     }));
 
-    Some(OverlayWindowConfig {
+    Ok(OverlayWindowConfig {
         name,
         category: OverlayCategory::Panel,
         default_state: OverlayWindowState {
@@ -81,3 +100,29 @@ pub fn create_custom(app: &mut AppState, name: Arc<str>) -> Option<OverlayWindow
         ..OverlayWindowConfig::from_backend(Box::new(panel))
     })
 }
+
+/// Fills `devices_root` and `trackers_root`, if the panel has them.
+fn populate_devices(
+    panel: &mut GuiPanel<CustomPanelState>,
+    app: &mut AppState,
+    xml_path: &str,
+) -> anyhow::Result<()> {
+    let doc_params = ParseDocumentParams {
+        globals: panel.layout.state.globals.clone(),
+        path: AssetPath::FileOrBuiltIn(xml_path),
+        extra: panel.doc_extra.take().unwrap_or_default(),
+    };
+
+    let changed = panel.state.device_list.populate(
+        app,
+        &mut panel.layout,
+        &mut panel.parser_state,
+        &doc_params,
+    );
+    panel.doc_extra = Some(doc_params.extra);
+
+    if changed? {
+        panel.process_custom_elems(app);
+    }
+    Ok(())
+}
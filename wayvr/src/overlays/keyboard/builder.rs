@@ -1,4 +1,4 @@
-use std::{collections::HashMap, rc::Rc, time::Duration};
+use std::{cell::Cell, collections::HashMap, rc::Rc, time::Duration};
 
 use crate::{
     app_misc,
@@ -17,21 +17,25 @@ use glam::{FloatExt, Mat4, Vec2, vec2, vec3};
 use wgui::{
     animation::{Animation, AnimationEasing},
     assets::AssetPath,
+    components::button::ComponentButton,
     drawing::{self, Color},
-    event::{self, CallbackMetadata, EventAlterables, EventListenerKind},
-    layout::LayoutUpdateParams,
+    event::{self, CallbackMetadata, EventAlterables, EventListenerKind, StyleSetRequest},
+    i18n::Translation,
+    layout::{LayoutUpdateParams, WidgetID},
     log::LogErr,
     parser::{Fetchable, ParseDocumentParams},
     renderer_vk::util,
     taffy::{self, prelude::length},
     widget::{EventResult, div::WidgetDiv, rectangle::WidgetRectangle},
 };
+use wlx_common::common::LeftRight;
 use crate::overlays::keyboard::layout::KeyData;
-use super::{KeyButtonData, KeyState, KeyboardState, handle_press, handle_release, layout::{self, KeyCapType}, handle_enter};
+use super::{KeyButtonData, KeyState, KeyboardPanelState, handle_press, handle_release, layout::{self, KeyCapType}, handle_enter};
+use super::{candidates::MAX_CANDIDATES, choose_candidate, undo_candidate};
 
 const PIXELS_PER_UNIT: f32 = 60.;
 
-fn new_doc_params(panel: &mut GuiPanel<KeyboardState>) -> ParseDocumentParams<'static> {
+fn new_doc_params(panel: &mut GuiPanel<KeyboardPanelState>) -> ParseDocumentParams<'static> {
     ParseDocumentParams {
         globals: panel.layout.state.globals.clone(),
         path: AssetPath::FileOrBuiltIn("gui/keyboard.xml"),
@@ -43,21 +47,15 @@ fn new_doc_params(panel: &mut GuiPanel<KeyboardState>) -> ParseDocumentParams<'s
 pub(super) fn create_keyboard_panel(
     app: &mut AppState,
     keymap: Option<&XkbKeymap>,
-    state: KeyboardState,
+    state: KeyboardPanelState,
     layout: &layout::Layout,
-) -> anyhow::Result<GuiPanel<KeyboardState>> {
+    side: Option<LeftRight>,
+) -> anyhow::Result<GuiPanel<KeyboardPanelState>> {
     let mut panel =
         GuiPanel::new_from_template(app, "gui/keyboard.xml", state, NewGuiPanelParams::default())?;
 
     let doc_params = new_doc_params(&mut panel);
 
-    let globals = app.wgui_globals.clone();
-
-    let (accent_color, anim_mult) = {
-        let def = globals.defaults();
-        (def.accent_color, def.animation_mult)
-    };
-
     let root = panel
         .parser_state
         .get_widget_id("keyboard_root")
@@ -65,216 +63,34 @@ pub(super) fn create_keyboard_panel(
 
     let has_altgr = keymap.as_ref().is_some_and(|m| XkbKeymap::has_altgr(m));
 
-    for row in 0..layout.key_sizes.len() {
-        let (div, _) = panel.layout.add_child(
-            root,
-            WidgetDiv::create(),
-            taffy::Style {
-                flex_direction: taffy::FlexDirection::Row,
-                ..Default::default()
-            },
-        )?;
-
-        for col in 0..layout.key_sizes[row].len() {
-            let my_size_f32 = layout.key_sizes[row][col];
-
-            let key_width = PIXELS_PER_UNIT * my_size_f32;
-            let key_height = PIXELS_PER_UNIT;
-
-            let taffy_size = taffy::Size {
-                width: length(key_width),
-                height: length(PIXELS_PER_UNIT),
-            };
-
-            let Some(key) = layout.get_key_data(keymap, has_altgr, col, row) else {
-                let _ = panel.layout.add_child(
-                    div.id,
-                    WidgetDiv::create(),
-                    taffy::Style {
-                        size: taffy_size,
-                        min_size: taffy_size,
-                        max_size: taffy_size,
-                        ..Default::default()
-                    },
-                )?;
-                continue;
-            };
-
-            let my_id: Rc<str> = Rc::from(format!("key-{row}-{col}"));
-
-            let my_modifier = match key.button_state {
-                KeyButtonData::Modifier { modifier, .. } => Some(modifier),
-                _ => None,
-            };
-
-            // todo: make this easier to maintain somehow
-            let mut params: HashMap<Rc<str>, Rc<str>> = HashMap::new();
-            params.insert(Rc::from("id"), my_id.clone());
-            params.insert(Rc::from("width"), Rc::from(key_width.to_string()));
-            params.insert(Rc::from("height"), Rc::from(key_height.to_string()));
-
-            let mut label = key.label.clone().into_iter();
-            label
-                .next()
-                .and_then(|s| params.insert("text".into(), s.into()));
-
-            match key.cap_type {
-                KeyCapType::LetterAltGr => {
-                    label
-                        .next()
-                        .and_then(|s| params.insert("text_altgr".into(), s.into()));
-                }
-                KeyCapType::Symbol => {
-                    label
-                        .next()
-                        .and_then(|s| params.insert("text_shift".into(), s.into()));
-                }
-                KeyCapType::SymbolAltGr => {
-                    label
-                        .next()
-                        .and_then(|s| params.insert("text_shift".into(), s.into()));
-                    label
-                        .next()
-                        .and_then(|s| params.insert("text_altgr".into(), s.into()));
-                }
-                _ => {}
-            }
-
-            let template_key = format!("Key{:?}", key.cap_type);
-            panel.parser_state.instantiate_template(
-                &doc_params,
-                &template_key,
-                &mut panel.layout,
-                div.id,
-                params,
+    // halves only carry the keys, and one candidate strip is enough
+    match side {
+        None => setup_swipe_bar(&mut panel, &doc_params)?,
+        Some(LeftRight::Left) => {
+            hide_widgets(&mut panel, &["keyboard_bar", "keyboard_bar_gap"])?;
+            setup_swipe_bar(&mut panel, &doc_params)?;
+        }
+        Some(LeftRight::Right) => {
+            hide_widgets(
+                &mut panel,
+                &["keyboard_bar", "keyboard_bar_gap", "swipe_bar"],
             )?;
-
-            if let Ok(widget_id) = panel.parser_state.get_widget_id(&my_id) {
-                let key_state = {
-                    let rect = panel
-                        .layout
-                        .state
-                        .widgets
-                        .get_as::<WidgetRectangle>(widget_id)
-                        .unwrap(); // want panic
-
-                    Rc::new(KeyState {
-                        button_state: key.button_state,
-                        color: rect.params.color,
-                        color2: rect.params.color2,
-                        base_border_color: rect.params.border_color,
-                        cur_border_color: rect.params.border_color.into(),
-                        border: rect.params.border,
-                        drawn_state: false.into(),
-                    })
-                };
-
-                let key_cap_type: Rc<KeyCapType> = Rc::from(key.cap_type);
-                let key_label: Rc<Vec<String>> = Rc::from(key.label);
-
-                let width_mul = 1. / my_size_f32;
-
-                panel.add_event_listener(
-                    widget_id,
-                    EventListenerKind::MouseEnter,
-                    Box::new({
-                        let k = key_state.clone();
-                        let k_label = key_label.clone();
-                        let k_cap_type = key_cap_type.clone();
-                        move |common, data, _app, state| {
-                            common.alterables.trigger_haptics();
-                            on_enter_anim(
-                                k.clone(),
-                                common,
-                                data,
-                                accent_color,
-                                anim_mult,
-                                width_mul,
-                            );
-
-                            handle_enter(&k, &k_label, &k_cap_type, state);
-                            Ok(EventResult::Pass)
-                        }
-                    }),
-                );
-                panel.add_event_listener(
-                    widget_id,
-                    EventListenerKind::MouseLeave,
-                    Box::new({
-                        let k = key_state.clone();
-                        move |common, data, _app, _state| {
-                            common.alterables.trigger_haptics();
-                            on_leave_anim(
-                                k.clone(),
-                                common,
-                                data,
-                                accent_color,
-                                anim_mult,
-                                width_mul,
-                            );
-
-                            Ok(EventResult::Pass)
-                        }
-                    }),
-                );
-                panel.add_event_listener(
-                    widget_id,
-                    EventListenerKind::MousePress,
-                    Box::new({
-                        let k = key_state.clone();
-                        let k_label = key_label.clone();
-                        let k_cap_type = key_cap_type.clone();
-                        move |common, data, app, state| {
-                            let CallbackMetadata::MouseButton(button) = data.metadata else {
-                                panic!("CallbackMetadata should contain MouseButton!");
-                            };
-
-                            handle_press(app, &k, &k_cap_type, &k_label, state, button);
-                            on_press_anim(k.clone(), common, data);
-                            Ok(EventResult::Pass)
-                        }
-                    }),
-                );
-                panel.add_event_listener(
-                    widget_id,
-                    EventListenerKind::MouseRelease,
-                    Box::new({
-                        let k = key_state.clone();
-                        let k_label = key_label.clone();
-                        let k_cap_type = key_cap_type.clone();
-                        move |common, data, app, state| {
-                            if handle_release(app, &k, &k_cap_type, state) {
-                                on_release_anim(k.clone(), common, data);
-                            }
-
-                            Ok(EventResult::Pass)
-                        }
-                    }),
-                );
-
-                if let Some(modifier) = my_modifier {
-                    panel.add_event_listener(
-                        widget_id,
-                        EventListenerKind::InternalStateChange,
-                        Box::new({
-                            let k = key_state.clone();
-                            move |common, data, _app, state| {
-                                if (state.modifiers & modifier) != 0 {
-                                    on_press_anim(k.clone(), common, data);
-                                } else {
-                                    on_release_anim(k.clone(), common, data);
-                                }
-                                Ok(EventResult::Pass)
-                            }
-                        }),
-                    );
-                }
-            } else {
-                log::warn!("No ID for key at ({row}, {col})");
-            }
         }
     }
 
+    for (name, page) in layout.pages() {
+        add_page(
+            &mut panel,
+            &doc_params,
+            root,
+            layout,
+            name,
+            page,
+            keymap,
+            has_altgr,
+        )?;
+    }
+
     panel.on_notify = Some(Box::new({
         let name = "kbd";
         move |panel, app, event_data| {
@@ -298,9 +114,18 @@ pub(super) fn create_keyboard_panel(
 
             match event_data {
                 OverlayEventData::SettingsChanged => {
-                    panel.state.alt_modifier =
+                    let mut keyboard = panel.state.keyboard.borrow_mut();
+                    keyboard.alt_modifier =
                         alt_modifier_to_key(app.session.config.keyboard_middle_click_mode);
 
+                    keyboard.predictor.configure(
+                        app.session.config.keyboard_suggestions,
+                        app.session.config.keyboard_learn_words,
+                        app.session.config.keyboard_autocorrect,
+                    );
+                    keyboard.candidates.clear();
+                    drop(keyboard);
+
                     if app.session.config.clock_12h != panel.state.clock_12h {
                         panel.state.clock_12h = app.session.config.clock_12h;
 
@@ -355,6 +180,416 @@ pub(super) fn create_keyboard_panel(
     Ok(panel)
 }
 
+fn hide_widgets(panel: &mut GuiPanel<KeyboardPanelState>, ids: &[&str]) -> anyhow::Result<()> {
+    let mut alterables = EventAlterables::default();
+    for id in ids {
+        let Ok(widget) = panel
+            .parser_state
+            .get_widget_id(id)
+            .log_warn("keyboard.xml may be out of date")
+        else {
+            continue;
+        };
+        alterables.set_style(widget, StyleSetRequest::Display(taffy::Display::None));
+    }
+    panel.layout.process_alterables(alterables)
+}
+
+/// Adds the keys of a page in a div of their own, shown while the page is active.
+/// Keys are scaled so that every page is as wide as the main one.
+#[allow(clippy::too_many_lines, clippy::too_many_arguments)]
+fn add_page(
+    panel: &mut GuiPanel<KeyboardPanelState>,
+    doc_params: &ParseDocumentParams,
+    root: WidgetID,
+    layout: &layout::Layout,
+    name: &str,
+    page: &layout::Page,
+    keymap: Option<&XkbKeymap>,
+    has_altgr: bool,
+) -> anyhow::Result<()> {
+    let globals = panel.layout.state.globals.clone();
+
+    let (accent_color, anim_mult) = {
+        let def = globals.defaults();
+        (def.accent_color, def.animation_mult)
+    };
+
+    let unit = PIXELS_PER_UNIT * layout.main.row_size / page.row_size;
+
+    let (page_div, _) = panel.layout.add_child(
+        root,
+        WidgetDiv::create(),
+        taffy::Style {
+            // keys are placed within
+            size: taffy::Size {
+                width: length(unit * page.row_size),
+                height: length(unit * page.height),
+            },
+            display: if name == layout::MAIN_PAGE {
+                taffy::Display::Flex
+            } else {
+                taffy::Display::None
+            },
+            ..Default::default()
+        },
+    )?;
+
+    let shown = Cell::new(name == layout::MAIN_PAGE);
+    let page_name = name.to_string();
+    panel.add_event_listener(
+        page_div.id,
+        EventListenerKind::InternalStateChange,
+        Box::new(move |common, _data, _app, state| {
+            let show = state.keyboard.borrow().page == page_name;
+            if shown.replace(show) == show {
+                return Ok(EventResult::Pass);
+            }
+            let display = if show {
+                taffy::Display::Flex
+            } else {
+                taffy::Display::None
+            };
+            common
+                .alterables
+                .set_style(page_div.id, StyleSetRequest::Display(display));
+            Ok(EventResult::Pass)
+        }),
+    );
+
+    for (idx, page_key) in page.keys.iter().enumerate() {
+        let Some(key) = layout.get_key_data(&page_key.key, keymap, has_altgr) else {
+            continue;
+        };
+
+        let my_modifier = match key.button_state {
+            KeyButtonData::Modifier { modifier, .. } => Some(modifier),
+            _ => None,
+        };
+
+        // one widget per rectangle, only the first one is labeled
+        let mut widgets = Vec::with_capacity(page_key.rects.len());
+        for (part, rect) in page_key.rects.iter().enumerate() {
+            let my_id: Rc<str> = Rc::from(format!("key-{name}-{idx}-{part}"));
+            let key_width = unit * rect.width;
+            let key_height = unit * rect.height;
+
+            let (div, _) = panel.layout.add_child(
+                page_div.id,
+                WidgetDiv::create(),
+                taffy::Style {
+                    position: taffy::Position::Absolute,
+                    margin: taffy::Rect {
+                        left: length(unit * rect.x),
+                        top: length(unit * rect.y),
+                        bottom: length(0.0),
+                        right: length(0.0),
+                    },
+                    ..Default::default()
+                },
+            )?;
+
+            // todo: make this easier to maintain somehow
+            let mut params: HashMap<Rc<str>, Rc<str>> = HashMap::new();
+            params.insert(Rc::from("id"), my_id.clone());
+            params.insert(Rc::from("width"), Rc::from(key_width.to_string()));
+            params.insert(Rc::from("height"), Rc::from(key_height.to_string()));
+
+            let template_key = if part == 0 {
+                let mut label = key.label.clone().into_iter();
+                label
+                    .next()
+                    .and_then(|s| params.insert("text".into(), s.into()));
+
+                match key.cap_type {
+                    KeyCapType::LetterAltGr => {
+                        label
+                            .next()
+                            .and_then(|s| params.insert("text_altgr".into(), s.into()));
+                    }
+                    KeyCapType::Symbol => {
+                        label
+                            .next()
+                            .and_then(|s| params.insert("text_shift".into(), s.into()));
+                    }
+                    KeyCapType::SymbolAltGr => {
+                        label
+                            .next()
+                            .and_then(|s| params.insert("text_shift".into(), s.into()));
+                        label
+                            .next()
+                            .and_then(|s| params.insert("text_altgr".into(), s.into()));
+                    }
+                    _ => {}
+                }
+                format!("Key{:?}", key.cap_type)
+            } else {
+                params.insert("text".into(), "".into());
+                format!("Key{:?}", KeyCapType::Other)
+            };
+
+            panel.parser_state.instantiate_template(
+                doc_params,
+                &template_key,
+                &mut panel.layout,
+                div.id,
+                params,
+            )?;
+
+            match panel.parser_state.get_widget_id(&my_id) {
+                Ok(widget_id) => widgets.push((widget_id, rect.width)),
+                Err(_) => log::warn!("No ID for key {} on page {name}", page_key.key),
+            }
+        }
+
+        let Some(&(first_widget, _)) = widgets.first() else {
+            continue;
+        };
+
+        let key_state = {
+            let rect = panel
+                .layout
+                .state
+                .widgets
+                .get_as::<WidgetRectangle>(first_widget)
+                .unwrap(); // want panic
+
+            Rc::new(KeyState {
+                button_state: key.button_state,
+                color: rect.params.color,
+                color2: rect.params.color2,
+                base_border_color: rect.params.border_color,
+                cur_border_color: rect.params.border_color.into(),
+                border: rect.params.border,
+                drawn_state: false.into(),
+            })
+        };
+
+        let key_cap_type: Rc<KeyCapType> = Rc::from(key.cap_type);
+        let key_label: Rc<Vec<String>> = Rc::from(key.label);
+
+        for (widget_id, width) in widgets {
+            let width_mul = 1. / width;
+
+            panel.add_event_listener(
+                widget_id,
+                EventListenerKind::MouseEnter,
+                Box::new({
+                    let k = key_state.clone();
+                    let k_label = key_label.clone();
+                    let k_cap_type = key_cap_type.clone();
+                    move |common, data, _app, state| {
+                        common.alterables.trigger_haptics();
+                        on_enter_anim(k.clone(), common, data, accent_color, anim_mult, width_mul);
+
+                        handle_enter(&k, &k_label, &k_cap_type, &mut state.keyboard.borrow_mut());
+                        Ok(EventResult::Pass)
+                    }
+                }),
+            );
+            panel.add_event_listener(
+                widget_id,
+                EventListenerKind::MouseLeave,
+                Box::new({
+                    let k = key_state.clone();
+                    move |common, data, _app, _state| {
+                        common.alterables.trigger_haptics();
+                        on_leave_anim(k.clone(), common, data, accent_color, anim_mult, width_mul);
+
+                        Ok(EventResult::Pass)
+                    }
+                }),
+            );
+            panel.add_event_listener(
+                widget_id,
+                EventListenerKind::MousePress,
+                Box::new({
+                    let k = key_state.clone();
+                    let k_label = key_label.clone();
+                    let k_cap_type = key_cap_type.clone();
+                    move |common, data, app, state| {
+                        let CallbackMetadata::MouseButton(button) = data.metadata else {
+                            panic!("CallbackMetadata should contain MouseButton!");
+                        };
+
+                        let keyboard = &mut state.keyboard.borrow_mut();
+                        handle_press(app, &k, &k_cap_type, &k_label, keyboard, button);
+                        on_press_anim(k.clone(), common, data);
+                        Ok(EventResult::Pass)
+                    }
+                }),
+            );
+            panel.add_event_listener(
+                widget_id,
+                EventListenerKind::MouseRelease,
+                Box::new({
+                    let k = key_state.clone();
+                    let k_label = key_label.clone();
+                    let k_cap_type = key_cap_type.clone();
+                    move |common, data, app, state| {
+                        let keyboard = &mut state.keyboard.borrow_mut();
+                        if handle_release(app, &k, &k_cap_type, keyboard) {
+                            on_release_anim(k.clone(), common, data);
+                        }
+
+                        Ok(EventResult::Pass)
+                    }
+                }),
+            );
+
+            if let Some(modifier) = my_modifier {
+                panel.add_event_listener(
+                    widget_id,
+                    EventListenerKind::InternalStateChange,
+                    Box::new({
+                        let k = key_state.clone();
+                        move |common, data, _app, state| {
+                            if (state.keyboard.borrow().modifiers & modifier) != 0 {
+                                on_press_anim(k.clone(), common, data);
+                            } else {
+                                on_release_anim(k.clone(), common, data);
+                            }
+                            Ok(EventResult::Pass)
+                        }
+                    }),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Candidate strip above the keys, for swipe typing and word suggestions.
+/// Refreshed from `KeyboardState` on each state change.
+fn setup_swipe_bar(
+    panel: &mut GuiPanel<KeyboardPanelState>,
+    doc_params: &ParseDocumentParams,
+) -> anyhow::Result<()> {
+    let Ok(bar) = panel
+        .parser_state
+        .get_widget_id("swipe_bar")
+        .log_warn("keyboard.xml may be out of date")
+    else {
+        return Ok(());
+    };
+
+    // suggestions can be turned on and off in the settings
+    let shown = Cell::new(None);
+    panel.add_event_listener(
+        bar,
+        EventListenerKind::InternalStateChange,
+        Box::new(move |common, _data, _app, state| {
+            let keyboard = state.keyboard.borrow();
+            let show = keyboard.swipe.is_enabled() || keyboard.predictor.is_enabled();
+            if shown.replace(Some(show)) == Some(show) {
+                return Ok(EventResult::Pass);
+            }
+            let display = if show {
+                taffy::Display::Flex
+            } else {
+                taffy::Display::None
+            };
+            common
+                .alterables
+                .set_style(bar, StyleSetRequest::Display(display));
+            Ok(EventResult::Pass)
+        }),
+    );
+
+    let candidates_root = panel.parser_state.get_widget_id("swipe_candidates")?;
+
+    for idx in 0..MAX_CANDIDATES {
+        let id = format!("swipe_candidate_{idx}");
+        let mut params: HashMap<Rc<str>, Rc<str>> = HashMap::new();
+        params.insert(Rc::from("id"), Rc::from(id.as_str()));
+        panel.parser_state.instantiate_template(
+            doc_params,
+            "SwipeCandidate",
+            &mut panel.layout,
+            candidates_root,
+            params,
+        )?;
+
+        let button = panel
+            .parser_state
+            .fetch_component_as::<ComponentButton>(&id)?;
+        let rect = button.get_rect();
+
+        panel.add_event_listener(
+            rect,
+            EventListenerKind::MouseRelease,
+            Box::new(move |_common, _data, app, state| {
+                choose_candidate(app, &mut state.keyboard.borrow_mut(), idx);
+                Ok(EventResult::Pass)
+            }),
+        );
+
+        let generation = Cell::new(u64::MAX);
+        panel.add_event_listener(
+            rect,
+            EventListenerKind::InternalStateChange,
+            Box::new(move |common, _data, _app, state| {
+                let keyboard = state.keyboard.borrow();
+                let candidates = &keyboard.candidates;
+                if generation.replace(candidates.generation()) == candidates.generation() {
+                    return Ok(EventResult::Pass);
+                }
+                let word = candidates.get(idx);
+                button.set_text(common, Translation::from_raw_text(word.unwrap_or_default()));
+                button.set_sticky_state(common, candidates.is_chosen(idx));
+                let display = if word.is_some() {
+                    taffy::Display::Flex
+                } else {
+                    taffy::Display::None
+                };
+                common
+                    .alterables
+                    .set_style(rect, StyleSetRequest::Display(display));
+                Ok(EventResult::Pass)
+            }),
+        );
+    }
+
+    let undo = panel
+        .parser_state
+        .fetch_component_as::<ComponentButton>("swipe_undo")?
+        .get_rect();
+
+    panel.add_event_listener(
+        undo,
+        EventListenerKind::MouseRelease,
+        Box::new(|_common, _data, app, state| {
+            undo_candidate(app, &mut state.keyboard.borrow_mut());
+            Ok(EventResult::Pass)
+        }),
+    );
+
+    let generation = Cell::new(u64::MAX);
+    panel.add_event_listener(
+        undo,
+        EventListenerKind::InternalStateChange,
+        Box::new(move |common, _data, _app, state| {
+            let keyboard = state.keyboard.borrow();
+            let candidates = &keyboard.candidates;
+            if generation.replace(candidates.generation()) == candidates.generation() {
+                return Ok(EventResult::Pass);
+            }
+            let display = if candidates.can_undo() {
+                taffy::Display::Flex
+            } else {
+                taffy::Display::None
+            };
+            common
+                .alterables
+                .set_style(undo, StyleSetRequest::Display(display));
+            Ok(EventResult::Pass)
+        }),
+    );
+
+    Ok(())
+}
+
 const BUTTON_HOVER_SCALE: f32 = 0.1;
 
 fn get_anim_transform(pos: f32, widget_size: Vec2, width_mult: f32) -> Mat4 {
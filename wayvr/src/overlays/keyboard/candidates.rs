@@ -0,0 +1,84 @@
+/// Number of words shown in the candidate strip
+pub const MAX_CANDIDATES: usize = 5;
+
+/// A candidate that has been typed. Stays replaceable until something else is typed.
+struct Committed {
+    text: String,
+    /// Index into `words`
+    chosen: usize,
+}
+
+/// Words offered in the strip above the keys, from swipe typing or word suggestions.
+#[derive(Default)]
+pub struct Candidates {
+    words: Vec<String>,
+    committed: Option<Committed>,
+    /// Bumped whenever the strip needs to be redrawn
+    generation: u64,
+}
+
+impl Candidates {
+    pub const fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&str> {
+        self.words.get(idx).map(String::as_str)
+    }
+
+    pub fn is_chosen(&self, idx: usize) -> bool {
+        self.committed.as_ref().is_some_and(|c| c.chosen == idx)
+    }
+
+    pub const fn can_undo(&self) -> bool {
+        self.committed.is_some()
+    }
+
+    /// Suggestions that haven't been typed yet.
+    pub fn offer(&mut self, words: Vec<String>) {
+        if self.committed.is_none() && self.words == words {
+            return;
+        }
+        self.words = words;
+        self.committed = None;
+        self.generation += 1;
+    }
+
+    /// `words[chosen]` has been typed as `text`.
+    pub fn committed(&mut self, words: Vec<String>, chosen: usize, text: String) {
+        self.words = words;
+        self.committed = Some(Committed { text, chosen });
+        self.generation += 1;
+    }
+
+    /// Returns the text to erase and the word to type instead.
+    pub fn choose(&mut self, idx: usize) -> Option<(String, String)> {
+        let word = self.words.get(idx)?.clone();
+        let committed = self.committed.as_mut()?;
+        if committed.chosen == idx {
+            return None;
+        }
+        committed.chosen = idx;
+        self.generation += 1;
+        Some((committed.text.clone(), word))
+    }
+
+    /// Updates the text that would be erased by an undo or a different choice.
+    pub fn retyped(&mut self, text: String) {
+        if let Some(committed) = self.committed.as_mut() {
+            committed.text = text;
+        }
+    }
+
+    /// Returns the text to erase.
+    pub fn undo(&mut self) -> Option<String> {
+        let committed = self.committed.take()?;
+        self.words.clear();
+        self.generation += 1;
+        Some(committed.text)
+    }
+
+    pub fn clear(&mut self) {
+        self.offer(vec![]);
+    }
+}
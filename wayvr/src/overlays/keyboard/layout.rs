@@ -1,11 +1,12 @@
 use std::{collections::HashMap, str::FromStr, sync::LazyLock};
 
+use anyhow::Context;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use smithay::reexports::wayland_protocols::wp::pointer_gestures::zv1::server::zwp_pointer_gesture_swipe_v1::ZwpPointerGestureSwipeV1;
-use swipe_types::types::Point;
+use wlx_common::common::LeftRight;
+
 use crate::{
-    config::{ConfigType, load_known_yaml},
+    config::{ConfigType, load_checked_yaml},
     subsystem::hid::{
         KEYS_TO_MODS, KeyType, META, NUM_LOCK, SHIFT, VirtualKey, XkbKeymap, get_key_type,
     },
@@ -16,12 +17,247 @@ use super::KeyButtonData;
 static MACRO_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^([A-Za-z0-9_-]+)(?: +(UP|DOWN))?$").unwrap()); // want panic
 
-#[derive(Debug, Deserialize, Serialize)]
-#[allow(clippy::struct_field_names)]
-pub struct Layout {
+/// Keys with this prefix switch to the page named after it
+const PAGE_PREFIX: &str = "PAGE_";
+pub(super) const MAIN_PAGE: &str = "main";
+/// In a layout row, extends the key above down into this spot
+const CONTINUE_ABOVE: &str = "^";
+/// Tolerance when comparing key positions
+const EPSILON: f32 = 0.001;
+
+/// In key units, from the top left of the page.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct KeyRect {
+    pub(super) x: f32,
+    pub(super) y: f32,
+    pub(super) width: f32,
+    pub(super) height: f32,
+}
+
+impl KeyRect {
+    const fn right(&self) -> f32 {
+        self.x + self.width
+    }
+
+    const fn bottom(&self) -> f32 {
+        self.y + self.height
+    }
+
+    fn overlaps(&self, other: &Self) -> bool {
+        self.x < other.right() - EPSILON
+            && other.x < self.right() - EPSILON
+            && self.y < other.bottom() - EPSILON
+            && other.y < self.bottom() - EPSILON
+    }
+
+    /// Whether `other` starts right below this one, sharing some of its width.
+    fn touches_top_of(&self, other: &Self) -> bool {
+        (self.bottom() - other.y).abs() < EPSILON
+            && self.x < other.right() - EPSILON
+            && other.x < self.right() - EPSILON
+    }
+}
+
+/// A key at a position of its own, outside of the rows.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PlacedKey {
+    pub(super) key: String,
+    #[serde(flatten)]
+    pub(super) rect: KeyRect,
+}
+
+/// A key and the area it covers.
+/// Keys that span rows unevenly, like ISO Enter, are made of more than one rectangle.
+#[derive(Debug, Clone)]
+pub(super) struct PageKey {
+    pub(super) key: String,
+    pub(super) rects: Vec<KeyRect>,
+}
+
+/// A set of keys, shown one at a time.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Page {
     pub(super) row_size: f32,
-    pub(super) key_sizes: Vec<Vec<f32>>,
-    pub(super) main_layout: Vec<Vec<Option<String>>>,
+    #[serde(default)]
+    key_sizes: Vec<Vec<f32>>,
+    #[serde(default, alias = "main_layout")]
+    layout: Vec<Vec<Option<String>>>,
+    #[serde(default)]
+    placed_keys: Vec<PlacedKey>,
+
+    /// From the rows and the placed keys, filled in by `post_load`
+    #[serde(skip)]
+    pub(super) keys: Vec<PageKey>,
+    /// In key units
+    #[serde(skip)]
+    pub(super) height: f32,
+}
+
+impl Page {
+    fn post_load(&mut self) -> anyhow::Result<()> {
+        if self.layout.len() != self.key_sizes.len() {
+            anyhow::bail!(
+                "has {} rows of keys, but {} rows of key_sizes",
+                self.layout.len(),
+                self.key_sizes.len()
+            );
+        }
+
+        let mut keys: Vec<PageKey> = vec![];
+        for (row, (sizes, row_keys)) in self.key_sizes.iter().zip(&self.layout).enumerate() {
+            let width: f32 = sizes.iter().sum();
+            if width > self.row_size + EPSILON {
+                anyhow::bail!(
+                    "row {row} is {width} units wide, but row_size is {}",
+                    self.row_size
+                );
+            }
+            if sizes.len() != row_keys.len() {
+                anyhow::bail!(
+                    "row {row} has {} keys, needs to have {} according to key_sizes",
+                    row_keys.len(),
+                    sizes.len()
+                );
+            }
+
+            let mut x = 0.0;
+            for (col, (&width, key)) in sizes.iter().zip(row_keys).enumerate() {
+                if width <= 0.0 {
+                    anyhow::bail!("row {row} key {col} has a size of {width}");
+                }
+                let rect = KeyRect {
+                    x,
+                    y: row as f32,
+                    width,
+                    height: 1.0,
+                };
+                x += width;
+
+                match key.as_deref() {
+                    None => {}
+                    Some(CONTINUE_ABOVE) => {
+                        if !extend_above(&mut keys, rect) {
+                            anyhow::bail!(
+                                "row {row} key {col} is {CONTINUE_ABOVE}, but there's no key above it"
+                            );
+                        }
+                    }
+                    Some(key) => keys.push(PageKey {
+                        key: key.to_string(),
+                        rects: vec![rect],
+                    }),
+                }
+            }
+        }
+
+        for placed in &self.placed_keys {
+            let rect = placed.rect;
+            if rect.x < 0.0
+                || rect.y < 0.0
+                || rect.width <= 0.0
+                || rect.height <= 0.0
+                || rect.right() > self.row_size + EPSILON
+            {
+                anyhow::bail!(
+                    "placed key {} at ({}, {}) with a size of {}x{} doesn't fit within row_size {}",
+                    placed.key,
+                    rect.x,
+                    rect.y,
+                    rect.width,
+                    rect.height,
+                    self.row_size
+                );
+            }
+            keys.push(PageKey {
+                key: placed.key.clone(),
+                rects: vec![rect],
+            });
+        }
+
+        let rects: Vec<(&str, &KeyRect)> = keys
+            .iter()
+            .flat_map(|k| k.rects.iter().map(|r| (k.key.as_str(), r)))
+            .collect();
+        for (i, (key_a, a)) in rects.iter().enumerate() {
+            if let Some((key_b, _)) = rects[i + 1..].iter().find(|(_, b)| a.overlaps(b)) {
+                anyhow::bail!("keys {key_a} and {key_b} overlap at ({}, {})", a.x, a.y);
+            }
+        }
+
+        self.height = rects
+            .iter()
+            .map(|(_, r)| r.bottom())
+            .fold(self.layout.len() as f32, f32::max);
+        self.keys = keys;
+        Ok(())
+    }
+
+    /// The keys whose first rectangle is on `side` of the middle, moved to the left edge.
+    fn half(&self, side: LeftRight) -> Self {
+        let middle = self.row_size / 2.0;
+        let mut keys: Vec<PageKey> = self
+            .keys
+            .iter()
+            .filter(|key| {
+                key.rects.first().is_some_and(|rect| {
+                    let on_left = rect.x + rect.width / 2.0 < middle;
+                    on_left == (side == LeftRight::Left)
+                })
+            })
+            .cloned()
+            .collect();
+
+        let rects = || keys.iter().flat_map(|key| &key.rects);
+        let (left, right) = if keys.is_empty() {
+            (0.0, middle)
+        } else {
+            (
+                rects().map(|rect| rect.x).fold(f32::INFINITY, f32::min),
+                rects().map(KeyRect::right).fold(0.0, f32::max),
+            )
+        };
+        for rect in keys.iter_mut().flat_map(|key| &mut key.rects) {
+            rect.x -= left;
+        }
+
+        Self {
+            row_size: right - left,
+            keys,
+            ..self.clone()
+        }
+    }
+}
+
+/// Makes the key above `rect` reach down into it. Returns false if there's no such key.
+fn extend_above(keys: &mut [PageKey], rect: KeyRect) -> bool {
+    let Some(key) = keys
+        .iter_mut()
+        .rev()
+        .find(|k| k.rects.iter().any(|r| r.touches_top_of(&rect)))
+    else {
+        return false;
+    };
+
+    // same width: just make it taller
+    if let Some(above) = key.rects.iter_mut().find(|r| {
+        r.touches_top_of(&rect)
+            && (r.x - rect.x).abs() < EPSILON
+            && (r.width - rect.width).abs() < EPSILON
+    }) {
+        above.height += rect.height;
+    } else {
+        key.rects.push(rect);
+    }
+    true
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Layout {
+    /// `row_size`, `key_sizes`, `main_layout` and `placed_keys` at the top level
+    #[serde(flatten)]
+    pub(super) main: Page,
+    #[serde(default)]
+    pub(super) pages: HashMap<String, Page>,
     pub(super) exec_commands: HashMap<String, Vec<String>>,
     pub(super) macros: HashMap<String, Vec<String>>,
     pub(super) labels: HashMap<String, Vec<String>>,
@@ -30,45 +266,49 @@ pub struct Layout {
 
 impl Layout {
     pub(super) fn load_from_disk() -> Self {
-        let mut layout = load_known_yaml::<Self>(ConfigType::Keyboard);
-        layout.post_load();
-        layout
+        load_checked_yaml::<Self>(ConfigType::Keyboard, Self::post_load)
     }
 
-    fn post_load(&mut self) {
-        for i in 0..self.key_sizes.len() {
-            let row = &self.key_sizes[i];
-            let width: f32 = row.iter().sum();
-            assert!(
-                (width - self.row_size).abs() < 0.001,
-                "Row {} has a width of {}, but the row size is {}",
-                i,
-                width,
-                self.row_size
-            );
+    fn post_load(&mut self) -> anyhow::Result<()> {
+        self.main.post_load().context("main_layout")?;
+        for (name, page) in &mut self.pages {
+            page.post_load().with_context(|| format!("page {name}"))?;
         }
+        Ok(())
+    }
 
-        for i in 0..self.main_layout.len() {
-            let row = &self.main_layout[i];
-            let width = row.len();
-            assert!(
-                (width == self.key_sizes[i].len()),
-                "Row {} has {} keys, needs to have {} according to key_sizes",
-                i,
-                width,
-                self.key_sizes[i].len()
-            );
+    /// Every page cut down to the keys on one side, for a keyboard split between the hands.
+    pub(super) fn half(&self, side: LeftRight) -> Self {
+        Self {
+            main: self.main.half(side),
+            pages: self
+                .pages
+                .iter()
+                .map(|(name, page)| (name.clone(), page.half(side)))
+                .collect(),
+            ..self.clone()
         }
     }
 
+    /// The main page first, then the others sorted by name.
+    pub(super) fn pages(&self) -> Vec<(&str, &Page)> {
+        let mut pages: Vec<(&str, &Page)> = self
+            .pages
+            .iter()
+            .filter(|(name, _)| *name != MAIN_PAGE)
+            .map(|(name, page)| (name.as_str(), page))
+            .collect();
+        pages.sort_by_key(|(name, _)| *name);
+        pages.insert(0, (MAIN_PAGE, &self.main));
+        pages
+    }
+
     pub(super) fn get_key_data(
         &self,
+        key: &str,
         keymap: Option<&XkbKeymap>,
         has_altgr: bool,
-        col: usize,
-        row: usize,
     ) -> Option<KeyData> {
-        let key = self.main_layout[row][col].as_ref()?;
         let mut label = Vec::with_capacity(3);
         let mut cap_type = KeyCapType::Letter;
         let button_state: KeyButtonData;
@@ -151,6 +391,27 @@ impl Layout {
                 log::error!("Keyboard: EXEC args empty for {key}");
                 return None;
             }
+        } else if let Some(name) = key.strip_prefix(PAGE_PREFIX) {
+            if name != MAIN_PAGE && !self.pages.contains_key(name) {
+                log::error!("Keyboard: no page named {name} for {key}");
+                return None;
+            }
+            cap_type = KeyCapType::Other;
+            if !self.labels.contains_key(key) {
+                label.push(name.to_string());
+            }
+            button_state = KeyButtonData::Page {
+                name: name.to_string(),
+            };
+        } else if !is_key_name(key) {
+            // e.g. "€" or an emoji
+            cap_type = KeyCapType::Other;
+            if !self.labels.contains_key(key) {
+                label.push(key.to_string());
+            }
+            button_state = KeyButtonData::Text {
+                text: key.to_string(),
+            };
         } else {
             log::error!("Unknown key: {key}");
             return None;
@@ -177,24 +438,28 @@ impl Layout {
         if key.len() == 1 {
             return vec![key.to_string().to_lowercase()];
         }
-        let mut key = key;
-        if key.starts_with("KP_") {
-            key = &key[3..];
-        }
-        if key.contains('_') {
-            key = key.split('_').next().unwrap_or_else(|| {
-                log::error!("keyboard.yaml: Key '{key}' must not start or end with '_'!");
-                "???"
-            });
-        }
+        let name = key.strip_prefix("KP_").unwrap_or(key);
+        // `VOLUME_UP` is labeled `Volume`
+        let name = name.split_once('_').map_or(name, |(first, _)| first);
+        let mut chars = name.chars();
+        let Some(first) = chars.next() else {
+            log::error!("keyboard.yaml: Key '{key}' must not start with '_', or needs a label!");
+            return vec!["???".into()];
+        };
         vec![format!(
             "{}{}",
-            key.chars().next().unwrap().to_uppercase(), // safe because we checked is_empty
-            &key[1..].to_lowercase()
+            first.to_uppercase(),
+            chars.as_str().to_lowercase()
         )]
     }
 }
 
+/// Virtual keys, macros, exec commands and pages. Anything else is typed as text.
+fn is_key_name(key: &str) -> bool {
+    key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && key.chars().any(|c| c.is_ascii_alphanumeric())
+}
+
 fn key_events_for_macro(macro_verbs: &Vec<String>) -> Vec<(VirtualKey, bool)> {
     let mut key_events = vec![];
     for verb in macro_verbs {
@@ -250,3 +515,10 @@ pub enum KeyCapType {
     /// Label has text in the center, e.g. Home
     Other,
 }
+
+impl KeyCapType {
+    /// Keys that type a letter, these take part in swipe typing
+    pub const fn is_letter(&self) -> bool {
+        matches!(self, Self::Letter | Self::LetterAltGr)
+    }
+}
@@ -1,8 +1,10 @@
 use std::{
-    cell::Cell,
+    cell::{Cell, RefCell},
     collections::HashMap,
     process::{Child, Command},
+    rc::Rc,
     sync::atomic::Ordering,
+    time::{Duration, Instant},
 };
 
 use crate::{
@@ -15,11 +17,13 @@ use crate::{
     overlays::keyboard::builder::create_keyboard_panel,
     state::AppState,
     subsystem::{
+        clipboard,
         dbus::DbusConnector,
         hid::{
             ALT, CTRL, KeyModifier, META, SHIFT, SUPER, VirtualKey, WheelDelta, XkbKeymap,
             get_keymap_wl, get_keymap_x11,
         },
+        input::KeyboardFocus,
     },
     windowing::{
         backend::{FrameMeta, OverlayBackend, OverlayEventData, RenderResources, ShouldRender},
@@ -27,106 +31,129 @@ use crate::{
     },
 };
 use anyhow::Context;
-use arboard::Clipboard;
 use glam::{Affine3A, Quat, Vec3, vec3};
 use regex::Regex;
 use slotmap::{SlotMap, new_key_type};
-use super_swipe_engine::SwipeEngine;
 use wgui::{
     drawing,
     event::{InternalStateChangeEvent, MouseButtonEvent, MouseButtonIndex},
 };
 use wlx_common::windowing::{OverlayWindowState, Positioning};
 use wlx_common::{
+    common::LeftRight,
     config::AltModifier,
     overlays::{BackendAttrib, BackendAttribValue},
 };
-use codes_iso_639::part_1::LanguageCode;
 use crate::overlays::keyboard::layout::KeyCapType;
-use crate::overlays::keyboard::swipe_type::{copy_text_to_primary_clipboard, create_new_swipe_engine};
+use crate::overlays::keyboard::swipe_type::SwipeState;
+use crate::overlays::keyboard::{candidates::Candidates, predict::WordPredictor};
 
 pub mod builder;
+mod candidates;
 mod layout;
+mod predict;
 mod swipe_type;
 
 pub const KEYBOARD_NAME: &str = "kbd";
+/// The right half of a split keyboard
+pub const KEYBOARD_RIGHT_NAME: &str = "kbd-right";
 const AUTO_RELEASE_MODS: [KeyModifier; 5] = [SHIFT, CTRL, ALT, SUPER, META];
 const SYSTEM_LAYOUT_ALIASES: [&str; 5] = ["mozc", "pinyin", "hangul", "sayura", "unikey"];
-
-pub fn create_keyboard(app: &mut AppState, wayland: bool) -> anyhow::Result<OverlayWindowConfig> {
+/// Meters per key unit on the halves of a split keyboard
+const SPLIT_UNIT_WIDTH: f32 = 0.02;
+/// How far out from the controller each half sits
+const SPLIT_OFFSET_X: f32 = 0.04;
+
+/// The keyboard, or the left and right halves of it if `split`.
+/// The left half is the one named `KEYBOARD_NAME`.
+pub fn create_keyboard(
+    app: &mut AppState,
+    wayland: bool,
+    split: bool,
+) -> anyhow::Result<(OverlayWindowConfig, Option<OverlayWindowConfig>)> {
     let layout = layout::Layout::load_from_disk();
-    let default_state = KeyboardState {
+    let mut predictor = WordPredictor::default();
+    predictor.configure(
+        app.session.config.keyboard_suggestions,
+        app.session.config.keyboard_learn_words,
+        app.session.config.keyboard_autocorrect,
+    );
+    let keyboard = Rc::new(RefCell::new(KeyboardState {
         modifiers: 0,
         alt_modifier: alt_modifier_to_key(app.session.config.keyboard_middle_click_mode),
         processes: vec![],
-        overlay_list: OverlayList::default(),
-        set_list: SetList::default(),
-        clock_12h: app.session.config.clock_12h,
-        swipe_engine: None,
-        current_swipe_input: String::new(),
-        is_swiping: false,
-        last_pressed_key_label: String::new(),
-        clipboard: Clipboard::new()?,
-        last_swiped_word: None
-    };
-
-    let auto_labels = layout.auto_labels.unwrap_or(true);
-
-    let width = layout.row_size * 0.05 * app.session.config.keyboard_scale;
-
-    let mut backend = KeyboardBackend {
-        layout_panels: SlotMap::default(),
-        layout_ids: HashMap::default(),
-        active_layout: KeyboardPanelKey::default(),
-        default_state,
-        wlx_layout: layout,
-        wayland,
-        re_fcitx: Regex::new(r"^keyboard-([^-]+)(?:-([^-]+))?$").unwrap(),
-    };
-
-    let mut maybe_keymap = backend
-        .get_effective_keymap()
-        .inspect_err(|e| log::warn!("{e:?}"))
-        .or_else(|_| {
-            if let Some(layout_variant) = app.session.config.default_keymap.as_ref() {
-                let mut splat = layout_variant.split('-');
-                XkbKeymap::from_layout_variant(
-                    splat.next().unwrap_or(""),
-                    splat.next().unwrap_or(""),
-                )
-                .context("invalid value for default_keymap")
-            } else {
-                anyhow::bail!("no default_keymap set")
-            }
-        })
-        .ok();
-
-    if let Some(keymap) = maybe_keymap.as_ref() {
-        app.hid_provider
-            .keymap_changed(app.wvr_server.as_mut(), keymap);
-    }
-
-    if !auto_labels {
-        maybe_keymap = None;
+        swipe: SwipeState::default(),
+        predictor,
+        candidates: Candidates::default(),
+        page: layout::MAIN_PAGE.into(),
+        held: None,
+    }));
+
+    if !split {
+        let width = layout.main.row_size * 0.05 * app.session.config.keyboard_scale;
+        let backend = KeyboardBackend::new(app, wayland, layout, keyboard, None)?;
+
+        let config = OverlayWindowConfig {
+            name: KEYBOARD_NAME.into(),
+            category: OverlayCategory::Keyboard,
+            default_state: OverlayWindowState {
+                grabbable: true,
+                positioning: Positioning::Anchored,
+                interactable: true,
+                curvature: Some(0.15),
+                transform: Affine3A::from_scale_rotation_translation(
+                    Vec3::ONE * width,
+                    Quat::from_rotation_x(-10f32.to_radians()),
+                    vec3(0.0, -0.65, -0.5),
+                ),
+                ..OverlayWindowState::default()
+            },
+            ..OverlayWindowConfig::from_backend(Box::new(backend))
+        };
+        return Ok((config, None));
     }
 
-    backend.active_layout = backend.add_new_keymap(maybe_keymap.as_ref(), app)?;
+    let left = create_half(app, wayland, &layout, keyboard.clone(), LeftRight::Left)?;
+    let right = create_half(app, wayland, &layout, keyboard, LeftRight::Right)?;
+    Ok((left, Some(right)))
+}
 
+/// Typed on with the other hand, so it's attached to the controller of `side`.
+fn create_half(
+    app: &mut AppState,
+    wayland: bool,
+    layout: &layout::Layout,
+    keyboard: Rc<RefCell<KeyboardState>>,
+    side: LeftRight,
+) -> anyhow::Result<OverlayWindowConfig> {
+    let layout = layout.half(side);
+    let width = layout.main.row_size * SPLIT_UNIT_WIDTH * app.session.config.keyboard_scale;
+    let backend = KeyboardBackend::new(app, wayland, layout, keyboard, Some(side))?;
+
+    let (name, x) = match side {
+        LeftRight::Left => (KEYBOARD_NAME, -SPLIT_OFFSET_X),
+        LeftRight::Right => (KEYBOARD_RIGHT_NAME, SPLIT_OFFSET_X),
+    };
     Ok(OverlayWindowConfig {
-        name: KEYBOARD_NAME.into(),
+        name: name.into(),
         category: OverlayCategory::Keyboard,
         default_state: OverlayWindowState {
-            grabbable: true,
-            positioning: Positioning::Anchored,
             interactable: true,
-            curvature: Some(0.15),
+            positioning: Positioning::FollowHand {
+                hand: side,
+                lerp: 1.0,
+                align_to_hmd: false,
+            },
+            // above the controller, facing up towards the eyes
             transform: Affine3A::from_scale_rotation_translation(
                 Vec3::ONE * width,
-                Quat::from_rotation_x(-10f32.to_radians()),
-                vec3(0.0, -0.65, -0.5),
+                Quat::from_rotation_x(-60f32.to_radians()),
+                vec3(x, 0.06, -0.1),
             ),
             ..OverlayWindowState::default()
         },
+        // the right half is shown along with the left one, regardless of sets
+        global: side == LeftRight::Right,
         ..OverlayWindowConfig::from_backend(Box::new(backend))
     })
 }
@@ -147,34 +174,83 @@ new_key_type! {
 }
 
 struct KeyboardBackend {
-    layout_panels: SlotMap<KeyboardPanelKey, GuiPanel<KeyboardState>>,
+    layout_panels: SlotMap<KeyboardPanelKey, GuiPanel<KeyboardPanelState>>,
     layout_ids: HashMap<String, KeyboardPanelKey>,
     active_layout: KeyboardPanelKey,
-    default_state: KeyboardState,
+    /// Shared by the panels of all keymaps, and with the other half of a split keyboard
+    keyboard: Rc<RefCell<KeyboardState>>,
+    /// Which half of a split keyboard this is
+    side: Option<LeftRight>,
     wlx_layout: layout::Layout,
     wayland: bool,
     re_fcitx: Regex,
 }
 
 impl KeyboardBackend {
+    fn new(
+        app: &mut AppState,
+        wayland: bool,
+        wlx_layout: layout::Layout,
+        keyboard: Rc<RefCell<KeyboardState>>,
+        side: Option<LeftRight>,
+    ) -> anyhow::Result<Self> {
+        let auto_labels = wlx_layout.auto_labels.unwrap_or(true);
+
+        let mut backend = Self {
+            layout_panels: SlotMap::default(),
+            layout_ids: HashMap::default(),
+            active_layout: KeyboardPanelKey::default(),
+            keyboard,
+            side,
+            wlx_layout,
+            wayland,
+            re_fcitx: Regex::new(r"^keyboard-([^-]+)(?:-([^-]+))?$").unwrap(),
+        };
+
+        let mut maybe_keymap = backend
+            .get_effective_keymap()
+            .inspect_err(|e| log::warn!("{e:?}"))
+            .or_else(|_| {
+                if let Some(layout_variant) = app.session.config.default_keymap.as_ref() {
+                    let mut splat = layout_variant.split('-');
+                    XkbKeymap::from_layout_variant(
+                        splat.next().unwrap_or(""),
+                        splat.next().unwrap_or(""),
+                    )
+                    .context("invalid value for default_keymap")
+                } else {
+                    anyhow::bail!("no default_keymap set")
+                }
+            })
+            .ok();
+
+        if let Some(keymap) = maybe_keymap.as_ref() {
+            app.hid_provider
+                .keymap_changed(app.wvr_server.as_mut(), keymap);
+        }
+
+        if !auto_labels {
+            maybe_keymap = None;
+        }
+
+        backend.active_layout = backend.add_new_keymap(maybe_keymap.as_ref(), app)?;
+        Ok(backend)
+    }
+
     fn add_new_keymap(
         &mut self,
         keymap: Option<&XkbKeymap>,
         app: &mut AppState,
     ) -> anyhow::Result<KeyboardPanelKey> {
-        let mut state = self.default_state.take();
+        self.load_language(keymap, app);
 
-        state.swipe_engine =  match create_new_swipe_engine(&keymap, &self.wlx_layout) {
-            Ok(engine) => Some(engine),
-            Err(e) => {
-                log::error!("Error occured while trying to load swipe engine: {:?}", e);
-                None
-            }
+        let state = KeyboardPanelState {
+            keyboard: self.keyboard.clone(),
+            overlay_list: OverlayList::default(),
+            set_list: SetList::default(),
+            clock_12h: app.session.config.clock_12h,
         };
-
-        log::info!("swipe engine created");
-        let panel =
-            create_keyboard_panel(app, keymap, state, &self.wlx_layout)?;
+        let panel = create_keyboard_panel(app, keymap, state, &self.wlx_layout, self.side)?;
 
         let id = self.layout_panels.insert(panel);
         if let Some(layout_name) = keymap.and_then(|k| k.get_name()) {
@@ -199,37 +275,35 @@ impl KeyboardBackend {
             if self.active_layout.eq(new_key) {
                 return Ok(false);
             }
-            self.internal_switch_keymap(*new_key, keymap);
+            self.internal_switch_keymap(*new_key, keymap, app);
         } else {
             let new_key = self.add_new_keymap(Some(keymap), app)?;
-            self.internal_switch_keymap(new_key, keymap);
+            self.internal_switch_keymap(new_key, keymap, app);
         }
         app.tasks
             .enqueue(TaskType::Overlay(OverlayTask::KeyboardChanged));
         Ok(true)
     }
 
-    fn internal_switch_keymap(&mut self, new_key: KeyboardPanelKey, keymap: &XkbKeymap) {
-        let mut state_from = self
-            .layout_panels
-            .get_mut(self.active_layout)
-            .unwrap()
-            .state
-            .take();
-
-        state_from.swipe_engine =  match create_new_swipe_engine(&Some(keymap), &self.wlx_layout) {
-            Ok(engine) => Some(engine),
-            Err(e) => {
-                log::error!("Error occured while trying to load swipe engine: {:?}", e);
-                None
-            }
-        };
+    fn internal_switch_keymap(
+        &mut self,
+        new_key: KeyboardPanelKey,
+        keymap: &XkbKeymap,
+        app: &AppState,
+    ) {
+        self.load_language(Some(keymap), app);
+        self.keyboard.borrow_mut().candidates.clear();
         self.active_layout = new_key;
+    }
 
-        self.layout_panels
-            .get_mut(self.active_layout)
-            .unwrap()
-            .state = state_from;
+    fn load_language(&self, keymap: Option<&XkbKeymap>, app: &AppState) {
+        let language = predict::pick_language(keymap, app.session.config.swipe_language.as_deref());
+        let mut keyboard = self.keyboard.borrow_mut();
+        // swiping across the halves of a split keyboard can't be followed
+        if self.side.is_none() {
+            keyboard.swipe.load(keymap, &self.wlx_layout, &language);
+        }
+        keyboard.predictor.set_language(&language);
     }
 
     fn get_effective_keymap(&mut self) -> anyhow::Result<XkbKeymap> {
@@ -270,7 +344,23 @@ impl KeyboardBackend {
         self.switch_keymap(&keymap, app)
     }
 
-    fn panel(&mut self) -> &mut GuiPanel<KeyboardState> {
+    /// Catches up with a keymap switch made by the other half of a split keyboard.
+    fn follow_keymap(&mut self, app: &mut AppState) -> bool {
+        let Some(keymap) = app.hid_provider.keymap.clone() else {
+            return false;
+        };
+        if keymap
+            .get_name()
+            .is_none_or(|name| self.layout_ids.get(name) == Some(&self.active_layout))
+        {
+            return false;
+        }
+        self.switch_keymap(&keymap, app)
+            .inspect_err(|e| log::warn!("{e:?}"))
+            .unwrap_or(false)
+    }
+
+    fn panel(&mut self) -> &mut GuiPanel<KeyboardPanelState> {
         self.layout_panels.get_mut(self.active_layout).unwrap() // want panic
     }
 }
@@ -280,23 +370,33 @@ impl OverlayBackend for KeyboardBackend {
         self.panel().init(app)
     }
     fn should_render(&mut self, app: &mut AppState) -> anyhow::Result<ShouldRender> {
-        while KEYMAP_CHANGE.swap(false, Ordering::Relaxed) {
-            if self
+        let mut switched = false;
+        while !switched && KEYMAP_CHANGE.swap(false, Ordering::Relaxed) {
+            switched = self
                 .auto_switch_keymap(app)
                 .inspect_err(|e| log::warn!("{e:?}"))
-                .unwrap_or(false)
-            {
-                let panel = self.panel();
-                if !panel.initialized {
-                    panel.init(app)?;
-                }
-                return Ok(match panel.should_render(app)? {
-                    ShouldRender::Should | ShouldRender::Can => ShouldRender::Should,
-                    ShouldRender::Unable => ShouldRender::Unable,
-                });
+                .unwrap_or(false);
+        }
+        if !switched && self.side.is_some() {
+            switched = self.follow_keymap(app);
+        }
+
+        // the halves of a split keyboard share the held key
+        if self.side != Some(LeftRight::Right) {
+            repeat_held_key(app, &mut self.keyboard.borrow_mut());
+        }
+
+        let panel = self.panel();
+        if switched {
+            if !panel.initialized {
+                panel.init(app)?;
             }
+            return Ok(match panel.should_render(app)? {
+                ShouldRender::Should | ShouldRender::Can => ShouldRender::Should,
+                ShouldRender::Unable => ShouldRender::Unable,
+            });
         }
-        self.panel().should_render(app)
+        panel.should_render(app)
     }
     fn render(&mut self, app: &mut AppState, rdr: &mut RenderResources) -> anyhow::Result<()> {
         self.panel().render(app, rdr)
@@ -305,7 +405,18 @@ impl OverlayBackend for KeyboardBackend {
         self.panel().frame_meta()
     }
     fn pause(&mut self, app: &mut AppState) -> anyhow::Result<()> {
-        self.panel().state.modifiers = 0;
+        {
+            let mut keyboard = self.keyboard.borrow_mut();
+            keyboard.modifiers = 0;
+            keyboard.held = None;
+            // the cursor may be moved while we're hidden
+            keyboard.predictor.reset();
+            keyboard.candidates.clear();
+            let _ = keyboard
+                .predictor
+                .save()
+                .inspect_err(|e| log::warn!("Could not save learned words: {e:?}"));
+        }
         app.hid_provider
             .set_modifiers_routed(app.wvr_server.as_mut(), 0);
         self.panel().pause(app)
@@ -350,49 +461,31 @@ impl OverlayBackend for KeyboardBackend {
     }
 }
 
+/// What's being typed, shared by all panels of the keyboard.
 struct KeyboardState {
     modifiers: KeyModifier,
     alt_modifier: KeyModifier,
     processes: Vec<Child>,
-    overlay_list: OverlayList,
-    set_list: SetList,
-    clock_12h: bool,
-
-    // todo move all this swipe stuff into its own class
-    swipe_engine: Option<SwipeEngine>,
-    current_swipe_input: String,
-    last_pressed_key_label: String,
-    is_swiping: bool,
-    clipboard: Clipboard,
-    last_swiped_word: Option<String>
-
+    swipe: SwipeState,
+    predictor: WordPredictor,
+    candidates: Candidates,
+    /// Name of the page of keys that's shown
+    page: String,
+    held: Option<HeldKey>,
 }
 
-macro_rules! take_and_leave_default {
-    ($what:expr) => {{
-        let mut x = Default::default();
-        std::mem::swap(&mut x, $what);
-        x
-    }};
+/// A key that's held down, repeated until it's released.
+struct HeldKey {
+    vk: VirtualKey,
+    next_repeat: Instant,
 }
 
-impl KeyboardState {
-    fn take(&mut self) -> Self {
-        Self {
-            modifiers: self.modifiers,
-            alt_modifier: self.alt_modifier,
-            processes: take_and_leave_default!(&mut self.processes),
-            overlay_list: OverlayList::default(),
-            set_list: SetList::default(),
-            clock_12h: self.clock_12h,
-            swipe_engine: None,
-            current_swipe_input: String::new(),
-            is_swiping: false,
-            last_pressed_key_label: String::new(),
-            clipboard: Clipboard::new().unwrap(),
-            last_swiped_word: None
-        }
-    }
+/// State of the panel of one keymap.
+struct KeyboardPanelState {
+    keyboard: Rc<RefCell<KeyboardState>>,
+    overlay_list: OverlayList,
+    set_list: SetList,
+    clock_12h: bool,
 }
 
 fn play_key_click(app: &mut AppState) {
@@ -423,6 +516,12 @@ enum KeyButtonData {
     Macro {
         verbs: Vec<(VirtualKey, bool)>,
     },
+    Page {
+        name: String,
+    },
+    Text {
+        text: String,
+    },
     Exec {
         program: String,
         args: Vec<String>,
@@ -431,49 +530,51 @@ enum KeyButtonData {
     },
 }
 
-fn handle_enter(key: &KeyState, key_label: &Vec<String>, key_cap_type: &KeyCapType, keyboard: &mut KeyboardState) {
-    if let Some(_) = keyboard.swipe_engine.as_ref() && *key_cap_type == KeyCapType::Letter {
-        if *key_label.iter().next().unwrap() != keyboard.last_pressed_key_label {
-            keyboard.is_swiping = true;
-        }
-        if keyboard.is_swiping {
-            match &key.button_state {
-                KeyButtonData::Key { vk, pressed } => {
-                    keyboard.current_swipe_input.push_str(&*key_label.iter().next().unwrap().to_ascii_lowercase())
-                }
-                _ => {}
-            }
-        }
+fn handle_enter(
+    key: &KeyState,
+    key_label: &[String],
+    key_cap_type: &KeyCapType,
+    keyboard: &mut KeyboardState,
+) {
+    if keyboard.swipe.is_enabled()
+        && key_cap_type.is_letter()
+        && matches!(key.button_state, KeyButtonData::Key { .. })
+        && let Some(label) = key_label.first()
+    {
+        keyboard.swipe.enter(label);
     }
 }
+
 fn handle_press(
     app: &mut AppState,
     key: &KeyState,
     key_cap_type: &KeyCapType,
-    key_label: &Vec<String>,
+    key_label: &[String],
     keyboard: &mut KeyboardState,
     button: MouseButtonEvent,
 ) {
-    keyboard.is_swiping = false;
     match &key.button_state {
         KeyButtonData::Key { vk, pressed } => {
-            if let Some(_) = keyboard.swipe_engine.as_ref() && *key_cap_type == KeyCapType::Letter {
-                let actual_label = key_label.iter().next().unwrap();
-                keyboard.last_pressed_key_label = actual_label.clone();
-                keyboard.current_swipe_input.clear();
-                keyboard.current_swipe_input.push_str(&*actual_label.to_ascii_lowercase())
-            }
-            else {
+            // key positions are only known for the main page
+            if keyboard.swipe.is_enabled()
+                && keyboard.page == layout::MAIN_PAGE
+                && key_cap_type.is_letter()
+                && let Some(label) = key_label.first()
+            {
+                keyboard.swipe.begin(label);
+            } else {
                 keyboard.modifiers |= match button.index {
                     MouseButtonIndex::Right => SHIFT,
                     MouseButtonIndex::Middle => keyboard.alt_modifier,
                     _ => 0,
                 };
+                track_typed(app, keyboard, *vk);
                 app.hid_provider
                     .set_modifiers_routed(app.wvr_server.as_mut(), keyboard.modifiers);
                 app.hid_provider
                     .send_key_routed(app.wvr_server.as_mut(), *vk, true);
                 pressed.set(true);
+                keyboard.held = hold_key(app, *vk);
                 play_key_click(app);
             }
         }
@@ -485,12 +586,24 @@ fn handle_press(
             play_key_click(app);
         }
         KeyButtonData::Macro { verbs } => {
+            keyboard.predictor.reset();
+            keyboard.candidates.clear();
             for (vk, press) in verbs {
                 app.hid_provider
                     .send_key_routed(app.wvr_server.as_mut(), *vk, *press);
             }
             play_key_click(app);
         }
+        KeyButtonData::Page { name } => {
+            keyboard.page.clone_from(name);
+            play_key_click(app);
+        }
+        KeyButtonData::Text { text } => {
+            keyboard.predictor.end_word(false);
+            keyboard.candidates.offer(keyboard.predictor.suggestions());
+            replace_text(app, keyboard.modifiers, "", text);
+            play_key_click(app);
+        }
         KeyButtonData::Exec { program, args, .. } => {
             // Reap previous processes
             keyboard
@@ -505,54 +618,32 @@ fn handle_press(
     }
 }
 
-fn handle_release(app: &mut AppState, key: &KeyState, k_cap_type: &KeyCapType, keyboard: &mut KeyboardState) -> bool {
+fn handle_release(
+    app: &mut AppState,
+    key: &KeyState,
+    k_cap_type: &KeyCapType,
+    keyboard: &mut KeyboardState,
+) -> bool {
+    // a swipe may end on any key
+    if keyboard.swipe.is_active() {
+        handle_swipe_release(app, key, k_cap_type, keyboard);
+        return true;
+    }
+
     match &key.button_state {
         KeyButtonData::Key { vk, pressed } => {
-            if let Some(engine) = keyboard.swipe_engine.as_ref() && *k_cap_type == KeyCapType::Letter {
-                if keyboard.is_swiping {
-                    if !keyboard.current_swipe_input.is_empty() {
-                        let prediction = engine.predict(&*keyboard.current_swipe_input, keyboard.last_swiped_word.as_ref().map(|x| x.as_str()), 5);
-                        keyboard.current_swipe_input.clear();
-                        println!("swipe path: {}", keyboard.current_swipe_input);
-                        println!("{:?}", prediction);
-
-                        let best_prediction = prediction.first().unwrap().word.as_ref();
-
-                        copy_text_to_primary_clipboard(best_prediction, &mut keyboard.clipboard);
-                        app.hid_provider
-                            .set_modifiers_routed(app.wvr_server.as_mut(), SHIFT);
-                        app.hid_provider
-                            .send_key_routed(app.wvr_server.as_mut(), VirtualKey::Insert, true);
-                        app.hid_provider
-                            .send_key_routed(app.wvr_server.as_mut(), VirtualKey::Insert, false);
-                        app.hid_provider
-                            .set_modifiers_routed(app.wvr_server.as_mut(), keyboard.modifiers);
-                        keyboard.last_swiped_word = Some(best_prediction.parse().unwrap())
-                    }
-                }
-                else { // pointer must have been released on the same key it was pressed on
-                    app.hid_provider
-                        .send_key_routed(app.wvr_server.as_mut(), *vk, true);
-                    pressed.set(true);
-                    app.hid_provider
-                        .send_key_routed(app.wvr_server.as_mut(), *vk, false);
-                    play_key_click(app);
-                }
+            pressed.set(false);
+            keyboard.held.take_if(|held| held.vk == *vk);
 
-            }
-            else {
-                pressed.set(false);
-
-                for m in &AUTO_RELEASE_MODS {
-                    if keyboard.modifiers & *m != 0 {
-                        keyboard.modifiers &= !*m;
-                    }
+            for m in &AUTO_RELEASE_MODS {
+                if keyboard.modifiers & *m != 0 {
+                    keyboard.modifiers &= !*m;
                 }
-                app.hid_provider
-                    .send_key_routed(app.wvr_server.as_mut(), *vk, false);
-                app.hid_provider
-                    .set_modifiers_routed(app.wvr_server.as_mut(), keyboard.modifiers);
             }
+            app.hid_provider
+                .send_key_routed(app.wvr_server.as_mut(), *vk, false);
+            app.hid_provider
+                .set_modifiers_routed(app.wvr_server.as_mut(), keyboard.modifiers);
             true
         }
         KeyButtonData::Modifier { modifier, sticky } => {
@@ -585,3 +676,175 @@ fn handle_release(app: &mut AppState, key: &KeyState, k_cap_type: &KeyCapType, k
         _ => true,
     }
 }
+
+fn handle_swipe_release(
+    app: &mut AppState,
+    key: &KeyState,
+    k_cap_type: &KeyCapType,
+    keyboard: &mut KeyboardState,
+) {
+    let was_swiping = keyboard.swipe.is_swiping();
+    if let Some(candidates) = keyboard.swipe.finish(keyboard.predictor.context()) {
+        commit_swipe(app, keyboard, candidates);
+        return;
+    }
+
+    // pointer must have been released on the same key it was pressed on
+    if !was_swiping
+        && k_cap_type.is_letter()
+        && let KeyButtonData::Key { vk, .. } = &key.button_state
+    {
+        track_typed(app, keyboard, *vk);
+        app.hid_provider
+            .send_key_routed(app.wvr_server.as_mut(), *vk, true);
+        app.hid_provider
+            .send_key_routed(app.wvr_server.as_mut(), *vk, false);
+        play_key_click(app);
+    }
+}
+
+/// Starts repeating `vk` after the configured delay.
+/// Modifiers never get here, they aren't `KeyButtonData::Key`.
+fn hold_key(app: &AppState, vk: VirtualKey) -> Option<HeldKey> {
+    let config = &app.session.config;
+    (config.keyboard_repeat_rate > 0).then(|| HeldKey {
+        vk,
+        next_repeat: Instant::now() + Duration::from_millis(config.keyboard_repeat_delay_ms.into()),
+    })
+}
+
+/// Types the held key again once it's due.
+fn repeat_held_key(app: &mut AppState, keyboard: &mut KeyboardState) {
+    let Some(held) = keyboard.held.as_mut() else {
+        return;
+    };
+    let now = Instant::now();
+    if held.next_repeat > now {
+        return;
+    }
+
+    if app.hid_provider.keyboard_focus == KeyboardFocus::WayVR {
+        // the client repeats the key on its own, from wl_keyboard.repeat_info,
+        // so there's no telling what has been typed
+        keyboard.held = None;
+        keyboard.predictor.reset();
+        keyboard.candidates.clear();
+        return;
+    }
+
+    // repeat may have been turned off while the key was held
+    let Some(period) = Duration::from_secs(1).checked_div(app.session.config.keyboard_repeat_rate)
+    else {
+        keyboard.held = None;
+        return;
+    };
+    held.next_repeat = now + period;
+    let vk = held.vk;
+
+    track_typed(app, keyboard, vk);
+    // released in between so the desktop doesn't start a repeat of its own
+    app.hid_provider
+        .send_key_routed(app.wvr_server.as_mut(), vk, false);
+    app.hid_provider
+        .send_key_routed(app.wvr_server.as_mut(), vk, true);
+}
+
+/// Follows the text typed for word suggestions. Called before `vk` is sent.
+fn track_typed(app: &mut AppState, keyboard: &mut KeyboardState, vk: VirtualKey) {
+    let predictor = &mut keyboard.predictor;
+    if !predictor.is_enabled() {
+        // the word before is only known when swiping
+        predictor.reset();
+        keyboard.candidates.clear();
+        return;
+    }
+
+    if vk == VirtualKey::BackSpace {
+        predictor.backspace();
+    } else if keyboard.modifiers & (CTRL | ALT | SUPER) != 0 {
+        // shortcuts may move the cursor
+        predictor.reset();
+    } else if matches!(vk, VirtualKey::Return | VirtualKey::KP_Enter) {
+        predictor.end_word(false);
+    } else {
+        let text = app.hid_provider.text_for_key(vk, keyboard.modifiers);
+        let mut chars = text.as_deref().unwrap_or_default().chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_alphabetic() || c == '\'' => predictor.push(c),
+            (Some(' '), None) => {
+                if let Some(fix) = predictor.correction() {
+                    // the space itself is sent by the caller
+                    let typed = predictor.word().to_string();
+                    replace_text(app, keyboard.modifiers, &typed, &fix);
+                    predictor.accept(&fix);
+                    let text = format!("{fix} ");
+                    keyboard.candidates.committed(vec![fix, typed], 0, text);
+                    return;
+                }
+                predictor.end_word(true);
+            }
+            (Some(c), None) => {
+                predictor.end_word(c.is_whitespace());
+            }
+            // arrows, dead keys and the like
+            _ => predictor.reset(),
+        }
+    }
+    keyboard.candidates.offer(predictor.suggestions());
+}
+
+/// Types the best prediction, the others are offered in the candidate strip.
+fn commit_swipe(app: &mut AppState, keyboard: &mut KeyboardState, candidates: Vec<String>) {
+    let text = format!("{} ", candidates[0]);
+    replace_text(app, keyboard.modifiers, "", &text);
+    keyboard.predictor.accept(&candidates[0]);
+    keyboard.candidates.committed(candidates, 0, text);
+}
+
+/// Swaps the word typed last for another candidate, or types a suggestion for the current word.
+fn choose_candidate(app: &mut AppState, keyboard: &mut KeyboardState, idx: usize) {
+    if keyboard.candidates.can_undo() {
+        let Some((old, word)) = keyboard.candidates.choose(idx) else {
+            return;
+        };
+        let text = format!("{word} ");
+        replace_text(app, keyboard.modifiers, &old, &text);
+        keyboard.predictor.replace_last(old.trim_end(), &word);
+        keyboard.candidates.retyped(text);
+    } else {
+        let Some(word) = keyboard.candidates.get(idx).map(str::to_string) else {
+            return;
+        };
+        let typed = keyboard.predictor.word().to_string();
+        replace_text(app, keyboard.modifiers, &typed, &format!("{word} "));
+        keyboard.predictor.accept(&word);
+        keyboard.candidates.offer(keyboard.predictor.suggestions());
+    }
+    play_key_click(app);
+}
+
+/// Erases the word typed last from the candidate strip.
+fn undo_candidate(app: &mut AppState, keyboard: &mut KeyboardState) {
+    if let Some(old) = keyboard.candidates.undo() {
+        replace_text(app, keyboard.modifiers, &old, "");
+        keyboard.predictor.undo_last(old.trim_end());
+        keyboard.candidates.offer(keyboard.predictor.suggestions());
+        play_key_click(app);
+    }
+}
+
+/// Backspaces over `old` and types `new` through the keymap, so the clipboard is left alone.
+/// If the keymap can't type `new`, such as before one is known, it's pasted instead.
+/// The held modifiers are restored afterwards.
+fn replace_text(app: &mut AppState, modifiers: KeyModifier, old: &str, new: &str) {
+    let hid = &mut app.hid_provider;
+    hid.erase_routed(app.wvr_server.as_mut(), old.chars().count());
+    if hid.can_type(new) {
+        hid.type_text_routed(app.wvr_server.as_mut(), new);
+    } else {
+        log::debug!("Keymap can't type {new:?}, pasting it.");
+        clipboard::paste_text(app, new);
+    }
+    app.hid_provider
+        .set_modifiers_routed(app.wvr_server.as_mut(), modifiers);
+}
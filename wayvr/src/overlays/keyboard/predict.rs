@@ -0,0 +1,546 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    ops::Bound,
+    path::PathBuf,
+};
+
+use anyhow::Context;
+use wlx_common::config_io;
+
+use crate::overlays::keyboard::{candidates::MAX_CANDIDATES, swipe_type::load_user_words};
+use crate::subsystem::hid::XkbKeymap;
+
+/// Used when there's no `dictionary/en.txt` in the config dir
+const BUILTIN_EN: &str = include_str!("../../res/words_en.txt");
+
+/// Score of the most frequent dictionary word
+const MAX_SCORE: u32 = 1000;
+/// Added to the score each time a word was typed, up to `MAX_LEARNED` times
+const LEARNED_BONUS: u32 = 250;
+const MAX_LEARNED: u32 = 8;
+const MAX_WORD_LEN: usize = 32;
+/// Longer words are more likely to be passphrases than something worth suggesting
+const MAX_LEARNED_LEN: usize = 16;
+/// Smaller dictionaries miss too many common words to tell typos apart from them
+const MIN_CORRECTION_DICTIONARY: usize = 5000;
+/// Shorter words are a single typo away from too many others
+const MIN_CORRECTION_LEN: usize = 4;
+
+/// Picks the configured language, or guesses it from the keymap. Defaults to English.
+pub fn pick_language(keymap: Option<&XkbKeymap>, setting: Option<&str>) -> String {
+    setting
+        .or_else(|| {
+            keymap
+                .and_then(XkbKeymap::get_name)
+                .and_then(language_for_layout)
+        })
+        .unwrap_or("en")
+        .to_lowercase()
+}
+
+/// Guesses the language from an xkb layout name, e.g. "German (Switzerland)".
+fn language_for_layout(name: &str) -> Option<&'static str> {
+    const LANGUAGES: [(&str, &str); 30] = [
+        ("English", "en"),
+        ("German", "de"),
+        ("French", "fr"),
+        ("Spanish", "es"),
+        ("Portuguese", "pt"),
+        ("Italian", "it"),
+        ("Dutch", "nl"),
+        ("Polish", "pl"),
+        ("Czech", "cs"),
+        ("Slovak", "sk"),
+        ("Slovenian", "sl"),
+        ("Croatian", "hr"),
+        ("Serbian", "sr"),
+        ("Hungarian", "hu"),
+        ("Romanian", "ro"),
+        ("Bulgarian", "bg"),
+        ("Russian", "ru"),
+        ("Ukrainian", "uk"),
+        ("Greek", "el"),
+        ("Turkish", "tr"),
+        ("Swedish", "sv"),
+        ("Norwegian", "no"),
+        ("Danish", "da"),
+        ("Finnish", "fi"),
+        ("Icelandic", "is"),
+        ("Estonian", "et"),
+        ("Latvian", "lv"),
+        ("Lithuanian", "lt"),
+        ("Irish", "ga"),
+        ("Vietnamese", "vi"),
+    ];
+
+    let first_word = name.split([' ', '(']).next()?;
+    LANGUAGES
+        .iter()
+        .find(|(layout, _)| *layout == first_word)
+        .map(|(_, code)| *code)
+}
+
+/// Words typed on the keyboard, kept in `learned_words/<language>.txt`.
+#[derive(Default)]
+struct Learned {
+    words: HashMap<String, u32>,
+    /// How often a word followed another
+    pairs: HashMap<String, HashMap<String, u32>>,
+    dirty: bool,
+}
+
+impl Learned {
+    fn add(&mut self, prev: Option<&str>, word: &str) {
+        *self.words.entry(word.to_string()).or_default() += 1;
+        if let Some(prev) = prev {
+            *self
+                .pairs
+                .entry(prev.to_string())
+                .or_default()
+                .entry(word.to_string())
+                .or_default() += 1;
+        }
+        self.dirty = true;
+    }
+
+    fn remove(&mut self, prev: Option<&str>, word: &str) {
+        fn decrement(map: &mut HashMap<String, u32>, key: &str) {
+            if let Some(count) = map.get_mut(key) {
+                *count -= 1;
+                if *count == 0 {
+                    map.remove(key);
+                }
+            }
+        }
+
+        decrement(&mut self.words, word);
+        if let Some(prev) = prev
+            && let Some(next) = self.pairs.get_mut(prev)
+        {
+            decrement(next, word);
+            if next.is_empty() {
+                self.pairs.remove(prev);
+            }
+        }
+        self.dirty = true;
+    }
+}
+
+/// Tracks the word being typed and suggests completions for it,
+/// or the next word once it's finished.
+#[derive(Default)]
+pub struct WordPredictor {
+    enabled: bool,
+    /// Whether finished words are added to `learned`
+    learn: bool,
+    autocorrect: bool,
+    language: String,
+    /// Lowercase words and how common they are, up to `MAX_SCORE`
+    dictionary: BTreeMap<String, u32>,
+    /// Only a user-supplied dictionary that's large enough is good for corrections
+    can_correct: bool,
+    /// Offered when nothing better is known
+    top_words: Vec<String>,
+    /// Letters used by the dictionary, for finding typos
+    alphabet: Vec<char>,
+    learned: Learned,
+    /// The word being typed, as typed
+    word: String,
+    /// The word before the cursor, lowercase
+    context: Option<String>,
+    /// The word before `context`, to unlearn it if it's replaced
+    before: Option<String>,
+}
+
+impl WordPredictor {
+    pub fn configure(&mut self, enabled: bool, learn: bool, autocorrect: bool) {
+        self.enabled = enabled;
+        self.learn = enabled && learn;
+        self.autocorrect = enabled && autocorrect;
+        if !enabled {
+            self.reset();
+        }
+    }
+
+    pub const fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Loads the dictionaries for `language`. Words learned in the previous language are saved.
+    pub fn set_language(&mut self, language: &str) {
+        if self.language == language {
+            return;
+        }
+        let _ = self
+            .save()
+            .inspect_err(|e| log::warn!("Could not save learned words: {e:?}"));
+
+        self.language = language.to_string();
+        let (dictionary, user_supplied) = load_dictionary(language);
+        self.can_correct = user_supplied && dictionary.len() >= MIN_CORRECTION_DICTIONARY;
+        self.dictionary = dictionary;
+        // words added for swipe typing are likely to be typed, too
+        for word in load_user_words(language) {
+            self.dictionary
+                .entry(word.to_lowercase())
+                .or_insert(MAX_SCORE / 2);
+        }
+
+        let mut by_score: Vec<(&String, &u32)> = self.dictionary.iter().collect();
+        by_score.sort_by(|(wa, a), (wb, b)| b.cmp(a).then(wa.cmp(wb)));
+        self.top_words = by_score
+            .into_iter()
+            .take(MAX_CANDIDATES)
+            .map(|(word, _)| word.clone())
+            .collect();
+
+        self.alphabet = self.dictionary.keys().flat_map(|w| w.chars()).collect();
+        self.alphabet.sort_unstable();
+        self.alphabet.dedup();
+
+        self.learned = load_learned(language);
+        self.reset();
+
+        log::info!(
+            "word suggestions loaded for '{language}' with {} words, {} learned",
+            self.dictionary.len(),
+            self.learned.words.len()
+        );
+    }
+
+    /// A letter of the current word was typed.
+    pub fn push(&mut self, c: char) {
+        self.word.push(c);
+    }
+
+    pub fn backspace(&mut self) {
+        if self.word.pop().is_none() {
+            // back into text we haven't seen
+            self.context = None;
+        }
+    }
+
+    /// The cursor went somewhere we can't follow.
+    pub fn reset(&mut self) {
+        self.word.clear();
+        self.context = None;
+        self.before = None;
+    }
+
+    pub fn word(&self) -> &str {
+        &self.word
+    }
+
+    /// The word before the cursor, if it's known.
+    pub fn context(&self) -> Option<&str> {
+        self.context.as_deref()
+    }
+
+    /// A separator was typed. Whitespace keeps the finished word as context for the next one.
+    /// Returns the finished word.
+    pub fn end_word(&mut self, keep_context: bool) -> String {
+        let word = std::mem::take(&mut self.word);
+        if word.is_empty() {
+            if !keep_context {
+                self.context = None;
+            }
+            return word;
+        }
+
+        let lower = word.to_lowercase();
+        let prev = self.context.take();
+        if is_word(&lower) {
+            if self.learn && is_learnable(&word) {
+                self.learned.add(prev.as_deref(), &lower);
+            }
+            self.context = keep_context.then_some(lower);
+        }
+        self.before = prev;
+        word
+    }
+
+    /// `word` was typed instead of the current word, followed by a space.
+    pub fn accept(&mut self, word: &str) {
+        self.word = word.to_string();
+        self.end_word(true);
+    }
+
+    /// The last finished word was changed to `word`.
+    pub fn replace_last(&mut self, old: &str, word: &str) {
+        self.forget_last(old);
+        self.word = word.to_string();
+        self.context = self.before.take();
+        self.end_word(true);
+    }
+
+    /// The last finished word was erased.
+    pub fn undo_last(&mut self, old: &str) {
+        self.forget_last(old);
+        self.word.clear();
+        self.context = self.before.take();
+    }
+
+    fn forget_last(&mut self, old: &str) {
+        let old = old.to_lowercase();
+        if self.learn && self.context.as_deref() == Some(old.as_str()) {
+            self.learned.remove(self.before.as_deref(), &old);
+        }
+    }
+
+    /// Completions of the current word, or likely next words between words.
+    pub fn suggestions(&self) -> Vec<String> {
+        if !self.enabled {
+            return vec![];
+        }
+        if self.word.is_empty() {
+            return self.next_words();
+        }
+
+        let prefix = self.word.to_lowercase();
+        let mut scored: Vec<(u32, &str)> = self
+            .dictionary
+            .range::<str, _>((Bound::Included(prefix.as_str()), Bound::Unbounded))
+            .take_while(|(word, _)| word.starts_with(&prefix))
+            .map(|(word, score)| (self.score(word, *score), word.as_str()))
+            .collect();
+        scored.extend(
+            self.learned
+                .words
+                .keys()
+                .filter(|word| word.starts_with(&prefix) && !self.dictionary.contains_key(*word))
+                .map(|word| (self.score(word, 0), word.as_str())),
+        );
+        scored.sort_by(|(a, wa), (b, wb)| b.cmp(a).then(wa.cmp(wb)));
+
+        // nothing starts like this, it's probably a typo
+        if scored.is_empty() {
+            return self
+                .closest(&prefix)
+                .map(|word| vec![match_case(&word, &self.word)])
+                .unwrap_or_default();
+        }
+
+        scored
+            .into_iter()
+            .take(MAX_CANDIDATES)
+            .map(|(_, word)| match_case(word, &self.word))
+            .collect()
+    }
+
+    /// What the current word should be replaced with when it's finished, if autocorrect is on.
+    pub fn correction(&self) -> Option<String> {
+        if !self.autocorrect || !self.can_correct || self.word.chars().count() < MIN_CORRECTION_LEN
+        {
+            return None;
+        }
+        self.closest(&self.word.to_lowercase())
+            .map(|word| match_case(&word, &self.word))
+    }
+
+    /// The most common known word one typo away from `word`, if `word` itself is unknown.
+    fn closest(&self, word: &str) -> Option<String> {
+        if !is_word(word) || self.is_known(word) {
+            return None;
+        }
+
+        let chars: Vec<char> = word.chars().collect();
+        let mut edits: Vec<String> = Vec::new();
+        for i in 0..=chars.len() {
+            let head: String = chars[..i].iter().collect();
+            let tail: String = chars[i..].iter().collect();
+            let after: String = chars.iter().skip(i + 1).collect();
+            if i < chars.len() {
+                edits.push(format!("{head}{after}"));
+            }
+            if i + 1 < chars.len() {
+                let rest: String = chars[i + 2..].iter().collect();
+                edits.push(format!("{head}{}{}{rest}", chars[i + 1], chars[i]));
+            }
+            for c in &self.alphabet {
+                edits.push(format!("{head}{c}{tail}"));
+                if i < chars.len() {
+                    edits.push(format!("{head}{c}{after}"));
+                }
+            }
+        }
+
+        edits
+            .into_iter()
+            .filter(|edit| self.is_known(edit))
+            .map(|edit| {
+                let score = self.score(&edit, self.dictionary.get(&edit).copied().unwrap_or(0));
+                (score, edit)
+            })
+            .max_by_key(|(score, _)| *score)
+            .map(|(_, edit)| edit)
+    }
+
+    fn next_words(&self) -> Vec<String> {
+        let mut scored: Vec<(u32, &str)> = self
+            .context
+            .as_ref()
+            .and_then(|prev| self.learned.pairs.get(prev))
+            .map(|next| next.iter().map(|(w, c)| (*c, w.as_str())).collect())
+            .unwrap_or_default();
+        scored.sort_by(|(a, wa), (b, wb)| b.cmp(a).then(wa.cmp(wb)));
+
+        let mut words: Vec<String> = scored.into_iter().map(|(_, w)| w.to_string()).collect();
+        for word in &self.top_words {
+            if words.len() >= MAX_CANDIDATES {
+                break;
+            }
+            if !words.contains(word) {
+                words.push(word.clone());
+            }
+        }
+        words.truncate(MAX_CANDIDATES);
+        words
+    }
+
+    /// Typing a word once could be a typo, twice is on purpose.
+    fn is_known(&self, word: &str) -> bool {
+        self.dictionary.contains_key(word) || self.learned.words.get(word).is_some_and(|c| *c > 1)
+    }
+
+    fn score(&self, word: &str, dictionary_score: u32) -> u32 {
+        let learned = self.learned.words.get(word).copied().unwrap_or(0);
+        dictionary_score + LEARNED_BONUS * learned.min(MAX_LEARNED)
+    }
+
+    /// Writes the learned words to the config dir, if there's anything new.
+    pub fn save(&mut self) -> anyhow::Result<()> {
+        if !self.learned.dirty || self.language.is_empty() {
+            return Ok(());
+        }
+        self.learned.dirty = false;
+
+        let mut content = String::new();
+        for (word, count) in &self.learned.words {
+            let _ = writeln!(content, "{count} {word}");
+        }
+        for (prev, next) in &self.learned.pairs {
+            for (word, count) in next {
+                let _ = writeln!(content, "{count} {prev} {word}");
+            }
+        }
+
+        let path = learned_path(&self.language);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("could not create {}", dir.display()))?;
+        }
+        std::fs::write(&path, content)
+            .with_context(|| format!("could not write {}", path.display()))
+    }
+}
+
+impl Drop for WordPredictor {
+    fn drop(&mut self) {
+        let _ = self
+            .save()
+            .inspect_err(|e| log::warn!("Could not save learned words: {e:?}"));
+    }
+}
+
+/// Letters and apostrophes only, no longer than `MAX_WORD_LEN`.
+fn is_word(word: &str) -> bool {
+    (2..=MAX_WORD_LEN).contains(&word.chars().count())
+        && word.chars().all(|c| c.is_alphabetic() || c == '\'')
+}
+
+/// Skips what looks more like a secret than a word: long, or with capitals in the middle.
+fn is_learnable(word: &str) -> bool {
+    let mut rest = word.chars().skip(1);
+    word.chars().count() <= MAX_LEARNED_LEN
+        && (rest.clone().all(char::is_lowercase) || rest.all(|c| !c.is_lowercase()))
+}
+
+/// Capitalizes `word` like `typed`.
+fn match_case(word: &str, typed: &str) -> String {
+    let mut typed_chars = typed.chars();
+    if !typed_chars.next().is_some_and(char::is_uppercase) {
+        return word.to_string();
+    }
+    if typed.chars().count() > 1 && typed_chars.all(char::is_uppercase) {
+        return word.to_uppercase();
+    }
+
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+fn learned_path(language: &str) -> PathBuf {
+    config_io::get_config_file_path(&format!("learned_words/{language}.txt"))
+}
+
+/// `dictionary/<language>.txt` in the config dir, one word per line, most frequent first.
+/// `#` starts a comment. Scored by rank.
+/// Also returns whether the dictionary came from the config dir.
+fn load_dictionary(language: &str) -> (BTreeMap<String, u32>, bool) {
+    let path = config_io::get_config_file_path(&format!("dictionary/{language}.txt"));
+    let (content, user_supplied) = match std::fs::read_to_string(&path) {
+        Ok(content) => (content, true),
+        Err(_) if language == "en" => (BUILTIN_EN.to_string(), false),
+        Err(_) => {
+            log::info!(
+                "No dictionary at {}, only learned words will be suggested.",
+                path.display()
+            );
+            return (BTreeMap::new(), false);
+        }
+    };
+
+    let words: Vec<String> = content
+        .lines()
+        .map(|line| {
+            line.split('#')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    let len = words.len().max(1) as u64;
+    let mut dictionary = BTreeMap::new();
+    for (rank, word) in words.into_iter().enumerate() {
+        // below MAX_SCORE, since rank < len
+        let score = MAX_SCORE - (u64::from(MAX_SCORE) * rank as u64 / len) as u32;
+        dictionary.entry(word).or_insert(score);
+    }
+    (dictionary, user_supplied)
+}
+
+/// `count word` or `count prev word` per line.
+fn load_learned(language: &str) -> Learned {
+    let mut learned = Learned::default();
+    let Ok(content) = std::fs::read_to_string(learned_path(language)) else {
+        return learned;
+    };
+
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let Some(Ok(count)) = parts.next().map(str::parse::<u32>) else {
+            continue;
+        };
+        match (parts.next(), parts.next()) {
+            (Some(word), None) => {
+                learned.words.insert(word.to_string(), count);
+            }
+            (Some(prev), Some(word)) => {
+                learned
+                    .pairs
+                    .entry(prev.to_string())
+                    .or_default()
+                    .insert(word.to_string(), count);
+            }
+            _ => {}
+        }
+    }
+    learned
+}
@@ -1,53 +1,210 @@
-use std::collections::HashMap;
-use arboard::{Clipboard, LinuxClipboardKind, SetExtLinux};
+use std::{collections::HashMap, str::FromStr};
+
 use codes_iso_639::part_1::LanguageCode;
-use futures::TryFutureExt;
-use strum::IntoEnumIterator;
 use super_swipe_engine::{EngineLoadError, SwipeEngine};
 use swipe_types::types::Point;
-use crate::overlays::keyboard::layout;
-use crate::overlays::keyboard::layout::KeyCapType;
-use crate::subsystem::hid::{get_key_type, KeyType, VirtualKey, XkbKeymap};
+use wlx_common::config_io;
+
+use crate::overlays::keyboard::{candidates::MAX_CANDIDATES, layout};
+use crate::subsystem::hid::XkbKeymap;
+
+#[derive(Default)]
+pub struct SwipeState {
+    engine: Option<SwipeEngine>,
+    /// From `swipe_words/<language>.txt` in the config dir
+    user_words: Vec<String>,
+    input: String,
+    last_pressed_key_label: String,
+    active: bool,
+    is_swiping: bool,
+}
+
+impl SwipeState {
+    /// Loads the dictionary for `language`, with the keys placed as in `layout`.
+    pub fn load(&mut self, keymap: Option<&XkbKeymap>, layout: &layout::Layout, language: &str) {
+        let point_map = build_key_to_char_point_map(keymap, layout);
+
+        self.engine = match create_new_swipe_engine(language, point_map) {
+            Ok(engine) => Some(engine),
+            Err(e) => {
+                log::error!("Error occured while trying to load swipe engine: {e:?}");
+                None
+            }
+        };
+        self.user_words = load_user_words(language);
+        log::info!(
+            "swipe engine created for '{language}' with {} user words",
+            self.user_words.len()
+        );
+    }
+
+    pub const fn is_enabled(&self) -> bool {
+        self.engine.is_some() || !self.user_words.is_empty()
+    }
+
+    /// A letter key is being held, the pointer path is recorded.
+    pub const fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub const fn is_swiping(&self) -> bool {
+        self.is_swiping
+    }
+
+    /// Pointer went down on a letter key.
+    pub fn begin(&mut self, label: &str) {
+        self.active = true;
+        self.is_swiping = false;
+        self.last_pressed_key_label = label.to_string();
+        self.input.clear();
+        self.input.push_str(&label.to_lowercase());
+    }
+
+    /// Pointer moved onto a letter key.
+    pub fn enter(&mut self, label: &str) {
+        if !self.active {
+            return;
+        }
+        if label != self.last_pressed_key_label {
+            self.is_swiping = true;
+        }
+        if self.is_swiping {
+            self.input.push_str(&label.to_lowercase());
+        }
+    }
+
+    /// Pointer was released. Returns the predictions, best first, if this was a swipe.
+    /// `context` is the word before, if any.
+    pub fn finish(&mut self, context: Option<&str>) -> Option<Vec<String>> {
+        self.active = false;
+        let input = std::mem::take(&mut self.input);
+        if !std::mem::take(&mut self.is_swiping) || input.is_empty() {
+            return None;
+        }
+
+        let predictions: Vec<String> = self
+            .engine
+            .as_ref()
+            .map(|engine| {
+                engine
+                    .predict(&input, context, MAX_CANDIDATES)
+                    .into_iter()
+                    .map(|p| p.word.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let predictions = merge_user_words(predictions, &self.user_words, &input);
+        log::debug!("swipe path: {input}, predictions: {predictions:?}");
+
+        (!predictions.is_empty()).then_some(predictions)
+    }
+}
+
+fn create_new_swipe_engine(
+    language: &str,
+    point_map: HashMap<char, Point>,
+) -> Result<SwipeEngine, EngineLoadError> {
+    let code = LanguageCode::from_str(language).unwrap_or_else(|_| {
+        log::warn!("Unknown swipe language '{language}', using English.");
+        LanguageCode::En
+    });
+
+    match SwipeEngine::new(code, Some(point_map.clone())) {
+        Err(e) if code != LanguageCode::En => {
+            log::warn!("No swipe dictionary for '{language}', using English: {e:?}");
+            SwipeEngine::new(LanguageCode::En, Some(point_map))
+        }
+        result => result,
+    }
+}
+
+/// One word per line, `#` starts a comment.
+pub fn load_user_words(language: &str) -> Vec<String> {
+    let path = config_io::get_config_file_path(&format!("swipe_words/{language}.txt"));
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return vec![];
+    };
 
-pub fn copy_text_to_primary_clipboard(text: &str, clip: &mut Clipboard) {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// How many keys of `word` are found in order along the swipe `path`.
+/// The word has to start and end where the path does.
+fn fit_to_path(word: &str, path: &[char]) -> Option<usize> {
+    let mut keys: Vec<char> = word.chars().flat_map(char::to_lowercase).collect();
+    keys.dedup(); // double letters are a single key
+
+    if keys.first() != path.first() || keys.last() != path.last() {
+        return None;
+    }
 
-    println!("{}", std::env::var("WAYLAND_DISPLAY").unwrap());
-    clip.set_text(format!("{text} ")).unwrap();
+    let mut remaining = path.iter();
+    keys.iter()
+        .all(|key| remaining.any(|c| c == key))
+        .then_some(keys.len())
 }
-pub fn create_new_swipe_engine(keymap: &Option<&XkbKeymap>, layout: &layout::Layout) -> Result<SwipeEngine, EngineLoadError> {
-    let layout_name = keymap.and_then(|k| k.get_name()).unwrap_or("us");
-    let point_map = build_key_to_char_point_map(*keymap, layout);
 
-    // todo: use the layout_name to choose a sensible language for the swipe engine
-    SwipeEngine::new(LanguageCode::En, Some(point_map))
+/// User words that explain more of the path than the engine's best guess come first,
+/// the others are offered right after it.
+fn merge_user_words(predictions: Vec<String>, user_words: &[String], input: &str) -> Vec<String> {
+    let path: Vec<char> = input.chars().collect();
+
+    let mut fitting: Vec<(usize, &String)> = user_words
+        .iter()
+        .filter_map(|word| fit_to_path(word, &path).map(|fit| (fit, word)))
+        .collect();
+    fitting.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+    let best_fit = predictions
+        .first()
+        .and_then(|word| fit_to_path(word, &path))
+        .unwrap_or(0);
+    let (better, worse): (Vec<_>, Vec<_>) =
+        fitting.into_iter().partition(|(fit, _)| *fit > best_fit);
+
+    let mut predictions = predictions.into_iter();
+    let mut merged: Vec<String> = better.into_iter().map(|(_, word)| word.clone()).collect();
+    merged.extend(predictions.next());
+    merged.extend(worse.into_iter().map(|(_, word)| word.clone()));
+    merged.extend(predictions);
+
+    let mut seen = std::collections::HashSet::new();
+    merged.retain(|word| seen.insert(word.to_lowercase()));
+    merged.truncate(MAX_CANDIDATES);
+    merged
 }
-fn build_key_to_char_point_map(keymap: Option<&XkbKeymap>, layout: &layout::Layout) -> HashMap<char, Point> {
+
+fn build_key_to_char_point_map(
+    keymap: Option<&XkbKeymap>,
+    layout: &layout::Layout,
+) -> HashMap<char, Point> {
     let mut map = HashMap::new();
 
     let has_altgr = keymap.as_ref().is_some_and(|m| XkbKeymap::has_altgr(m));
-    let mut pos_x: f32 = 0.0;
-    let mut pos_y: f32 = 0.0;
-
-    for (row_idx, row) in layout.main_layout.iter().enumerate() {
-        for (col_idx, col) in row.iter().enumerate() {
-
-            let label = layout.get_key_data(keymap, has_altgr, col_idx, row_idx);
-            if let Some(label) = label {
-                match label.cap_type {
-                    KeyCapType::Letter => {
-                        if let Some(char) = label.label.iter().next() {
-                            let point = Point {x: pos_x as f64, y: pos_y as f64};
-                            println!("char: {} \n point: {:?}\n",char, point);
-                            map.insert(char.to_ascii_lowercase().chars().next().unwrap(), point);
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            pos_x += layout.key_sizes[row_idx][col_idx];
+
+    for page_key in &layout.main.keys {
+        let Some(rect) = page_key.rects.first() else {
+            continue;
+        };
+        let key = layout.get_key_data(&page_key.key, keymap, has_altgr);
+        if let Some(key) = key
+            && key.cap_type.is_letter()
+            && let Some(c) = key
+                .label
+                .first()
+                .and_then(|l| l.to_lowercase().chars().next())
+        {
+            let point = Point {
+                x: f64::from(rect.x),
+                y: f64::from(rect.y),
+            };
+            map.insert(c, point);
         }
-        pos_y += 1.0;
-        pos_x = 0.0;
     }
     map
 }
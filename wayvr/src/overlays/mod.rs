@@ -1,8 +1,11 @@
 pub mod anchor;
+pub mod clipboard;
 pub mod custom;
 pub mod dashboard;
 pub mod edit;
 pub mod keyboard;
+pub mod notifications;
+pub mod quick_settings;
 pub mod screen;
 pub mod toast;
 pub mod watch;
@@ -0,0 +1,192 @@
+use std::{collections::HashMap, rc::Rc};
+
+use glam::{Affine3A, Quat, Vec3, vec3};
+use wgui::{
+    assets::AssetPath,
+    event::{CallbackDataCommon, EventAlterables, StyleSetRequest},
+    i18n::Translation,
+    log::LogErr,
+    parser::{Fetchable, ParseDocumentParams},
+    taffy,
+    widget::label::WidgetLabel,
+};
+use wlx_common::windowing::{OverlayWindowState, Positioning};
+
+use crate::{
+    backend::task::{OverlayTask, TaskType},
+    gui::panel::{GuiPanel, NewGuiPanelParams},
+    state::AppState,
+    subsystem::{input::KeyboardFocus, notifications::INLINE_REPLY_KEY},
+    windowing::{OverlaySelector, backend::OverlayEventData, window::OverlayWindowConfig},
+};
+
+pub const NOTIFICATIONS_NAME: &str = "notifications";
+
+const NOTIFICATIONS_XML: &str = "gui/notifications.xml";
+
+pub fn create_notifications(app: &mut AppState) -> anyhow::Result<OverlayWindowConfig> {
+    let mut panel =
+        GuiPanel::new_from_template(app, NOTIFICATIONS_XML, (), NewGuiPanelParams::default())?;
+
+    let doc_params = ParseDocumentParams {
+        globals: panel.layout.state.globals.clone(),
+        path: AssetPath::FileOrBuiltIn(NOTIFICATIONS_XML),
+        extra: panel.doc_extra.take().unwrap_or_default(),
+    };
+
+    populate_history(&mut panel, app, &doc_params)?;
+
+    panel.on_notify = Some(Box::new(move |panel, app, event_data| {
+        if matches!(event_data, OverlayEventData::NotificationsChanged) {
+            populate_history(panel, app, &doc_params)?;
+        }
+        Ok(())
+    }));
+
+    panel.update_layout(app)?;
+
+    Ok(OverlayWindowConfig {
+        name: NOTIFICATIONS_NAME.into(),
+        default_state: OverlayWindowState {
+            interactable: true,
+            grabbable: true,
+            positioning: Positioning::Floating,
+            transform: Affine3A::from_scale_rotation_translation(
+                Vec3::ONE * 0.5,
+                Quat::IDENTITY,
+                vec3(0., -0.1, -0.6),
+            ),
+            ..OverlayWindowState::default()
+        },
+        // typing into the reply field
+        keyboard_focus: Some(KeyboardFocus::Panel),
+        global: true,
+        ..OverlayWindowConfig::from_backend(Box::new(panel))
+    })
+}
+
+/// Let the notifications panel know that it needs to rebuild its list.
+pub fn notify_history_changed(app: &mut AppState) {
+    app.tasks.enqueue(TaskType::Overlay(OverlayTask::Modify(
+        OverlaySelector::Name(NOTIFICATIONS_NAME.into()),
+        Box::new(|app, owc| {
+            let _ = owc
+                .backend
+                .notify(app, OverlayEventData::NotificationsChanged)
+                .log_warn("Could not refresh notifications");
+        }),
+    )));
+}
+
+fn populate_history(
+    panel: &mut GuiPanel<()>,
+    app: &mut AppState,
+    doc_params: &ParseDocumentParams,
+) -> anyhow::Result<()> {
+    let mut alterables = EventAlterables::default();
+
+    let root = panel.parser_state.get_widget_id("notifications_root")?;
+    panel.layout.remove_children(root);
+
+    let time_format = if app.session.config.clock_12h {
+        "%I:%M %p"
+    } else {
+        "%H:%M"
+    };
+
+    let mut num_entries = 0;
+    for entry in app.notification_history.entries() {
+        num_entries += 1;
+        let id: Rc<str> = entry.id.to_string().into();
+
+        let mut params: HashMap<Rc<str>, Rc<str>> = HashMap::new();
+        params.insert("id".into(), id.clone());
+        panel.parser_state.instantiate_template(
+            doc_params,
+            "Notification",
+            &mut panel.layout,
+            root,
+            params,
+        )?;
+
+        let mut com = CallbackDataCommon {
+            alterables: &mut alterables,
+            state: &panel.layout.state,
+        };
+
+        for (label_id, text) in [
+            (format!("notif_title_{id}"), entry.title.clone()),
+            (format!("notif_body_{id}"), entry.body.clone()),
+            (
+                format!("notif_time_{id}"),
+                entry.received.format(time_format).to_string(),
+            ),
+        ] {
+            let mut label = panel
+                .parser_state
+                .fetch_widget_as::<WidgetLabel>(&panel.layout.state, &label_id)?;
+            label.set_text(&mut com, Translation::from_raw_text(&text));
+        }
+
+        // without an origin, there's no one to send actions to
+        if !entry.can_invoke(&app.dbus) {
+            continue;
+        }
+
+        let actions_root = panel
+            .parser_state
+            .get_widget_id(&format!("notif_actions_{id}"))?;
+
+        for action in &entry.actions {
+            if &*action.key == INLINE_REPLY_KEY {
+                continue;
+            }
+            let label = if action.label.is_empty() {
+                &action.key
+            } else {
+                &action.label
+            };
+
+            let mut params: HashMap<Rc<str>, Rc<str>> = HashMap::new();
+            params.insert("id".into(), id.clone());
+            params.insert("key".into(), action.key.as_ref().into());
+            params.insert("label".into(), label.as_ref().into());
+            panel.parser_state.instantiate_template(
+                doc_params,
+                "Action",
+                &mut panel.layout,
+                actions_root,
+                params,
+            )?;
+        }
+
+        if entry.can_reply(&app.dbus) {
+            let reply_root = panel
+                .parser_state
+                .get_widget_id(&format!("notif_reply_{id}"))?;
+
+            let mut params: HashMap<Rc<str>, Rc<str>> = HashMap::new();
+            params.insert("id".into(), id.clone());
+            panel.parser_state.instantiate_template(
+                doc_params,
+                "Reply",
+                &mut panel.layout,
+                reply_root,
+                params,
+            )?;
+        }
+    }
+
+    let empty_label = panel.parser_state.get_widget_id("notifications_empty")?;
+    let display = if num_entries == 0 {
+        taffy::Display::Flex
+    } else {
+        taffy::Display::None
+    };
+    alterables.set_style(empty_label, StyleSetRequest::Display(display));
+    alterables.mark_redraw();
+
+    panel.process_custom_elems(app);
+    panel.layout.process_alterables(alterables)?;
+    Ok(())
+}
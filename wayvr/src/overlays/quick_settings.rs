@@ -0,0 +1,38 @@
+use glam::{Affine3A, Quat, Vec3, vec3};
+use wlx_common::windowing::{OverlayWindowState, Positioning};
+
+use crate::{
+    gui::panel::{GuiPanel, NewGuiPanelParams},
+    state::AppState,
+    windowing::window::OverlayWindowConfig,
+};
+
+pub const QUICK_SETTINGS_NAME: &str = "quick_settings";
+
+const QUICK_SETTINGS_XML: &str = "gui/quick_settings.xml";
+
+/// Compact panel with the most commonly changed settings.
+/// The controls are bound using `::ConfigSet`, so everything applies instantly.
+pub fn create_quick_settings(app: &mut AppState) -> anyhow::Result<OverlayWindowConfig> {
+    let mut panel =
+        GuiPanel::new_from_template(app, QUICK_SETTINGS_XML, (), NewGuiPanelParams::default())?;
+
+    panel.update_layout(app)?;
+
+    Ok(OverlayWindowConfig {
+        name: QUICK_SETTINGS_NAME.into(),
+        default_state: OverlayWindowState {
+            interactable: true,
+            grabbable: true,
+            positioning: Positioning::Floating,
+            transform: Affine3A::from_scale_rotation_translation(
+                Vec3::ONE * 0.5,
+                Quat::IDENTITY,
+                vec3(0., -0.1, -0.6),
+            ),
+            ..OverlayWindowState::default()
+        },
+        global: true,
+        ..OverlayWindowConfig::from_backend(Box::new(panel))
+    })
+}
@@ -1,29 +1,64 @@
 use std::{
-    ops::Add,
+    collections::HashMap,
+    path::PathBuf,
+    rc::Rc,
     sync::{Arc, LazyLock},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use anyhow::Context;
 use glam::{Affine3A, Quat, Vec3, vec3};
-use wgui::{i18n::Translation, widget::label::WidgetLabel};
+use wgui::{
+    assets::AssetPath,
+    event::{CallbackDataCommon, EventAlterables, StyleSetRequest},
+    i18n::Translation,
+    parser::{Fetchable, ParseDocumentParams},
+    renderer_vk::text::custom_glyph::CustomGlyphData,
+    taffy,
+    widget::{image::WidgetImage, label::WidgetLabel},
+};
 use wlx_common::{
-    common::LeftRight,
-    overlays::{ToastDisplayMethod, ToastTopic},
+    overlays::{NotificationUrgency, ToastDisplayMethod, ToastTopic},
     windowing::{OverlayWindowState, Positioning},
 };
 
 use crate::{
-    backend::task::{OverlayTask, TaskType},
+    backend::{
+        input::Haptics,
+        task::{InputTask, OverlayTask, TaskType},
+    },
     gui::panel::{GuiPanel, NewGuiPanelParams, OnCustomIdFunc},
+    overlays::notifications::NOTIFICATIONS_NAME,
     state::AppState,
+    subsystem::notifications::{DbusNotificationOrigin, INLINE_REPLY_KEY, NotificationAction},
     windowing::{OverlaySelector, Z_ORDER_TOAST, window::OverlayWindowConfig},
 };
 
 const FONT_SIZE: isize = 16;
 const PADDING: (f32, f32) = (25., 7.);
 const PIXELS_TO_METERS: f32 = 1. / 2000.;
+/// Must match the width of `toast_preview` in toast.xml
+const PREVIEW_WIDTH: f32 = 436.;
 static TOAST_NAME: LazyLock<Arc<str>> = LazyLock::new(|| "toast".into());
+const TOAST_HAPTICS: Haptics = Haptics {
+    intensity: 0.5,
+    duration: 0.15,
+    frequency: 5.0,
+};
+
+/// Decoded on the main thread when the toast is shown.
+pub enum ToastIcon {
+    /// File path, or a name to look up in the icon theme
+    Named(String),
+    /// Encoded image file (png, jpeg, etc.)
+    Encoded(Vec<u8>),
+    /// Raw RGBA8 pixels
+    Rgba {
+        width: u32,
+        height: u32,
+        data: Vec<u8>,
+    },
+}
 
 pub struct Toast {
     pub title: String,
@@ -32,6 +67,25 @@ pub struct Toast {
     pub timeout: f32,
     pub sound: bool,
     pub topic: ToastTopic,
+    pub icon: Option<ToastIcon>,
+    pub actions: Vec<NotificationAction>,
+    pub origin: Option<DbusNotificationOrigin>,
+    pub history_id: Option<usize>,
+    /// Sending application, if known
+    pub app_name: Option<String>,
+    pub urgency: Option<NotificationUrgency>,
+    /// Overrides the display method of the topic
+    pub display: Option<ToastDisplayMethod>,
+    pub haptics: bool,
+    /// Played instead of the default toast sound
+    pub sound_file: Option<PathBuf>,
+    pub volume: f32,
+    /// Minimum height in pixels
+    pub height: Option<f32>,
+    /// Shown even during do-not-disturb
+    pub always_show: bool,
+    /// Larger image below the body, e.g. of a screenshot
+    pub preview: Option<ToastIcon>,
 }
 
 #[allow(dead_code)]
@@ -44,6 +98,19 @@ impl Toast {
             timeout: 3.0,
             sound: false,
             topic,
+            icon: None,
+            actions: Vec::new(),
+            origin: None,
+            history_id: None,
+            app_name: None,
+            urgency: None,
+            display: None,
+            haptics: false,
+            sound_file: None,
+            volume: 1.0,
+            height: None,
+            always_show: false,
+            preview: None,
         }
     }
     pub const fn with_timeout(mut self, timeout: f32) -> Self {
@@ -58,17 +125,96 @@ impl Toast {
         self.sound = sound;
         self
     }
+    pub fn with_icon(mut self, icon: Option<ToastIcon>) -> Self {
+        self.icon = icon;
+        self
+    }
+    pub fn with_actions(mut self, actions: Vec<NotificationAction>) -> Self {
+        self.actions = actions;
+        self
+    }
+    pub fn with_app_name(mut self, app_name: Option<String>) -> Self {
+        self.app_name = app_name;
+        self
+    }
+    pub const fn with_urgency(mut self, urgency: Option<NotificationUrgency>) -> Self {
+        self.urgency = urgency;
+        self
+    }
+    pub fn with_sound_file(mut self, sound_file: Option<PathBuf>) -> Self {
+        self.sound_file = sound_file;
+        self
+    }
+    pub const fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+    pub const fn with_height(mut self, height: Option<f32>) -> Self {
+        self.height = height;
+        self
+    }
+    pub const fn with_always_show(mut self, always_show: bool) -> Self {
+        self.always_show = always_show;
+        self
+    }
+    pub fn with_preview(mut self, preview: Option<ToastIcon>) -> Self {
+        self.preview = preview;
+        self
+    }
+    pub fn display_method(&self, app: &AppState) -> ToastDisplayMethod {
+        self.display.unwrap_or_else(|| {
+            app.session
+                .toast_topics
+                .get(self.topic)
+                .copied()
+                .unwrap_or(ToastDisplayMethod::Hide)
+        })
+    }
     pub fn submit(self, app: &mut AppState) {
         self.submit_at(app, Instant::now());
     }
     pub fn submit_at(self, app: &mut AppState, instant: Instant) {
         let selector = OverlaySelector::Name(TOAST_NAME.clone());
 
-        let destroy_at = instant.add(std::time::Duration::from_secs_f32(self.timeout));
+        // never for an infinite timeout
+        let destroy_at = Duration::try_from_secs_f32(self.timeout)
+            .ok()
+            .and_then(|timeout| instant.checked_add(timeout));
 
         if self.sound && app.session.config.notifications_sound_enabled {
-            app.audio_sample_player
-                .play_sample(&mut app.audio_system, "toast");
+            let played_file = self.sound_file.as_ref().is_some_and(|path| {
+                app.audio_system
+                    .play_file(path, self.volume)
+                    .inspect_err(|e| {
+                        log::warn!("Could not play sound '{}': {e:?}", path.display());
+                    })
+                    .is_ok()
+            });
+            if !played_file {
+                app.audio_sample_player.play_sample_volume(
+                    &mut app.audio_system,
+                    "toast",
+                    self.volume,
+                );
+            }
+        }
+
+        if self.haptics {
+            let watch = app.session.config.watch_hand as usize;
+            let devices = match self.display_method(app) {
+                ToastDisplayMethod::Hide => 0..0,
+                ToastDisplayMethod::Center => 0..2,
+                ToastDisplayMethod::Watch => watch..watch + 1,
+            };
+            for device in devices {
+                app.tasks.enqueue_at(
+                    TaskType::Input(InputTask::Haptics {
+                        device,
+                        haptics: TOAST_HAPTICS,
+                    }),
+                    instant,
+                );
+            }
         }
 
         // drop any toast that was created before us.
@@ -88,12 +234,14 @@ impl Toast {
                 selector.clone(),
                 Box::new(move |app| {
                     let maybe_toast = new_toast(self, app);
-                    app.tasks.enqueue_at(
-                        // at timeout, drop the overlay by ID instead
-                        // in order to avoid dropping any newer toasts
-                        TaskType::Overlay(OverlayTask::Drop(selector)),
-                        destroy_at,
-                    );
+                    if let Some(destroy_at) = destroy_at {
+                        app.tasks.enqueue_at(
+                            // at timeout, drop the overlay by ID instead
+                            // in order to avoid dropping any newer toasts
+                            TaskType::Overlay(OverlayTask::Drop(selector)),
+                            destroy_at,
+                        );
+                    }
                     maybe_toast
                 }),
             )),
@@ -102,13 +250,19 @@ impl Toast {
     }
 }
 
+/// Takes down the toast of a notification that's no longer in the history.
+pub fn dismiss_toast(app: &mut AppState, history_id: usize) {
+    if app.windowing.toast_history_id == Some(history_id) {
+        app.windowing.toast_history_id = None;
+        app.tasks
+            .enqueue(TaskType::Overlay(OverlayTask::Drop(OverlaySelector::Name(
+                TOAST_NAME.clone(),
+            ))));
+    }
+}
+
 fn new_toast(toast: Toast, app: &mut AppState) -> Option<OverlayWindowConfig> {
-    let current_method = app
-        .session
-        .toast_topics
-        .get(toast.topic)
-        .copied()
-        .unwrap_or(ToastDisplayMethod::Hide);
+    let current_method = toast.display_method(app);
 
     let (spawn_point, spawn_rotation, positioning) = match current_method {
         ToastDisplayMethod::Hide => {
@@ -122,7 +276,7 @@ fn new_toast(toast: Toast, app: &mut AppState) -> Option<OverlayWindowConfig> {
         ),
         ToastDisplayMethod::Watch => {
             let relative_to = Positioning::FollowHand {
-                hand: LeftRight::Left,
+                hand: app.session.config.watch_hand,
                 lerp: 0.1,
                 align_to_hmd: true,
             };
@@ -132,7 +286,14 @@ fn new_toast(toast: Toast, app: &mut AppState) -> Option<OverlayWindowConfig> {
 
     let title = if toast.title.is_empty() {
         Translation::from_translation_key("TOAST.DEFAULT_TITLE")
-    } else if matches!(toast.topic, ToastTopic::System | ToastTopic::Error) {
+    } else if matches!(
+        toast.topic,
+        ToastTopic::System
+            | ToastTopic::Error
+            | ToastTopic::Alarm
+            | ToastTopic::Battery
+            | ToastTopic::Screenshot
+    ) {
         Translation::from_translation_key(&toast.title)
     } else {
         Translation::from_raw_text(&toast.title)
@@ -179,11 +340,37 @@ fn new_toast(toast: Toast, app: &mut AppState) -> Option<OverlayWindowConfig> {
     .inspect_err(|e| log::error!("Could not create toast: {e:?}"))
     .ok()?;
 
+    if let Some(height) = toast.height {
+        let _ = set_toast_height(&mut panel, height)
+            .inspect_err(|e| log::debug!("Could not set toast height: {e:?}"));
+    }
+
+    if let Some(icon) = toast.icon.as_ref() {
+        let _ = set_toast_icon(&mut panel, app, icon)
+            .inspect_err(|e| log::debug!("Could not load notification icon: {e:?}"));
+    }
+
+    if let Some(preview) = toast.preview.as_ref() {
+        let _ = set_toast_preview(&mut panel, app, preview)
+            .inspect_err(|e| log::debug!("Could not load toast preview: {e:?}"));
+    }
+
+    // actions only reach the sender while we own the notification server name
+    let has_actions = toast.history_id.is_some()
+        && toast.origin.is_some()
+        && !toast.actions.is_empty()
+        && app.dbus.owns_notifications();
+    if has_actions {
+        let _ = add_toast_actions(&mut panel, app, &toast)
+            .inspect_err(|e| log::warn!("Could not add notification actions: {e:?}"));
+    }
+
     panel
         .update_layout(app)
         .context("layout update failed")
         .ok()?;
 
+    app.windowing.toast_history_id = toast.history_id;
     Some(OverlayWindowConfig {
         name: TOAST_NAME.clone(),
         default_state: OverlayWindowState {
@@ -193,6 +380,8 @@ fn new_toast(toast: Toast, app: &mut AppState) -> Option<OverlayWindowConfig> {
                 spawn_rotation,
                 spawn_point,
             ),
+            interactable: has_actions,
+            alpha: toast.opacity.clamp(0.0, 1.0),
             ..OverlayWindowState::default()
         },
         global: true,
@@ -202,6 +391,169 @@ fn new_toast(toast: Toast, app: &mut AppState) -> Option<OverlayWindowConfig> {
     })
 }
 
+fn load_icon(app: &AppState, icon: &ToastIcon) -> anyhow::Result<CustomGlyphData> {
+    match icon {
+        ToastIcon::Named(name) => {
+            let name = name.strip_prefix("file://").unwrap_or(name);
+            let path = if name.starts_with('/') {
+                name.to_string()
+            } else {
+                app.desktop_finder
+                    .find_icon_path(name)
+                    .or_else(|| {
+                        app.desktop_finder
+                            .get_cached_entry(name)
+                            .and_then(|e| e.icon_path.as_ref().map(ToString::to_string))
+                    })
+                    .with_context(|| format!("no icon named \"{name}\""))?
+            };
+            CustomGlyphData::from_assets(&app.wgui_globals, AssetPath::File(&path))
+        }
+        ToastIcon::Encoded(data) => {
+            CustomGlyphData::from_bytes_raster(&app.wgui_globals, "toast_icon", data)
+        }
+        ToastIcon::Rgba {
+            width,
+            height,
+            data,
+        } => CustomGlyphData::from_rgba(*width, *height, data.clone()),
+    }
+}
+
+/// Replaces the bell with the icon provided by the notification.
+fn set_toast_icon(
+    panel: &mut GuiPanel<()>,
+    app: &AppState,
+    icon: &ToastIcon,
+) -> anyhow::Result<()> {
+    let glyph = load_icon(app, icon)?;
+
+    let mut alterables = EventAlterables::default();
+    let mut com = CallbackDataCommon {
+        alterables: &mut alterables,
+        state: &panel.layout.state,
+    };
+
+    panel
+        .parser_state
+        .fetch_widget_as::<WidgetImage>(&panel.layout.state, "toast_icon")?
+        .set_content(&mut com, Some(glyph));
+
+    let icon_id = panel.parser_state.get_widget_id("toast_icon")?;
+    let bell_id = panel.parser_state.get_widget_id("toast_bell")?;
+    com.alterables
+        .set_style(icon_id, StyleSetRequest::Display(taffy::Display::Flex));
+    com.alterables
+        .set_style(bell_id, StyleSetRequest::Display(taffy::Display::None));
+
+    panel.layout.process_alterables(alterables)?;
+    Ok(())
+}
+
+/// Keeps the aspect ratio of raw images, others are shown in a 16:9 box.
+fn set_toast_preview(
+    panel: &mut GuiPanel<()>,
+    app: &AppState,
+    preview: &ToastIcon,
+) -> anyhow::Result<()> {
+    let glyph = load_icon(app, preview)?;
+
+    let mut alterables = EventAlterables::default();
+    let mut com = CallbackDataCommon {
+        alterables: &mut alterables,
+        state: &panel.layout.state,
+    };
+
+    panel
+        .parser_state
+        .fetch_widget_as::<WidgetImage>(&panel.layout.state, "toast_preview")?
+        .set_content(&mut com, Some(glyph));
+
+    let preview_id = panel.parser_state.get_widget_id("toast_preview")?;
+    com.alterables
+        .set_style(preview_id, StyleSetRequest::Display(taffy::Display::Flex));
+
+    if let ToastIcon::Rgba { width, height, .. } = *preview
+        && width > 0
+        && height > 0
+    {
+        // fit into a square
+        let scale = PREVIEW_WIDTH / width.max(height) as f32;
+        com.alterables.set_style(
+            preview_id,
+            StyleSetRequest::Size(taffy::Size {
+                width: taffy::prelude::length(width as f32 * scale),
+                height: taffy::prelude::length(height as f32 * scale),
+            }),
+        );
+    }
+
+    panel.layout.process_alterables(alterables)?;
+    Ok(())
+}
+
+fn set_toast_height(panel: &mut GuiPanel<()>, height: f32) -> anyhow::Result<()> {
+    let root_id = panel.parser_state.get_widget_id("toast_root")?;
+
+    let mut alterables = EventAlterables::default();
+    alterables.set_style(
+        root_id,
+        StyleSetRequest::MinHeight(taffy::prelude::length(height)),
+    );
+    panel.layout.process_alterables(alterables)?;
+    Ok(())
+}
+
+fn add_toast_actions(
+    panel: &mut GuiPanel<()>,
+    app: &mut AppState,
+    toast: &Toast,
+) -> anyhow::Result<()> {
+    let Some(history_id) = toast.history_id else {
+        return Ok(());
+    };
+
+    let doc_params = ParseDocumentParams {
+        globals: panel.layout.state.globals.clone(),
+        path: AssetPath::FileOrBuiltIn("gui/toast.xml"),
+        extra: panel.doc_extra.take().unwrap_or_default(),
+    };
+
+    let actions_root = panel.parser_state.get_widget_id("toast_actions")?;
+
+    for action in &toast.actions {
+        let mut params: HashMap<Rc<str>, Rc<str>> = HashMap::new();
+        params.insert("id".into(), history_id.to_string().into());
+
+        // replies need the keyboard, so those are done from the notifications panel
+        let template = if &*action.key == INLINE_REPLY_KEY {
+            params.insert("panel".into(), NOTIFICATIONS_NAME.into());
+            "Reply"
+        } else {
+            let label = if action.label.is_empty() {
+                &action.key
+            } else {
+                &action.label
+            };
+            params.insert("key".into(), action.key.as_ref().into());
+            params.insert("label".into(), label.as_ref().into());
+            "Action"
+        };
+
+        panel.parser_state.instantiate_template(
+            &doc_params,
+            template,
+            &mut panel.layout,
+            actions_root,
+            params,
+        )?;
+    }
+
+    panel.doc_extra = Some(doc_params.extra);
+    panel.process_custom_elems(app);
+    Ok(())
+}
+
 fn msg_err(app: &mut AppState, message: &str) {
     Toast::new(ToastTopic::Error, "TOAST.ERROR".into(), message.into())
         .with_timeout(3.)
@@ -1,6 +1,6 @@
 use std::{rc::Rc, time::Duration};
 
-use glam::{Affine3A, Quat, Vec3, vec3};
+use glam::{Affine3A, EulerRot, Quat, Vec3, vec3};
 use wgui::{
     assets::AssetPath,
     components::button::ComponentButton,
@@ -11,10 +11,12 @@ use wgui::{
 };
 use wlx_common::{
     common::LeftRight,
+    config::GeneralConfig,
     windowing::{OverlayWindowState, Positioning},
 };
 
 use crate::{
+    backend::task::{OverlayTask, TaskType},
     gui::{
         panel::{
             GuiPanel, NewGuiPanelParams, apply_custom_command, device_list::DeviceList,
@@ -23,7 +25,9 @@ use crate::{
         timer::GuiTimer,
     },
     state::AppState,
-    windowing::{Z_ORDER_WATCH, backend::OverlayEventData, window::OverlayWindowConfig},
+    windowing::{
+        OverlaySelector, Z_ORDER_WATCH, backend::OverlayEventData, window::OverlayWindowConfig,
+    },
 };
 
 pub const WATCH_NAME: &str = "watch";
@@ -32,6 +36,7 @@ const MAX_DEVICES: usize = 12;
 
 pub const WATCH_POS: Vec3 = vec3(-0.03, -0.01, 0.125);
 pub const WATCH_ROT: Quat = Quat::from_xyzw(-0.707_106_6, 0.000_796_361_8, 0.707_106_6, 0.0);
+const WATCH_SCALE: f32 = 0.115;
 
 struct OverlayButton {
     button: Rc<ComponentButton>,
@@ -116,6 +121,11 @@ pub fn create_watch(app: &mut AppState) -> anyhow::Result<OverlayWindowConfig> {
                     panel.layout.mark_redraw();
                     sets_or_overlays(panel, app, &mut alterables);
 
+                    app.tasks.enqueue(TaskType::Overlay(OverlayTask::Modify(
+                        OverlaySelector::Name(WATCH_NAME.into()),
+                        Box::new(apply_watch_placement),
+                    )));
+
                     if app.session.config.clock_12h != panel.state.clock_12h {
                         panel.state.clock_12h = app.session.config.clock_12h;
 
@@ -156,11 +166,7 @@ pub fn create_watch(app: &mut AppState) -> anyhow::Result<OverlayWindowConfig> {
         .timers
         .push(GuiTimer::new(Duration::from_millis(100), 0));
 
-    let positioning = Positioning::FollowHand {
-        hand: LeftRight::Left,
-        lerp: 1.0,
-        align_to_hmd: false,
-    };
+    let hand = app.session.config.watch_hand;
 
     panel.update_layout(app)?;
 
@@ -170,12 +176,8 @@ pub fn create_watch(app: &mut AppState) -> anyhow::Result<OverlayWindowConfig> {
         default_state: OverlayWindowState {
             grabbable: false,
             interactable: true,
-            positioning,
-            transform: Affine3A::from_scale_rotation_translation(
-                Vec3::ONE * 0.115,
-                WATCH_ROT,
-                WATCH_POS,
-            ),
+            positioning: watch_positioning(hand),
+            transform: watch_transform(&app.session.config, hand),
             angle_fade: true,
             ..OverlayWindowState::default()
         },
@@ -185,6 +187,58 @@ pub fn create_watch(app: &mut AppState) -> anyhow::Result<OverlayWindowConfig> {
     })
 }
 
+pub const fn watch_positioning(hand: LeftRight) -> Positioning {
+    Positioning::FollowHand {
+        hand,
+        lerp: 1.0,
+        align_to_hmd: false,
+    }
+}
+
+/// Where the watch sits relative to the controller of `hand`.
+/// `watch_offset` and `watch_rotation` are given for the left hand and mirrored for the right.
+pub fn watch_transform(config: &GeneralConfig, hand: LeftRight) -> Affine3A {
+    let [x, y, z] = config.watch_rotation.map(f32::to_radians);
+    let rotation = WATCH_ROT * Quat::from_euler(EulerRot::XYZ, x, y, z);
+    let translation = WATCH_POS + Vec3::from_array(config.watch_offset);
+
+    let (rotation, translation) = match hand {
+        LeftRight::Left => (rotation, translation),
+        // reflected across the YZ plane. still a proper rotation, so the face isn't mirrored
+        LeftRight::Right => (
+            Quat::from_xyzw(rotation.x, -rotation.y, -rotation.z, rotation.w),
+            translation * vec3(-1., 1., 1.),
+        ),
+    };
+
+    Affine3A::from_scale_rotation_translation(Vec3::ONE * WATCH_SCALE, rotation, translation)
+}
+
+/// Moves the watch to `watch_hand` at the configured placement, if either changed.
+/// Placing it by hand is kept otherwise.
+pub fn apply_watch_placement(app: &mut AppState, overlay: &mut OverlayWindowConfig) {
+    let hand = app.session.config.watch_hand;
+    let positioning = watch_positioning(hand);
+    let transform = watch_transform(&app.session.config, hand);
+
+    let same_hand = matches!(
+        overlay.default_state.positioning,
+        Positioning::FollowHand { hand: h, .. } if h == hand
+    );
+    if same_hand && overlay.default_state.transform == transform {
+        return;
+    }
+
+    log::debug!("Moving watch to {hand:?} hand");
+    overlay.default_state.positioning = positioning;
+    overlay.default_state.transform = transform;
+    if let Some(state) = overlay.active_state.as_mut() {
+        state.positioning = positioning;
+        state.saved_transform = None;
+    }
+    overlay.reset(app, true);
+}
+
 fn sets_or_overlays(
     panel: &GuiPanel<WatchState>,
     app: &mut AppState,
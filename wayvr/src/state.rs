@@ -4,7 +4,11 @@ use smallvec::{SmallVec, smallvec};
 use std::sync::Arc;
 use wgui::log::LogErr;
 use wgui::{
-    drawing, font_config::WguiFontConfig, gfx::WGfx, globals::WguiGlobals, parser::parse_color_hex,
+    drawing,
+    font_config::WguiFontConfig,
+    gfx::WGfx,
+    globals::{Defaults, WguiGlobals},
+    parser::parse_color_hex,
     renderer_vk::context::SharedContext as WSharedContext,
 };
 use wlx_common::locale::WayVRLangProvider;
@@ -26,7 +30,12 @@ use crate::{
     graphics::WGfxExtras,
     gui,
     ipc::{event_queue::SyncEventQueue, ipc_server, signal::WayVRSignal},
-    subsystem::{dbus::DbusConnector, input::HidWrapper},
+    subsystem::{
+        alarms::AlarmManager, battery::BatteryAlerts, calendar::Calendar,
+        clipboard::ClipboardHistory, dbus::DbusConnector, input::HidWrapper,
+        notifications::NotificationHistory, screenshot::Screenshots, sysmon::SystemMonitor,
+    },
+    windowing::WindowingStatus,
 };
 
 pub struct AppState {
@@ -46,10 +55,17 @@ pub struct AppState {
     pub screens: SmallVec<[ScreenMeta; 8]>,
     pub anchor: Affine3A,
     pub anchor_grabbed: bool,
+    pub windowing: WindowingStatus,
 
     pub wgui_globals: WguiGlobals,
 
     pub dbus: DbusConnector,
+    pub notification_history: NotificationHistory,
+    pub alarms: AlarmManager,
+    pub calendar: Calendar,
+    pub battery_alerts: BatteryAlerts,
+    pub clipboard: ClipboardHistory,
+    pub screenshots: Screenshots,
 
     pub xr_backend: XrBackend,
 
@@ -57,6 +73,7 @@ pub struct AppState {
     pub wayvr_signals: SyncEventQueue<WayVRSignal>,
 
     pub desktop_finder: DesktopFinder,
+    pub sysmon: SystemMonitor,
 
     #[cfg(feature = "osc")]
     pub osc_sender: Option<OscSender>,
@@ -80,9 +97,17 @@ impl AppState {
         let session = AppSession::load();
         let wvr_signals = SyncEventQueue::new();
 
-        let wvr_server = WvrServerState::new(gfx.clone(), &gfx_extras, wvr_signals.clone())
-            .log_err("Could not initialize WayVR Server")
-            .ok();
+        let clipboard = ClipboardHistory::new(&session.config);
+
+        let wvr_server = WvrServerState::new(
+            gfx.clone(),
+            &gfx_extras,
+            wvr_signals.clone(),
+            clipboard.selection_reader(),
+            &session.config,
+        )
+        .log_err("Could not initialize WayVR Server")
+        .ok();
 
         let mut hid_provider = HidWrapper::new();
 
@@ -112,25 +137,7 @@ impl AppState {
         let mut assets = Box::new(gui::asset::GuiAsset {});
         audio_sample_player.register_wgui_samples(assets.as_mut())?;
 
-        let mut defaults = wgui::globals::Defaults::default();
-
-        {
-            #[allow(clippy::ref_option)]
-            fn apply_color(default: &mut drawing::Color, value: &Option<String>) {
-                if let Some(parsed) = value.as_ref().and_then(|c| parse_color_hex(c)) {
-                    *default = parsed;
-                }
-            }
-
-            apply_color(&mut defaults.text_color, &session.config.color_text);
-            apply_color(&mut defaults.accent_color, &session.config.color_accent);
-            apply_color(&mut defaults.danger_color, &session.config.color_danger);
-            apply_color(&mut defaults.faded_color, &session.config.color_faded);
-            apply_color(&mut defaults.bg_color, &session.config.color_background);
-        }
-
-        defaults.animation_mult = 1. / session.config.ui_animation_speed;
-        defaults.rounding_mult = session.config.ui_round_multiplier;
+        let defaults = theme_defaults(&session.config);
 
         let dbus = DbusConnector::default();
 
@@ -141,6 +148,10 @@ impl AppState {
 
         let lang_provider = WayVRLangProvider::from_config(&session.config);
 
+        let alarms = AlarmManager::load(&session.config);
+        let calendar = Calendar::new(&session.config);
+        let battery_alerts = BatteryAlerts::new(&session.config);
+
         Ok(Self {
             session,
             tasks,
@@ -154,6 +165,7 @@ impl AppState {
             screens: smallvec![],
             anchor: Affine3A::IDENTITY,
             anchor_grabbed: false,
+            windowing: WindowingStatus::default(),
             wgui_globals: WguiGlobals::new(
                 assets,
                 &lang_provider,
@@ -162,10 +174,17 @@ impl AppState {
                 get_config_file_path(&theme),
             )?,
             dbus,
+            notification_history: NotificationHistory::default(),
+            alarms,
+            calendar,
+            battery_alerts,
+            clipboard,
+            screenshots: Screenshots::default(),
             xr_backend,
             ipc_server,
             wayvr_signals: wvr_signals,
             desktop_finder,
+            sysmon: SystemMonitor::default(),
 
             #[cfg(feature = "osc")]
             osc_sender,
@@ -187,9 +206,34 @@ impl AppState {
     }
 }
 
+/// wgui defaults with the theme colors and UI multipliers from the config applied.
+pub fn theme_defaults(config: &GeneralConfig) -> Defaults {
+    #[allow(clippy::ref_option)]
+    fn apply_color(default: &mut drawing::Color, value: &Option<String>) {
+        if let Some(parsed) = value.as_ref().and_then(|c| parse_color_hex(c)) {
+            *default = parsed;
+        }
+    }
+
+    let mut defaults = Defaults::default();
+
+    apply_color(&mut defaults.text_color, &config.color_text);
+    apply_color(&mut defaults.accent_color, &config.color_accent);
+    apply_color(&mut defaults.danger_color, &config.color_danger);
+    apply_color(&mut defaults.faded_color, &config.color_faded);
+    apply_color(&mut defaults.bg_color, &config.color_background);
+
+    defaults.animation_mult = 1. / config.ui_animation_speed;
+    defaults.rounding_mult = config.ui_round_multiplier;
+
+    defaults
+}
+
 pub struct AppSession {
     pub config: GeneralConfig,
     pub config_dirty: bool,
+    /// Bumped on every `OverlayTask::SettingsChanged`, so that bound controls can re-read the config
+    pub config_revision: u64,
 
     pub toast_topics: IdMap<ToastTopic, ToastDisplayMethod>,
 }
@@ -205,6 +249,10 @@ impl AppSession {
         toast_topics.insert(ToastTopic::Error, ToastDisplayMethod::Center);
         toast_topics.insert(ToastTopic::DesktopNotification, ToastDisplayMethod::Center);
         toast_topics.insert(ToastTopic::XSNotification, ToastDisplayMethod::Center);
+        toast_topics.insert(ToastTopic::Alarm, ToastDisplayMethod::Center);
+        toast_topics.insert(ToastTopic::Calendar, ToastDisplayMethod::Center);
+        toast_topics.insert(ToastTopic::Battery, ToastDisplayMethod::Center);
+        toast_topics.insert(ToastTopic::Screenshot, ToastDisplayMethod::Center);
 
         config.notification_topics.iter().for_each(|(k, v)| {
             toast_topics.insert(*k, *v);
@@ -214,6 +262,7 @@ impl AppSession {
             config,
             toast_topics,
             config_dirty: false,
+            config_revision: 0,
         }
     }
 }
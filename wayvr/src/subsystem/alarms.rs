@@ -0,0 +1,321 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use chrono::{DateTime, Days, Local, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use wlx_common::{config::GeneralConfig, config_io::get_config_file_path, overlays::ToastTopic};
+
+use crate::{
+    backend::{
+        input::Haptics,
+        task::{InputTask, TaskType},
+    },
+    overlays::toast::Toast,
+    state::AppState,
+};
+
+const ALARM_TOAST_TIMEOUT: f32 = 15.;
+/// Reminders more frequent than this would be little more than noise
+pub const MIN_REMINDER_SECS: u32 = 60;
+
+/// Repeated a few times so that it can't be mistaken for a regular toast
+const ALARM_HAPTICS: Haptics = Haptics {
+    intensity: 1.0,
+    duration: 0.2,
+    frequency: 5.0,
+};
+const ALARM_HAPTICS_PULSES: u32 = 3;
+const ALARM_HAPTICS_INTERVAL: Duration = Duration::from_millis(400);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlarmKind {
+    /// Fires once, after a duration
+    Countdown,
+    /// Fires once, at a time of day
+    Alarm,
+    /// Fires repeatedly, at an interval
+    Reminder,
+}
+
+impl AlarmKind {
+    pub const fn title_key(self) -> &'static str {
+        match self {
+            Self::Countdown => "ALARM.COUNTDOWN",
+            Self::Alarm => "ALARM.ALARM",
+            Self::Reminder => "ALARM.REMINDER",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Alarm {
+    pub id: u32,
+    pub kind: AlarmKind,
+    #[serde(default)]
+    pub label: String,
+    /// Unix timestamp, in seconds
+    pub due: i64,
+    /// Seconds between repeats of a reminder
+    #[serde(default)]
+    pub interval: Option<u32>,
+}
+
+impl Alarm {
+    pub fn due_local(&self) -> DateTime<Local> {
+        DateTime::from_timestamp(self.due, 0)
+            .map_or_else(Local::now, |due| due.with_timezone(&Local))
+    }
+
+    /// Whole seconds until due, 0 if overdue
+    pub fn remaining_secs(&self) -> u64 {
+        (self.due - Utc::now().timestamp()).max(0) as u64
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct AlarmsFile {
+    next_id: u32,
+    alarms: Vec<Alarm>,
+}
+
+fn get_alarms_path() -> PathBuf {
+    get_config_file_path("alarms.json")
+}
+
+/// Countdowns, alarms and reminders set from the watch or over IPC.
+/// Kept in `alarms.json` in the config root so that they survive restarts.
+pub struct AlarmManager {
+    next_id: u32,
+    /// Sorted by due time
+    alarms: Vec<Alarm>,
+    dirty: bool,
+
+    /// From `break_reminder_minutes`; counts from the start of the session
+    break_interval: Option<Duration>,
+    next_break: Instant,
+    session_start: Instant,
+}
+
+impl AlarmManager {
+    pub fn load(config: &GeneralConfig) -> Self {
+        let file = Self::load_file()
+            .inspect_err(|e| log::warn!("Could not load alarms: {e:?}"))
+            .unwrap_or_default();
+
+        let break_interval = (config.break_reminder_minutes > 0)
+            .then(|| Duration::from_secs(u64::from(config.break_reminder_minutes) * 60));
+
+        let now = Instant::now();
+        let mut me = Self {
+            next_id: file.next_id,
+            alarms: file.alarms,
+            dirty: false,
+            break_interval,
+            next_break: now + break_interval.unwrap_or_default(),
+            session_start: now,
+        };
+        me.alarms.sort_by_key(|a| a.due);
+        me
+    }
+
+    fn load_file() -> anyhow::Result<AlarmsFile> {
+        let path = get_alarms_path();
+        if !path.exists() {
+            return Ok(AlarmsFile::default());
+        }
+        let json = std::fs::read_to_string(&path)?;
+        serde_json::from_str(&json).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    fn save(&mut self) -> anyhow::Result<()> {
+        self.dirty = false;
+
+        let file = AlarmsFile {
+            next_id: self.next_id,
+            alarms: self.alarms.clone(),
+        };
+        let json = serde_json::to_string_pretty(&file).unwrap(); // want panic
+        std::fs::write(get_alarms_path(), json)?;
+        Ok(())
+    }
+
+    fn add(&mut self, kind: AlarmKind, label: String, due: i64, interval: Option<u32>) -> u32 {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+
+        log::info!("Adding {kind:?} {id} \"{label}\"");
+        self.alarms.push(Alarm {
+            id,
+            kind,
+            label,
+            due,
+            interval,
+        });
+        self.alarms.sort_by_key(|a| a.due);
+        self.dirty = true;
+        id
+    }
+
+    /// Fires once, `secs` from now.
+    pub fn add_countdown(&mut self, secs: u32, label: String) -> u32 {
+        let due = Utc::now().timestamp() + i64::from(secs);
+        self.add(AlarmKind::Countdown, label, due, None)
+    }
+
+    /// Fires once, at the next occurrence of `time` in local time.
+    pub fn add_alarm_at(&mut self, time: NaiveTime, label: String) -> anyhow::Result<u32> {
+        let now = Local::now();
+        let due = [0, 1]
+            .into_iter()
+            .filter_map(|days| {
+                (now.date_naive() + Days::new(days))
+                    .and_time(time)
+                    .and_local_timezone(Local)
+                    .earliest()
+            })
+            .find(|due| *due > now)
+            .with_context(|| format!("{time} does not exist in the local timezone"))?;
+
+        Ok(self.add(AlarmKind::Alarm, label, due.timestamp(), None))
+    }
+
+    /// Fires every `secs`, starting `secs` from now.
+    pub fn add_reminder(&mut self, secs: u32, label: String) -> u32 {
+        let secs = secs.max(MIN_REMINDER_SECS);
+        let due = Utc::now().timestamp() + i64::from(secs);
+        self.add(AlarmKind::Reminder, label, due, Some(secs))
+    }
+
+    /// Returns false if there is no such alarm.
+    pub fn remove(&mut self, id: u32) -> bool {
+        let Some(idx) = self.alarms.iter().position(|a| a.id == id) else {
+            return false;
+        };
+        self.alarms.remove(idx);
+        self.dirty = true;
+        true
+    }
+
+    /// Cancels whichever alarm is due first.
+    pub fn remove_next(&mut self) {
+        if !self.alarms.is_empty() {
+            self.alarms.remove(0);
+            self.dirty = true;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.dirty |= !self.alarms.is_empty();
+        self.alarms.clear();
+    }
+
+    pub fn next_due(&self) -> Option<&Alarm> {
+        self.alarms.first()
+    }
+
+    pub fn list(&self) -> &[Alarm] {
+        &self.alarms
+    }
+
+    /// Removes the alarms that are due. Reminders are rescheduled instead,
+    /// skipping any repeats that were missed while not running.
+    fn take_due(&mut self, now: i64) -> Vec<Alarm> {
+        let count = self.alarms.partition_point(|a| a.due <= now);
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let due: Vec<Alarm> = self.alarms.drain(..count).collect();
+        for alarm in &due {
+            if let Some(interval) = alarm.interval {
+                let interval = i64::from(interval.max(1));
+                let missed = (now - alarm.due) / interval + 1;
+                self.alarms.push(Alarm {
+                    due: alarm.due + missed * interval,
+                    ..alarm.clone()
+                });
+            }
+        }
+        self.alarms.sort_by_key(|a| a.due);
+        self.dirty = true;
+        due
+    }
+
+    fn take_break_due(&mut self) -> Option<Duration> {
+        let interval = self.break_interval?;
+        let now = Instant::now();
+        if now < self.next_break {
+            return None;
+        }
+        self.next_break = now + interval;
+        Some(now - self.session_start)
+    }
+}
+
+/// Call once per frame.
+pub fn tick(app: &mut AppState) {
+    for alarm in app.alarms.take_due(Utc::now().timestamp()) {
+        fire_alarm(app, &alarm);
+    }
+
+    if let Some(session_length) = app.alarms.take_break_due() {
+        let minutes = session_length.as_secs() / 60;
+        alarm_toast(
+            app,
+            "ALARM.BREAK",
+            format!("{}:{:02}", minutes / 60, minutes % 60),
+        );
+    }
+
+    if app.alarms.dirty {
+        let _ = app
+            .alarms
+            .save()
+            .inspect_err(|e| log::warn!("Could not save alarms: {e:?}"));
+    }
+}
+
+/// Formats the due time of an alarm like the `clock` label source does.
+pub fn format_due_time(alarm: &Alarm, app: &AppState) -> String {
+    let format = if app.session.config.clock_12h {
+        "%I:%M %p"
+    } else {
+        "%H:%M"
+    };
+    alarm.due_local().format(format).to_string()
+}
+
+fn fire_alarm(app: &mut AppState, alarm: &Alarm) {
+    log::info!("{:?} {} is due", alarm.kind, alarm.id);
+
+    let body = if alarm.label.is_empty() {
+        format_due_time(alarm, app)
+    } else {
+        alarm.label.clone()
+    };
+    alarm_toast(app, alarm.kind.title_key(), body);
+}
+
+fn alarm_toast(app: &mut AppState, title_key: &str, body: String) {
+    Toast::new(ToastTopic::Alarm, title_key.into(), body)
+        .with_timeout(ALARM_TOAST_TIMEOUT)
+        .with_sound(true)
+        .with_always_show(true)
+        .submit(app);
+
+    // on the wrist, like a watch would
+    let device = app.session.config.watch_hand as usize;
+    let now = Instant::now();
+    for pulse in 0..ALARM_HAPTICS_PULSES {
+        app.tasks.enqueue_at(
+            TaskType::Input(InputTask::Haptics {
+                device,
+                haptics: ALARM_HAPTICS,
+            }),
+            now + ALARM_HAPTICS_INTERVAL * pulse,
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use wlx_common::{config::GeneralConfig, overlays::ToastTopic};
+
+use crate::{backend::input::TrackedDevice, overlays::toast::Toast, state::AppState};
+
+struct DeviceAlert {
+    /// Lowest of `battery_warn_levels` that was announced
+    level: u32,
+    last_shown: Instant,
+}
+
+/// Warns about devices that are running low on battery.
+/// Checked whenever the device list is refreshed.
+pub struct BatteryAlerts {
+    /// Sorted from highest to lowest
+    levels: Vec<u32>,
+    repeat: Option<Duration>,
+    /// By `TrackedDevice::key`
+    alerts: HashMap<String, DeviceAlert>,
+}
+
+impl BatteryAlerts {
+    pub fn new(config: &GeneralConfig) -> Self {
+        let mut levels = config.battery_warn_levels.clone();
+        levels.sort_unstable_by(|a, b| b.cmp(a));
+        levels.dedup();
+
+        Self {
+            levels,
+            repeat: (config.battery_warn_repeat_minutes > 0)
+                .then(|| Duration::from_secs(u64::from(config.battery_warn_repeat_minutes) * 60)),
+            alerts: HashMap::new(),
+        }
+    }
+
+    /// Devices that need a warning now, with their charge in percent.
+    fn take_due<'a>(&mut self, devices: &'a [TrackedDevice]) -> Vec<(&'a TrackedDevice, u32)> {
+        let now = Instant::now();
+        let mut due = Vec::new();
+
+        for device in devices {
+            let key = device.key();
+            let Some(soc) = device.soc.filter(|_| !device.charging) else {
+                self.alerts.remove(&key);
+                continue;
+            };
+            let percent = (soc * 100.).round() as u32;
+
+            // the lowest level that the device is at or below
+            let Some(level) = self.levels.iter().copied().rfind(|l| percent <= *l) else {
+                self.alerts.remove(&key);
+                continue;
+            };
+
+            let show = match self.alerts.get(&key) {
+                None => true,
+                Some(alert) if level < alert.level => true,
+                Some(alert) => self
+                    .repeat
+                    .is_some_and(|repeat| now - alert.last_shown >= repeat),
+            };
+            if show {
+                self.alerts.insert(
+                    key,
+                    DeviceAlert {
+                        level,
+                        last_shown: now,
+                    },
+                );
+                due.push((device, percent));
+            }
+        }
+        due
+    }
+}
+
+/// Call after the device list was refreshed.
+pub fn check(app: &mut AppState) {
+    let due = app.battery_alerts.take_due(&app.input_state.devices);
+    if due.is_empty() {
+        return;
+    }
+
+    // toasts submitted together would replace each other, so all devices share one
+    let body = due
+        .iter()
+        .map(|(device, percent)| {
+            log::info!("{} battery low: {percent}%", device.display_name());
+            format!("{}: {percent}%", device.display_name())
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut toast = Toast::new(ToastTopic::Battery, "BATTERY.LOW".into(), body)
+        .with_timeout(8.)
+        .with_sound(true);
+    toast.haptics = true;
+    toast.submit(app);
+}
@@ -0,0 +1,830 @@
+//! Just enough of iCalendar (RFC 5545) to build an agenda.
+//!
+//! Reads `VEVENT`s with `DTSTART`, `DTEND` or `DURATION`, `SUMMARY`, `LOCATION`, `STATUS`,
+//! `EXDATE` and `RECURRENCE-ID`. Recurrence rules support `FREQ`, `INTERVAL`, `COUNT`, `UNTIL`,
+//! `BYDAY`, `BYMONTHDAY` and `BYMONTH`; rules with other `BY*` parts are skipped.
+
+use std::str::FromStr;
+
+use chrono::{
+    DateTime, Datelike, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone,
+    Utc, Weekday,
+};
+use chrono_tz::Tz;
+
+/// Stops runaway rules, such as a daily event from decades ago
+const MAX_ITERATIONS: u32 = 100_000;
+
+#[derive(Clone, Copy, Debug)]
+enum EventTz {
+    Utc,
+    Named(Tz),
+    /// No timezone given, or one that chrono-tz does not know
+    Floating,
+}
+
+impl EventTz {
+    fn to_local(self, naive: NaiveDateTime) -> Option<DateTime<Local>> {
+        match self {
+            Self::Utc => Some(Utc.from_utc_datetime(&naive).with_timezone(&Local)),
+            Self::Named(tz) => tz
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|dt| dt.with_timezone(&Local)),
+            Self::Floating => Local.from_local_datetime(&naive).earliest(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Freq {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Clone, Debug)]
+struct RRule {
+    freq: Freq,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<(NaiveDateTime, EventTz)>,
+    /// With the ordinal in front, such as 2 for `2TU`, the second Tuesday of the month
+    by_day: Vec<(Option<i32>, Weekday)>,
+    /// Negative days count from the end of the month
+    by_month_day: Vec<i32>,
+    by_month: Vec<u32>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct VEvent {
+    pub uid: String,
+    pub summary: String,
+    pub location: String,
+    pub all_day: bool,
+    start: Option<(NaiveDateTime, EventTz)>,
+    end: Option<NaiveDateTime>,
+    duration: Option<TimeDelta>,
+    rrule: Option<RRule>,
+    exdates: Vec<NaiveDateTime>,
+    /// Set on an edited instance of a recurring event
+    recurrence_id: Option<NaiveDateTime>,
+    cancelled: bool,
+}
+
+/// A single instance of an event, in local time.
+pub struct Occurrence {
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+}
+
+impl VEvent {
+    /// An edited instance of a recurring event, which replaces the original instance
+    pub const fn is_override(&self) -> bool {
+        self.recurrence_id.is_some()
+    }
+
+    fn duration(&self) -> TimeDelta {
+        let default = if self.all_day {
+            TimeDelta::days(1)
+        } else {
+            TimeDelta::zero()
+        };
+        match (self.start, self.end, self.duration) {
+            (_, _, Some(duration)) => duration,
+            (Some((start, _)), Some(end), None) if end > start => end - start,
+            _ => default,
+        }
+    }
+
+    /// Instances that have not ended by `from` and start before `to`.
+    /// `skip` lists instances that were edited or removed elsewhere.
+    pub fn occurrences(
+        &self,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+        skip: &[NaiveDateTime],
+    ) -> Vec<Occurrence> {
+        let Some((start, tz)) = self.start else {
+            return Vec::new();
+        };
+        if self.cancelled {
+            return Vec::new();
+        }
+
+        let duration = self.duration();
+        let mut result = Vec::new();
+        let mut push = |naive: NaiveDateTime| {
+            if self.exdates.contains(&naive) || skip.contains(&naive) {
+                return;
+            }
+            let Some(start) = tz.to_local(naive) else {
+                return;
+            };
+            let Some(end) = start.checked_add_signed(duration) else {
+                return;
+            };
+            if end > from && start < to {
+                result.push(Occurrence { start, end });
+            }
+        };
+
+        let Some(rrule) = self.rrule.as_ref() else {
+            push(start);
+            return result;
+        };
+
+        let past_until = |naive: NaiveDateTime| match rrule.until {
+            None => false,
+            // required to be in UTC when DTSTART has a timezone
+            Some((until, EventTz::Utc)) => tz
+                .to_local(naive)
+                .is_some_and(|local| local > Utc.from_utc_datetime(&until)),
+            Some((until, _)) => naive > until,
+        };
+
+        let mut emitted = 0;
+        'periods: for (iteration, batch) in rrule.iter(start).enumerate() {
+            if iteration as u32 >= MAX_ITERATIONS {
+                break;
+            }
+            for naive in batch {
+                if rrule.count.is_some_and(|count| emitted >= count)
+                    || past_until(naive)
+                    || tz.to_local(naive).is_some_and(|local| local >= to)
+                {
+                    break 'periods;
+                }
+                emitted += 1;
+                push(naive);
+            }
+        }
+        result
+    }
+}
+
+impl RRule {
+    fn parse(value: &str) -> Option<Self> {
+        let mut freq = None;
+        let mut rule = Self {
+            freq: Freq::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+        };
+
+        for part in value.split(';') {
+            let Some((key, value)) = part.split_once('=') else {
+                continue;
+            };
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    freq = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Freq::Daily,
+                        "WEEKLY" => Freq::Weekly,
+                        "MONTHLY" => Freq::Monthly,
+                        "YEARLY" => Freq::Yearly,
+                        unk => {
+                            log::debug!("Unsupported recurrence frequency: {unk}");
+                            return None;
+                        }
+                    });
+                }
+                "INTERVAL" => rule.interval = value.parse().ok()?,
+                "COUNT" => rule.count = value.parse().ok(),
+                "UNTIL" => rule.until = parse_date_time(value, None).map(|(dt, tz, _)| (dt, tz)),
+                "BYDAY" => rule.by_day = value.split(',').filter_map(parse_by_day).collect(),
+                "BYMONTHDAY" => {
+                    rule.by_month_day = value
+                        .split(',')
+                        .filter_map(|d| d.parse().ok())
+                        .filter(|d: &i32| *d != 0 && d.abs() <= 31)
+                        .collect();
+                }
+                "BYMONTH" => {
+                    rule.by_month = value
+                        .split(',')
+                        .filter_map(|m| m.parse().ok())
+                        .filter(|m| (1..=12).contains(m))
+                        .collect();
+                }
+                unk if unk.starts_with("BY") => {
+                    log::debug!("Unsupported recurrence rule part: {unk}");
+                    return None;
+                }
+                _ => {}
+            }
+        }
+
+        rule.freq = freq?;
+        rule.interval = rule.interval.max(1);
+
+        // such as the 20th Monday of the year
+        if rule.freq == Freq::Yearly
+            && rule.by_month.is_empty()
+            && rule.by_day.iter().any(|(ordinal, _)| ordinal.is_some())
+        {
+            log::debug!("Unsupported recurrence rule: {value}");
+            return None;
+        }
+        Some(rule)
+    }
+
+    /// Whether `date` passes the `BY*` parts, ignoring ordinals.
+    /// For rules whose period is shorter than those parts, which only narrow it down.
+    fn limits(&self, date: NaiveDate) -> bool {
+        let month_len = days_in_month(date);
+        let day_from_end = date.day() as i32 - month_len as i32 - 1;
+        (self.by_month.is_empty() || self.by_month.contains(&date.month()))
+            && (self.by_month_day.is_empty()
+                || self
+                    .by_month_day
+                    .iter()
+                    .any(|d| *d == date.day() as i32 || *d == day_from_end))
+            && (self.by_day.is_empty() || self.by_day.iter().any(|(_, wd)| *wd == date.weekday()))
+    }
+
+    /// Days of the month starting at `first` that the rule expands to.
+    /// Days the month doesn't have are skipped, not moved to its end.
+    fn month_days(&self, first: NaiveDate, start: NaiveDateTime) -> Vec<NaiveDate> {
+        let month_len = days_in_month(first);
+        let mut days: Vec<u32> = if !self.by_month_day.is_empty() {
+            self.by_month_day
+                .iter()
+                .map(|d| if *d > 0 { *d } else { month_len as i32 + 1 + d })
+                .filter(|d| (1..=month_len as i32).contains(d))
+                .map(|d| d as u32)
+                .collect()
+        } else if !self.by_day.is_empty() {
+            (1..=month_len).collect()
+        } else {
+            vec![start.day()]
+        };
+
+        if !self.by_day.is_empty() {
+            days.retain(|day| {
+                let Some(date) = first.with_day(*day) else {
+                    return false;
+                };
+                let nth = (*day as i32 - 1) / 7 + 1;
+                let nth_from_end = -((month_len - day) as i32 / 7 + 1);
+                self.by_day.iter().any(|(ordinal, wd)| {
+                    *wd == date.weekday() && ordinal.is_none_or(|o| o == nth || o == nth_from_end)
+                })
+            });
+        }
+        days.sort_unstable();
+        days.dedup();
+        days.into_iter()
+            .filter_map(|day| first.with_day(day))
+            .collect()
+    }
+
+    /// Instance start times from `start` on, without end, in batches of one period such as a
+    /// month. Batches may be empty, so rules that rarely match still make progress.
+    /// Ends early once the next period can't be represented.
+    fn iter(&self, start: NaiveDateTime) -> Box<dyn Iterator<Item = Vec<NaiveDateTime>> + '_> {
+        let interval = self.interval;
+        let at_start_time = move |dates: Vec<NaiveDate>| {
+            dates
+                .into_iter()
+                .map(|date| date.and_time(start.time()))
+                .filter(|dt| *dt >= start)
+                .collect::<Vec<_>>()
+        };
+
+        match self.freq {
+            Freq::Daily => {
+                let step = TimeDelta::days(i64::from(interval));
+                Box::new(
+                    (0..)
+                        .map_while(move |i: i32| start.checked_add_signed(step.checked_mul(i)?))
+                        .map(move |dt| {
+                            if self.limits(dt.date()) {
+                                vec![dt]
+                            } else {
+                                Vec::new()
+                            }
+                        }),
+                )
+            }
+            Freq::Weekly => {
+                let monday = TimeDelta::days(start.weekday().num_days_from_monday().into());
+                let Some(week_start) = start.date().checked_sub_signed(monday) else {
+                    return Box::new(std::iter::empty());
+                };
+                let mut days: Vec<u32> = if self.by_day.is_empty() {
+                    vec![start.weekday().num_days_from_monday()]
+                } else {
+                    self.by_day
+                        .iter()
+                        .map(|(_, wd)| wd.num_days_from_monday())
+                        .collect()
+                };
+                days.sort_unstable();
+                days.dedup();
+
+                Box::new(
+                    (0..)
+                        .map_while(move |week: i64| {
+                            let offset = TimeDelta::try_weeks(week.checked_mul(interval.into())?)?;
+                            week_start.checked_add_signed(offset)
+                        })
+                        .map(move |week| {
+                            at_start_time(
+                                days.iter()
+                                    .filter_map(|day| {
+                                        week.checked_add_signed(TimeDelta::days((*day).into()))
+                                    })
+                                    .filter(|date| self.limits(*date))
+                                    .collect(),
+                            )
+                        }),
+                )
+            }
+            Freq::Monthly | Freq::Yearly => {
+                let months = if self.freq == Freq::Monthly {
+                    Some(interval)
+                } else {
+                    interval.checked_mul(12)
+                };
+                let Some(months) = months else {
+                    return Box::new(std::iter::once(vec![start]));
+                };
+                let first_month = i64::from(start.year()) * 12 + i64::from(start.month0());
+
+                Box::new(
+                    (0..)
+                        .map_while(move |i: u32| {
+                            let month = first_month + i64::from(i.checked_mul(months)?);
+                            let year = i32::try_from(month.div_euclid(12)).ok()?;
+                            NaiveDate::from_ymd_opt(year, month.rem_euclid(12) as u32 + 1, 1)
+                        })
+                        .map(move |first| {
+                            let dates = if self.freq == Freq::Monthly {
+                                if self.by_month.is_empty()
+                                    || self.by_month.contains(&first.month())
+                                {
+                                    self.month_days(first, start)
+                                } else {
+                                    Vec::new()
+                                }
+                            } else {
+                                self.year_months(first, start)
+                                    .into_iter()
+                                    .flat_map(|first| self.month_days(first, start))
+                                    .collect()
+                            };
+                            at_start_time(dates)
+                        }),
+                )
+            }
+        }
+    }
+
+    /// First days of the months a yearly rule expands to, for the year starting at `first`.
+    fn year_months(&self, first: NaiveDate, start: NaiveDateTime) -> Vec<NaiveDate> {
+        let months: Vec<u32> = if !self.by_month.is_empty() {
+            let mut months = self.by_month.clone();
+            months.sort_unstable();
+            months.dedup();
+            months
+        } else if self.by_day.is_empty() && self.by_month_day.is_empty() {
+            vec![start.month()]
+        } else {
+            (1..=12).collect()
+        };
+        months
+            .into_iter()
+            .filter_map(|month| first.with_month(month))
+            .collect()
+    }
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    let first = date.with_day(1).unwrap_or(date);
+    first
+        .checked_add_months(Months::new(1))
+        .map_or(31, |next| (next - first).num_days() as u32)
+}
+
+/// `MO`, `2TU`, `-1FR`: a weekday with an optional ordinal in front
+fn parse_by_day(value: &str) -> Option<(Option<i32>, Weekday)> {
+    let value = value.trim();
+    let split = value.len().checked_sub(2)?;
+    let (ordinal, day) = (value.get(..split)?, value.get(split..)?);
+    let ordinal = if ordinal.is_empty() {
+        None
+    } else {
+        Some(ordinal.parse().ok()?)
+    };
+    Some((ordinal, parse_weekday(day)?))
+}
+
+/// `MO`, `TU`, ...
+fn parse_weekday(day: &str) -> Option<Weekday> {
+    Some(match day.to_ascii_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+/// Parses all `VEVENT`s in a calendar file.
+pub fn parse_events(ics: &str) -> Vec<VEvent> {
+    let mut events = Vec::new();
+    let mut current: Option<VEvent> = None;
+    // components inside the event, such as VALARM, have properties of their own
+    let mut nested = 0u32;
+
+    for line in unfold_lines(ics) {
+        let Some((name, params, value)) = parse_property(&line) else {
+            continue;
+        };
+        let is_event = value.eq_ignore_ascii_case("VEVENT");
+
+        match name.as_str() {
+            "BEGIN" if is_event => current = Some(VEvent::default()),
+            "END" if is_event => events.extend(current.take()),
+            "BEGIN" => nested += 1,
+            "END" => nested = nested.saturating_sub(1),
+            _ => {
+                if let Some(event) = current.as_mut()
+                    && nested == 0
+                {
+                    apply_property(event, &name, &params, value);
+                }
+            }
+        }
+    }
+
+    events
+}
+
+fn apply_property(event: &mut VEvent, name: &str, params: &[(String, &str)], value: &str) {
+    let tzid = params
+        .iter()
+        .find(|(k, _)| k == "TZID")
+        .map(|(_, v)| v.trim_matches('"'));
+
+    match name {
+        "UID" => event.uid = value.to_string(),
+        "SUMMARY" => event.summary = unescape_text(value),
+        "LOCATION" => event.location = unescape_text(value),
+        "STATUS" => event.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+        "DTSTART" => {
+            if let Some((dt, tz, all_day)) = parse_date_time(value, tzid) {
+                event.start = Some((dt, tz));
+                event.all_day = all_day;
+            }
+        }
+        "DTEND" => event.end = parse_date_time(value, tzid).map(|(dt, _, _)| dt),
+        "DURATION" => event.duration = parse_duration(value),
+        "RRULE" => event.rrule = RRule::parse(value),
+        "EXDATE" => event.exdates.extend(
+            value
+                .split(',')
+                .filter_map(|v| parse_date_time(v, tzid).map(|(dt, _, _)| dt)),
+        ),
+        "RECURRENCE-ID" => event.recurrence_id = parse_date_time(value, tzid).map(|(dt, _, _)| dt),
+        _ => {}
+    }
+}
+
+/// Edited instances of recurring events, by UID. Their original start times are skipped
+/// when expanding the recurring event.
+pub fn overridden_instances(events: &[VEvent]) -> Vec<(&str, NaiveDateTime)> {
+    events
+        .iter()
+        .filter_map(|e| Some((e.uid.as_str(), e.recurrence_id?)))
+        .collect()
+}
+
+/// Lines starting with whitespace continue the previous line.
+fn unfold_lines(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.lines() {
+        if let Some(rest) = line.strip_prefix([' ', '\t'])
+            && let Some(last) = lines.last_mut()
+        {
+            last.push_str(rest);
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+    lines
+}
+
+/// `NAME;PARAM=value;PARAM="quoted:value":VALUE`
+fn parse_property(line: &str) -> Option<(String, Vec<(String, &str)>, &str)> {
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(i, c)| {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ':' if !in_quotes => return Some(i),
+            _ => {}
+        }
+        None
+    })?;
+
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    let params = parts
+        .filter_map(|p| p.split_once('='))
+        .map(|(k, v)| (k.to_ascii_uppercase(), v))
+        .collect();
+
+    Some((name, params, value))
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => {}
+        }
+    }
+    result
+}
+
+/// `20250131`, `20250131T093000`, or `20250131T093000Z`.
+/// Returns the time as written, its timezone and whether it's a date without time.
+fn parse_date_time(value: &str, tzid: Option<&str>) -> Option<(NaiveDateTime, EventTz, bool)> {
+    let value = value.trim();
+
+    if value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some((date.and_time(NaiveTime::MIN), EventTz::Floating, true));
+    }
+
+    let (value, utc) = value
+        .strip_suffix('Z')
+        .map_or((value, false), |v| (v, true));
+    let dt = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+
+    let tz = if utc {
+        EventTz::Utc
+    } else if let Some(tzid) = tzid {
+        // some clients prefix the IANA name with a path
+        let name = tzid.rsplit_once("/zoneinfo/").map_or(tzid, |(_, n)| n);
+        Tz::from_str(name).map_or_else(
+            |_| {
+                log::debug!("Unknown timezone \"{tzid}\", using local time");
+                EventTz::Floating
+            },
+            EventTz::Named,
+        )
+    } else {
+        EventTz::Floating
+    };
+
+    Some((dt, tz, false))
+}
+
+/// `P1W`, `P1D`, `PT1H30M`, `-PT15M`, `P1DT12H`
+fn parse_duration(value: &str) -> Option<TimeDelta> {
+    let value = value.trim();
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let value = value.strip_prefix('P')?;
+
+    let mut total = TimeDelta::zero();
+    let mut number = String::new();
+    for c in value.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => {}
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                let part = match unit {
+                    'W' => TimeDelta::try_weeks(n),
+                    'D' => TimeDelta::try_days(n),
+                    'H' => TimeDelta::try_hours(n),
+                    'M' => TimeDelta::try_minutes(n),
+                    'S' => TimeDelta::try_seconds(n),
+                    _ => None,
+                }?;
+                total = total.checked_add(&part)?;
+            }
+        }
+    }
+
+    Some(if negative { -total } else { total })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A one-hour event on Monday, 2025-01-06 at 09:00, with the given extra properties.
+    fn event(lines: &[&str]) -> VEvent {
+        event_starting(
+            "DTSTART:20250106T090000",
+            &[&["DTEND:20250106T100000"], lines].concat(),
+        )
+    }
+
+    fn event_starting(dtstart: &str, lines: &[&str]) -> VEvent {
+        let mut ics = format!("BEGIN:VEVENT\nUID:test\n{dtstart}\n");
+        for line in lines {
+            ics.push_str(line);
+            ics.push('\n');
+        }
+        ics.push_str("END:VEVENT\n");
+
+        let mut events = parse_events(&ics);
+        assert_eq!(events.len(), 1);
+        events.remove(0)
+    }
+
+    fn local(year: i32, month: u32, day: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap()
+    }
+
+    /// 09:00 on the given day of January 2025
+    fn jan(day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 1, day)
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap()
+    }
+
+    fn starts(event: &VEvent, from: DateTime<Local>, to: DateTime<Local>) -> Vec<NaiveDateTime> {
+        event
+            .occurrences(from, to, &[])
+            .iter()
+            .map(|o| o.start.naive_local())
+            .collect()
+    }
+
+    fn starts_in_january(event: &VEvent) -> Vec<NaiveDateTime> {
+        starts(event, local(2025, 1, 1), local(2025, 2, 1))
+    }
+
+    fn at_nine(year: i32, month: u32, day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn count() {
+        let event = event(&["RRULE:FREQ=DAILY;COUNT=3"]);
+        assert_eq!(starts_in_january(&event), [jan(6), jan(7), jan(8)]);
+    }
+
+    #[test]
+    fn until() {
+        let event = event(&["RRULE:FREQ=WEEKLY;UNTIL=20250120T090000"]);
+        assert_eq!(starts_in_january(&event), [jan(6), jan(13), jan(20)]);
+    }
+
+    #[test]
+    fn by_day() {
+        let event = event(&["RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR;COUNT=5"]);
+        assert_eq!(
+            starts_in_january(&event),
+            [jan(6), jan(8), jan(10), jan(13), jan(15)]
+        );
+    }
+
+    #[test]
+    fn interval() {
+        let event = event(&["RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=TU"]);
+        assert_eq!(starts_in_january(&event), [jan(7), jan(21)]);
+    }
+
+    #[test]
+    fn until_utc() {
+        let event = event_starting(
+            "DTSTART;TZID=Etc/UTC:20250106T090000",
+            &["RRULE:FREQ=WEEKLY;UNTIL=20250120T085959Z"],
+        );
+        let in_local = |day| {
+            Utc.from_utc_datetime(&jan(day))
+                .with_timezone(&Local)
+                .naive_local()
+        };
+        assert_eq!(starts_in_january(&event), [in_local(6), in_local(13)]);
+    }
+
+    #[test]
+    fn monthly_by_day() {
+        let event = event(&["RRULE:FREQ=MONTHLY;BYDAY=2TU,-1FR;COUNT=4"]);
+        assert_eq!(
+            starts(&event, local(2025, 1, 1), local(2026, 1, 1)),
+            [
+                at_nine(2025, 1, 14),
+                at_nine(2025, 1, 31),
+                at_nine(2025, 2, 11),
+                at_nine(2025, 2, 28)
+            ]
+        );
+    }
+
+    #[test]
+    fn monthly_by_month_day() {
+        let event = event(&["RRULE:FREQ=MONTHLY;BYMONTHDAY=1,-1;COUNT=3"]);
+        assert_eq!(
+            starts(&event, local(2025, 1, 1), local(2026, 1, 1)),
+            [
+                at_nine(2025, 1, 31),
+                at_nine(2025, 2, 1),
+                at_nine(2025, 2, 28)
+            ]
+        );
+    }
+
+    #[test]
+    fn monthly_skips_missing_days() {
+        let event = event_starting("DTSTART:20250131T090000", &["RRULE:FREQ=MONTHLY;COUNT=3"]);
+        assert_eq!(
+            starts(&event, local(2025, 1, 1), local(2026, 1, 1)),
+            [
+                at_nine(2025, 1, 31),
+                at_nine(2025, 3, 31),
+                at_nine(2025, 5, 31)
+            ]
+        );
+    }
+
+    #[test]
+    fn yearly_skips_missing_days() {
+        let event = event_starting("DTSTART:20240229T090000", &["RRULE:FREQ=YEARLY"]);
+        assert_eq!(
+            starts(&event, local(2024, 1, 1), local(2030, 1, 1)),
+            [at_nine(2024, 2, 29), at_nine(2028, 2, 29)]
+        );
+    }
+
+    #[test]
+    fn yearly_by_month() {
+        let event = event(&["RRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=4TH;COUNT=2"]);
+        assert_eq!(
+            starts(&event, local(2025, 1, 1), local(2030, 1, 1)),
+            [at_nine(2025, 11, 27), at_nine(2026, 11, 26)]
+        );
+    }
+
+    #[test]
+    fn unsupported_by_part() {
+        // the rule is dropped, leaving the first instance
+        let event = event(&["RRULE:FREQ=MONTHLY;BYDAY=MO,TU;BYSETPOS=-1"]);
+        assert_eq!(
+            starts(&event, local(2025, 1, 1), local(2026, 1, 1)),
+            [jan(6)]
+        );
+    }
+
+    #[test]
+    fn exdate() {
+        // excluded instances still count towards COUNT
+        let event = event(&["RRULE:FREQ=DAILY;COUNT=3", "EXDATE:20250107T090000"]);
+        assert_eq!(starts_in_january(&event), [jan(6), jan(8)]);
+    }
+
+    #[test]
+    fn overflow() {
+        for rrule in [
+            "RRULE:FREQ=DAILY;INTERVAL=100000000",
+            "RRULE:FREQ=WEEKLY;INTERVAL=4000000000",
+            "RRULE:FREQ=WEEKLY;INTERVAL=4000000000;BYDAY=MO",
+            "RRULE:FREQ=MONTHLY;INTERVAL=4000000000",
+            "RRULE:FREQ=YEARLY;INTERVAL=4000000000",
+        ] {
+            let event = event(&[rrule]);
+            assert_eq!(
+                starts(&event, local(2025, 1, 1), local(2030, 1, 1)),
+                [jan(6)],
+                "{rrule}"
+            );
+        }
+    }
+
+    #[test]
+    fn overflowing_duration() {
+        assert_eq!(parse_duration("P999999999999999W"), None);
+        assert_eq!(parse_duration("P1DT12H"), TimeDelta::try_hours(36));
+    }
+}
@@ -0,0 +1,238 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::{Arc, mpsc},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeDelta};
+use wlx_common::{config::GeneralConfig, config_io::resolve_path, overlays::ToastTopic};
+
+use crate::{overlays::toast::Toast, state::AppState};
+
+mod ical;
+
+/// Files are re-read this often, to pick up changes synced in the meantime
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// How far ahead to look for events
+const HORIZON_DAYS: i64 = 7;
+
+/// How deep to look into directories for `.ics` files.
+/// vdirsyncer keeps one directory per calendar.
+const MAX_DEPTH: u32 = 3;
+
+/// Seconds each event toast stays up. Events due at once are shown one after the other.
+const TOAST_TIMEOUT: f32 = 10.;
+
+#[derive(Clone, Debug)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub summary: String,
+    pub location: String,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub all_day: bool,
+}
+
+impl CalendarEvent {
+    /// `09:00 - 10:30`, or empty for all-day events
+    pub fn format_time(&self, clock_12h: bool) -> String {
+        if self.all_day {
+            return String::new();
+        }
+        let format = if clock_12h { "%I:%M %p" } else { "%H:%M" };
+        format!(
+            "{} - {}",
+            self.start.format(format),
+            self.end.format(format)
+        )
+    }
+}
+
+/// Upcoming events from the iCalendar files in `calendar_paths`.
+/// Files are parsed on a background thread, so large calendars don't stall frames.
+pub struct Calendar {
+    paths: Arc<[PathBuf]>,
+    /// Sorted by start time
+    events: Vec<CalendarEvent>,
+    loading: Option<mpsc::Receiver<Vec<CalendarEvent>>>,
+    next_refresh: Instant,
+
+    /// From `calendar_toast_minutes`
+    toast_lead: Option<TimeDelta>,
+    /// Events that were already announced, by UID and start time
+    notified: HashSet<(String, i64)>,
+}
+
+impl Calendar {
+    pub fn new(config: &GeneralConfig) -> Self {
+        Self {
+            paths: config
+                .calendar_paths
+                .iter()
+                .map(|p| resolve_path(p))
+                .collect(),
+            events: Vec::new(),
+            loading: None,
+            next_refresh: Instant::now(),
+            toast_lead: (config.calendar_toast_minutes > 0)
+                .then(|| TimeDelta::minutes(i64::from(config.calendar_toast_minutes))),
+            notified: HashSet::new(),
+        }
+    }
+
+    /// Events that have not ended yet, soonest first.
+    pub fn upcoming(&self) -> impl Iterator<Item = &CalendarEvent> {
+        let now = Local::now();
+        self.events.iter().filter(move |e| e.end > now)
+    }
+
+    fn poll_loading(&mut self) {
+        if self.paths.is_empty() {
+            return;
+        }
+
+        if let Some(receiver) = self.loading.as_ref() {
+            match receiver.try_recv() {
+                Ok(events) => {
+                    log::debug!("Loaded {} upcoming calendar events", events.len());
+                    self.events = events;
+                    self.loading = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+                Err(mpsc::TryRecvError::Disconnected) => self.loading = None,
+            }
+            return;
+        }
+
+        if Instant::now() < self.next_refresh {
+            return;
+        }
+        self.next_refresh = Instant::now() + REFRESH_INTERVAL;
+
+        let (sender, receiver) = mpsc::channel();
+        let paths = self.paths.clone();
+        std::thread::spawn(move || {
+            let _ = sender.send(load_events(&paths));
+        });
+        self.loading = Some(receiver);
+    }
+
+    /// Timed events starting within `calendar_toast_minutes` that were not announced yet.
+    fn take_toasts(&mut self) -> Vec<CalendarEvent> {
+        let Some(lead) = self.toast_lead else {
+            return Vec::new();
+        };
+
+        let now = Local::now();
+        let due: Vec<CalendarEvent> = self
+            .events
+            .iter()
+            .filter(|e| !e.all_day && e.start > now && e.start - lead <= now)
+            .filter(|e| self.notified.insert((e.uid.clone(), e.start.timestamp())))
+            .cloned()
+            .collect();
+
+        if !due.is_empty() {
+            let yesterday = (now - TimeDelta::days(1)).timestamp();
+            self.notified.retain(|(_, start)| *start > yesterday);
+        }
+        due
+    }
+}
+
+/// Call once per frame.
+pub fn tick(app: &mut AppState) {
+    app.calendar.poll_loading();
+
+    let now = Instant::now();
+    for (i, event) in app.calendar.take_toasts().into_iter().enumerate() {
+        let mut body = event.format_time(app.session.config.clock_12h);
+        if !event.location.is_empty() {
+            body = format!("{body}\n{}", event.location);
+        }
+
+        let mut toast = Toast::new(ToastTopic::Calendar, event.summary, body)
+            .with_timeout(TOAST_TIMEOUT)
+            .with_sound(true);
+        toast.haptics = true;
+        toast.submit_at(app, now + Duration::from_secs_f32(TOAST_TIMEOUT) * i as u32);
+    }
+}
+
+fn collect_ics_files(path: &Path, depth: u32, files: &mut Vec<PathBuf>) {
+    if !path.is_dir() {
+        // files that were configured explicitly don't need the extension
+        if depth == 0
+            || path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("ics"))
+        {
+            files.push(path.to_path_buf());
+        }
+        return;
+    }
+    if depth > MAX_DEPTH {
+        return;
+    }
+
+    let entries = match std::fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("Could not read calendar directory {}: {e}", path.display());
+            return;
+        }
+    };
+    for entry in entries.flatten() {
+        collect_ics_files(&entry.path(), depth + 1, files);
+    }
+}
+
+fn load_events(paths: &[PathBuf]) -> Vec<CalendarEvent> {
+    let mut files = Vec::new();
+    for path in paths {
+        collect_ics_files(path, 0, &mut files);
+    }
+
+    let mut vevents = Vec::new();
+    for file in files {
+        match std::fs::read_to_string(&file) {
+            Ok(ics) => vevents.extend(ical::parse_events(&ics)),
+            Err(e) => log::warn!("Could not read calendar {}: {e}", file.display()),
+        }
+    }
+
+    let overrides = ical::overridden_instances(&vevents);
+
+    let now = Local::now();
+    let to = now + TimeDelta::days(HORIZON_DAYS);
+
+    let mut events = Vec::new();
+    for vevent in &vevents {
+        // edited instances are listed on their own
+        let skip: Vec<NaiveDateTime> = if vevent.is_override() {
+            Vec::new()
+        } else {
+            overrides
+                .iter()
+                .filter(|(uid, _)| *uid == vevent.uid)
+                .map(|(_, start)| *start)
+                .collect()
+        };
+
+        for occurrence in vevent.occurrences(now, to, &skip) {
+            events.push(CalendarEvent {
+                uid: vevent.uid.clone(),
+                summary: vevent.summary.clone(),
+                location: vevent.location.clone(),
+                start: occurrence.start,
+                end: occurrence.end,
+                all_day: vevent.all_day,
+            });
+        }
+    }
+
+    events.sort_by_key(|e| e.start);
+    events
+}
@@ -0,0 +1,549 @@
+use std::{
+    borrow::Cow,
+    collections::{HashSet, VecDeque},
+    hash::{DefaultHasher, Hash, Hasher},
+    io::{Cursor, PipeReader, Read},
+    os::fd::AsRawFd,
+    sync::{Arc, mpsc},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, bail};
+use chrono::{DateTime, Local};
+use wl_clipboard_rs::paste::{self as wl_paste, ClipboardType, MimeType, Seat};
+use wlx_common::config::GeneralConfig;
+
+use crate::{
+    overlays::clipboard::notify_history_changed,
+    state::AppState,
+    subsystem::{
+        hid::{SHIFT, VirtualKey},
+        input::HeldInput,
+    },
+};
+
+/// How often the desktop clipboard is checked for new content
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Give the clipboard owner some time to take over before pasting
+const PASTE_DELAY: Duration = Duration::from_millis(150);
+
+/// Give the focused app some time to fetch what's pasted before the clipboard is restored
+const RESTORE_DELAY: Duration = Duration::from_millis(500);
+
+/// Larger contents are not recorded
+const MAX_CONTENT_BYTES: usize = 64 * 1024 * 1024;
+
+/// A WayVR app that doesn't send its selection for this long is skipped
+const SELECTION_TIMEOUT_MS: i32 = 2000;
+
+/// Mime types offered for text, in order of preference
+pub const TEXT_MIME_TYPES: [&str; 5] = [
+    "text/plain;charset=utf-8",
+    "UTF8_STRING",
+    "text/plain",
+    "TEXT",
+    "STRING",
+];
+
+pub const IMAGE_MIME_TYPE: &str = "image/png";
+
+/// Offered by password managers along with secrets that should stay out of clipboard histories
+pub const PASSWORD_HINT_MIME_TYPE: &str = "x-kde-passwordManagerHint";
+
+#[derive(Clone)]
+pub struct ClipboardImage {
+    pub width: u32,
+    pub height: u32,
+    /// RGBA8
+    pub pixels: Arc<[u8]>,
+    hash: u64,
+}
+
+impl ClipboardImage {
+    pub fn from_rgba(width: u32, height: u32, pixels: Arc<[u8]>) -> Self {
+        let mut hasher = DefaultHasher::new();
+        pixels.hash(&mut hasher);
+        Self {
+            width,
+            height,
+            pixels,
+            hash: hasher.finish(),
+        }
+    }
+
+    pub fn from_png(data: &[u8]) -> anyhow::Result<Self> {
+        let image =
+            image::load_from_memory_with_format(data, image::ImageFormat::Png)?.into_rgba8();
+        Ok(Self::from_rgba(
+            image.width(),
+            image.height(),
+            image.into_raw().into(),
+        ))
+    }
+
+    pub fn to_png(&self) -> anyhow::Result<Vec<u8>> {
+        let image = image::RgbaImage::from_raw(self.width, self.height, self.pixels.to_vec())
+            .context("pixel data does not match dimensions")?;
+        let mut png = Vec::new();
+        image.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)?;
+        Ok(png)
+    }
+}
+
+#[derive(Clone)]
+pub enum ClipboardContent {
+    Text(Arc<str>),
+    Image(ClipboardImage),
+}
+
+impl ClipboardContent {
+    fn same_as(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Text(a), Self::Text(b)) => a == b,
+            (Self::Image(a), Self::Image(b)) => {
+                a.width == b.width && a.height == b.height && a.hash == b.hash
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Waits for the clipboard owner to take over, keys typed in the meantime wait along.
+struct PendingPaste {
+    content: ClipboardContent,
+    /// Held back after it was requested, until the next paste was
+    typed_after: Vec<HeldInput>,
+}
+
+pub struct ClipboardEntry {
+    pub id: usize,
+    pub content: ClipboardContent,
+    pub copied: DateTime<Local>,
+}
+
+/// Recently copied texts and images, newest first.
+/// Collected from the desktop clipboard and from WayVR apps.
+pub struct ClipboardHistory {
+    entries: VecDeque<ClipboardEntry>,
+    next_id: usize,
+    max_entries: usize,
+
+    sender: mpsc::Sender<ClipboardContent>,
+    receiver: mpsc::Receiver<ClipboardContent>,
+
+    /// Owns the desktop clipboard after selecting an entry
+    desktop: Option<arboard::Clipboard>,
+    /// The first one is on the clipboard, and pasted at `paste_at`
+    pastes: VecDeque<PendingPaste>,
+    paste_at: Option<Instant>,
+
+    /// What was on the clipboard before `paste_text` replaced it
+    restore: Option<ClipboardContent>,
+    restore_at: Option<Instant>,
+}
+
+impl ClipboardHistory {
+    pub fn new(config: &GeneralConfig) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let max_entries = config.clipboard_history_size as usize;
+
+        if max_entries > 0 {
+            let sender = sender.clone();
+            std::thread::spawn(move || poll_desktop(&sender));
+        }
+
+        Self {
+            entries: VecDeque::new(),
+            next_id: 0,
+            max_entries,
+            sender,
+            receiver,
+            desktop: None,
+            pastes: VecDeque::new(),
+            paste_at: None,
+            restore: None,
+            restore_at: None,
+        }
+    }
+
+    /// For what WayVR apps copy. Starts a thread to read their selections.
+    pub fn selection_reader(&self) -> SelectionReader {
+        let (requests, receiver) = mpsc::channel::<(PipeReader, String)>();
+        let sender = self.sender.clone();
+
+        std::thread::spawn(move || {
+            while let Ok((reader, mime_type)) = receiver.recv() {
+                match read_selection(&reader, &mime_type) {
+                    Ok(Some(content)) => {
+                        if sender.send(content).is_err() {
+                            return;
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => log::warn!("Could not read clipboard of WayVR app: {e:?}"),
+                }
+            }
+        });
+
+        SelectionReader { requests }
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &ClipboardEntry> {
+        self.entries.iter()
+    }
+
+    pub fn get(&self, id: usize) -> Option<&ClipboardEntry> {
+        self.entries.iter().find(|e| e.id == id)
+    }
+
+    fn remove(&mut self, id: usize) -> Option<ClipboardEntry> {
+        let idx = self.entries.iter().position(|e| e.id == id)?;
+        self.entries.remove(idx)
+    }
+
+    /// Returns true if the history changed.
+    /// Copying something again moves it to the top.
+    fn push(&mut self, content: ClipboardContent) -> bool {
+        if self.max_entries == 0 {
+            return false;
+        }
+        if self
+            .entries
+            .front()
+            .is_some_and(|e| e.content.same_as(&content))
+        {
+            return false;
+        }
+        self.entries.retain(|e| !e.content.same_as(&content));
+
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.entries.push_front(ClipboardEntry {
+            id,
+            content,
+            copied: Local::now(),
+        });
+        self.entries.truncate(self.max_entries);
+        true
+    }
+
+    /// The newest known copy, or the text on the desktop clipboard if there's no history.
+    fn current(&mut self) -> Option<ClipboardContent> {
+        if let Some(entry) = self.entries.front() {
+            return Some(entry.content.clone());
+        }
+        let desktop = match self.desktop.as_mut() {
+            Some(desktop) => desktop,
+            None => self.desktop.insert(arboard::Clipboard::new().ok()?),
+        };
+        desktop
+            .get_text()
+            .ok()
+            .map(|text| ClipboardContent::Text(text.into()))
+    }
+
+    fn set_desktop(&mut self, content: &ClipboardContent) -> anyhow::Result<()> {
+        let desktop = match self.desktop.as_mut() {
+            Some(desktop) => desktop,
+            None => self.desktop.insert(arboard::Clipboard::new()?),
+        };
+
+        match content {
+            ClipboardContent::Text(text) => desktop.set_text(text.as_ref())?,
+            ClipboardContent::Image(image) => desktop.set_image(arboard::ImageData {
+                width: image.width as usize,
+                height: image.height as usize,
+                bytes: Cow::Borrowed(&image.pixels),
+            })?,
+        }
+        Ok(())
+    }
+}
+
+/// Call once per frame.
+pub fn tick(app: &mut AppState) {
+    let mut changed = false;
+    while let Ok(content) = app.clipboard.receiver.try_recv() {
+        changed |= app.clipboard.push(content);
+    }
+    if changed {
+        notify_history_changed(app);
+    }
+
+    let now = Instant::now();
+    if app.clipboard.paste_at.is_some_and(|t| t <= now)
+        && let Some(done) = app.clipboard.pastes.pop_front()
+    {
+        app.clipboard.paste_at = None;
+        paste(app);
+
+        if let Some(next) = app.clipboard.pastes.front() {
+            let content = next.content.clone();
+            app.hid_provider
+                .replay(app.wvr_server.as_mut(), done.typed_after);
+            set_everywhere(app, &content);
+            app.clipboard.paste_at = Some(now + PASTE_DELAY);
+        } else {
+            let typed_after = app.hid_provider.release();
+            app.hid_provider
+                .replay(app.wvr_server.as_mut(), typed_after);
+            if app.clipboard.restore.is_some() {
+                app.clipboard.restore_at = Some(now + RESTORE_DELAY);
+            }
+        }
+    }
+
+    if app.clipboard.paste_at.is_none() && app.clipboard.restore_at.is_some_and(|t| t <= now) {
+        app.clipboard.restore_at = None;
+        if let Some(content) = app.clipboard.restore.take() {
+            set_everywhere(app, &content);
+            // newest again, even if the pasted text made it into the history
+            if app.clipboard.push(content) {
+                notify_history_changed(app);
+            }
+        }
+    }
+}
+
+/// Sets the clipboard of the desktop and of WayVR apps.
+fn set_everywhere(app: &mut AppState, content: &ClipboardContent) {
+    let _ = app
+        .clipboard
+        .set_desktop(content)
+        .inspect_err(|e| log::warn!("Could not set desktop clipboard: {e:?}"));
+
+    if let Some(wvr_server) = app.wvr_server.as_mut() {
+        let _ = wvr_server
+            .set_clipboard(content)
+            .inspect_err(|e| log::warn!("Could not set WayVR clipboard: {e:?}"));
+    }
+}
+
+/// Puts an entry from the history onto the clipboard of the desktop and of WayVR apps.
+/// With `paste`, it's also pasted into whatever has keyboard focus.
+pub fn select(app: &mut AppState, id: usize, paste: bool) -> anyhow::Result<()> {
+    let content = app
+        .clipboard
+        .get(id)
+        .with_context(|| format!("no clipboard entry with id {id}"))?
+        .content
+        .clone();
+
+    // chosen on purpose, so it stays
+    app.clipboard.restore = None;
+    app.clipboard.restore_at = None;
+
+    if paste {
+        queue_paste(app, content.clone());
+    } else {
+        set_everywhere(app, &content);
+    }
+    if app.clipboard.push(content) {
+        notify_history_changed(app);
+    }
+    Ok(())
+}
+
+/// Types text the keymap can't, such as emoji, by pasting it.
+/// What was on the clipboard is put back once it's pasted.
+pub fn paste_text(app: &mut AppState, text: &str) {
+    // when pasting several times in a row, the clipboard already holds the previous paste
+    if app.clipboard.restore.is_none() {
+        app.clipboard.restore = app.clipboard.current();
+    }
+    app.clipboard.restore_at = None;
+
+    queue_paste(app, ClipboardContent::Text(text.into()));
+}
+
+/// Pastes `content` once earlier pastes are done and the clipboard owner took over.
+/// Keys typed until then are held back, so they end up after the pasted content.
+fn queue_paste(app: &mut AppState, content: ClipboardContent) {
+    if let Some(last) = app.clipboard.pastes.back_mut() {
+        last.typed_after = app.hid_provider.take_held();
+    } else {
+        set_everywhere(app, &content);
+        app.clipboard.paste_at = Some(Instant::now() + PASTE_DELAY);
+        app.hid_provider.hold();
+    }
+    app.clipboard.pastes.push_back(PendingPaste {
+        content,
+        typed_after: Vec::new(),
+    });
+}
+
+/// Removes an entry from the history. The clipboard itself is left alone.
+pub fn remove(app: &mut AppState, id: usize) {
+    if app.clipboard.remove(id).is_some() {
+        notify_history_changed(app);
+    }
+}
+
+pub fn clear(app: &mut AppState) {
+    app.clipboard.entries.clear();
+    notify_history_changed(app);
+}
+
+/// Shift+Insert, ahead of any held back keys
+fn paste(app: &mut AppState) {
+    app.hid_provider.replay(
+        app.wvr_server.as_mut(),
+        [
+            HeldInput::Modifiers(SHIFT),
+            HeldInput::Key(VirtualKey::Insert, true),
+            HeldInput::Key(VirtualKey::Insert, false),
+            HeldInput::Modifiers(0),
+        ],
+    );
+}
+
+fn poll_desktop(sender: &mpsc::Sender<ClipboardContent>) {
+    let mut clipboard = match arboard::Clipboard::new() {
+        Ok(clipboard) => clipboard,
+        Err(e) => {
+            log::warn!("Clipboard history won't include the desktop clipboard: {e}");
+            return;
+        }
+    };
+
+    let mut last: Option<ClipboardContent> = None;
+    let mut last_png = None;
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+
+        // only known on Wayland, elsewhere nothing but text is recorded
+        let mime_types = wayland_mime_types();
+        if mime_types
+            .as_ref()
+            .is_some_and(|m| m.contains(PASSWORD_HINT_MIME_TYPE))
+        {
+            continue;
+        }
+
+        let content = match clipboard.get_text() {
+            Ok(text) if !text.is_empty() && text.len() <= MAX_CONTENT_BYTES => {
+                Some(ClipboardContent::Text(text.into()))
+            }
+            Ok(_) => None,
+            Err(_) if mime_types.is_some_and(|m| m.contains(IMAGE_MIME_TYPE)) => {
+                read_wayland_png(&mut last_png)
+            }
+            Err(_) => None,
+        };
+
+        let Some(content) = content else {
+            continue;
+        };
+        if last.as_ref().is_some_and(|l| l.same_as(&content)) {
+            continue;
+        }
+        if sender.send(content.clone()).is_err() {
+            return;
+        }
+        last = Some(content);
+    }
+}
+
+/// What's offered on the Wayland clipboard, if the compositor lets us see it.
+fn wayland_mime_types() -> Option<HashSet<String>> {
+    std::env::var_os("WAYLAND_DISPLAY")?;
+    match wl_paste::get_mime_types(ClipboardType::Regular, Seat::Unspecified) {
+        Ok(mime_types) => Some(mime_types),
+        Err(wl_paste::Error::ClipboardEmpty) => Some(HashSet::new()),
+        Err(_) => None,
+    }
+}
+
+/// Decoding is what's expensive, so the same PNG as last time is recognized by its hash.
+fn read_wayland_png(last_hash: &mut Option<u64>) -> Option<ClipboardContent> {
+    let (pipe, _) = wl_paste::get_contents(
+        ClipboardType::Regular,
+        Seat::Unspecified,
+        MimeType::Specific(IMAGE_MIME_TYPE),
+    )
+    .ok()?;
+
+    let mut data = Vec::new();
+    pipe.take(MAX_CONTENT_BYTES as u64 + 1)
+        .read_to_end(&mut data)
+        .ok()?;
+    if data.is_empty() || data.len() > MAX_CONTENT_BYTES {
+        return None;
+    }
+
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    let hash = hasher.finish();
+    if last_hash.replace(hash) == Some(hash) {
+        return None;
+    }
+
+    ClipboardImage::from_png(&data)
+        .inspect_err(|e| log::warn!("Could not decode clipboard image: {e:?}"))
+        .ok()
+        .map(ClipboardContent::Image)
+}
+
+/// Reads what WayVR apps put on the clipboard, one at a time.
+#[derive(Clone)]
+pub struct SelectionReader {
+    requests: mpsc::Sender<(PipeReader, String)>,
+}
+
+impl SelectionReader {
+    /// `reader` is the receiving end of a selection request for `mime_type`.
+    pub fn read(&self, reader: PipeReader, mime_type: String) {
+        let _ = self.requests.send((reader, mime_type));
+    }
+}
+
+/// Returns None if there's nothing worth recording.
+fn read_selection(
+    mut reader: &PipeReader,
+    mime_type: &str,
+) -> anyhow::Result<Option<ClipboardContent>> {
+    let mut data = Vec::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let mut pollfd = libc::pollfd {
+            fd: reader.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: a single valid pollfd
+        match unsafe { libc::poll(&raw mut pollfd, 1, SELECTION_TIMEOUT_MS) } {
+            0 => bail!("timed out"),
+            -1 => {
+                let err = std::io::Error::last_os_error();
+                if err.kind() == std::io::ErrorKind::Interrupted {
+                    continue;
+                }
+                return Err(err.into());
+            }
+            _ => {}
+        }
+
+        let len = reader.read(&mut buf)?;
+        if len == 0 {
+            break;
+        }
+        if data.len() + len > MAX_CONTENT_BYTES {
+            return Ok(None);
+        }
+        data.extend_from_slice(&buf[..len]);
+    }
+    if data.is_empty() {
+        return Ok(None);
+    }
+
+    let content = if mime_type == IMAGE_MIME_TYPE {
+        ClipboardContent::Image(
+            ClipboardImage::from_png(&data).context("could not decode clipboard image")?,
+        )
+    } else {
+        ClipboardContent::Text(String::from_utf8_lossy(&data).into())
+    };
+    Ok(Some(content))
+}
@@ -1,39 +1,112 @@
-use std::time::Duration;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
 
 use anyhow::Context;
 use dbus::{
     Message,
-    arg::{PropMap, Variant},
-    blocking::Connection,
-    channel::MatchingReceiver,
-    message::MatchRule,
+    arg::{AppendAll, PropMap, Variant},
+    blocking::{Connection, stdintf::org_freedesktop_dbus::RequestNameReply},
+    channel::{MatchingReceiver, Sender},
+    message::{MatchRule, SignalArgs},
+    strings::BusName,
 };
 
 use crate::subsystem::dbus::{
-    fcitx5::OrgFcitxFcitxController1, notifications::OrgFreedesktopNotifications,
+    fcitx5::OrgFcitxFcitxController1,
+    mpris::MprisClient,
+    notifications::{
+        OrgFreedesktopNotifications, OrgFreedesktopNotificationsActionInvoked,
+        OrgFreedesktopNotificationsNotificationReplied,
+    },
 };
 
 mod fcitx5;
+pub mod mpris;
 mod notifications;
 
+pub use notifications::OrgFreedesktopNotificationsNotificationClosed;
+
 pub type DbusReceiveCallback = Box<dyn FnMut(Message, &Connection) -> bool + Send>;
 pub type DbusMatchCallback = Box<dyn FnMut((), &Connection, &Message) -> bool + Send>;
 
+const NOTIFICATIONS_NAME: &str = "org.freedesktop.Notifications";
+const NOTIFICATIONS_PATH: &str = "/org/freedesktop/Notifications";
+
 #[derive(Default)]
 pub struct DbusConnector {
     pub connection: Option<Connection>,
+    /// Owns `org.freedesktop.Notifications` while we act as the notification server
+    notification_server: Option<Connection>,
+    /// Set once another notification server took `org.freedesktop.Notifications` from us
+    notification_server_lost: Arc<AtomicBool>,
+    /// Started on first use
+    mpris: Option<MprisClient>,
 }
 
 impl DbusConnector {
     pub fn tick(&self) {
-        if let Some(c) = self.connection.as_ref() {
+        for c in [&self.connection, &self.notification_server]
+            .into_iter()
+            .flatten()
+        {
             let _ = c.process(Duration::ZERO);
         }
     }
 
+    /// Claims `org.freedesktop.Notifications` and routes all method calls to `callback`.
+    /// Returns `Ok(false)` if another notification server is already running.
+    /// A notification daemon started later may take the name over, see `take_server_lost`.
+    pub fn serve_notifications(&mut self, callback: DbusReceiveCallback) -> anyhow::Result<bool> {
+        let connection = Connection::new_session()?;
+
+        // never queue up, so that we don't grab the name once the user's daemon exits
+        match connection.request_name(NOTIFICATIONS_NAME, true, false, true)? {
+            RequestNameReply::PrimaryOwner | RequestNameReply::AlreadyOwner => {}
+            RequestNameReply::Exists | RequestNameReply::InQueue => return Ok(false),
+        }
+
+        let _ = connection.start_receive(MatchRule::new_method_call(), callback);
+
+        let lost = self.notification_server_lost.clone();
+        let _ = connection.start_receive(
+            MatchRule::new_signal("org.freedesktop.DBus", "NameLost"),
+            Box::new(move |msg, _| {
+                if msg
+                    .read1::<&str>()
+                    .is_ok_and(|name| name == NOTIFICATIONS_NAME)
+                {
+                    lost.store(true, Ordering::Relaxed);
+                }
+                true
+            }),
+        );
+
+        self.notification_server = Some(connection);
+        Ok(true)
+    }
+
+    /// Whether we're the notification server. Only then can actions and replies be sent.
+    pub const fn owns_notifications(&self) -> bool {
+        self.notification_server.is_some()
+    }
+
+    /// Returns true once after another notification server replaced us.
+    pub fn take_server_lost(&mut self) -> bool {
+        if !self.notification_server_lost.swap(false, Ordering::Relaxed) {
+            return false;
+        }
+        self.notification_server = None;
+        true
+    }
+
     pub fn become_monitor(
         &mut self,
-        rule: MatchRule<'static>,
+        rules: &[MatchRule<'static>],
         callback: DbusReceiveCallback,
     ) -> anyhow::Result<()> {
         let connection = self
@@ -50,12 +123,16 @@ impl DbusConnector {
         let result: Result<(), dbus::Error> = proxy.method_call(
             "org.freedesktop.DBus.Monitoring",
             "BecomeMonitor",
-            (vec![rule.match_str()], 0u32),
+            (
+                rules.iter().map(MatchRule::match_str).collect::<Vec<_>>(),
+                0u32,
+            ),
         );
 
         result?;
 
-        let _ = connection.start_receive(rule, callback);
+        // the bus only forwards messages matching the rules above
+        let _ = connection.start_receive(MatchRule::new(), callback);
 
         self.connection = Some(connection);
         Ok(())
@@ -77,6 +154,10 @@ impl DbusConnector {
         Ok(())
     }
 
+    pub fn mpris(&mut self) -> &MprisClient {
+        self.mpris.get_or_insert_with(MprisClient::start)
+    }
+
     pub fn fcitx_keymap() -> anyhow::Result<String> {
         let connection = Connection::new_session()?;
         let proxy = connection.with_proxy(
@@ -135,4 +216,67 @@ impl DbusConnector {
         proxy.close_notification(id)?;
         Ok(())
     }
+
+    pub fn notify_action_invoked(
+        &self,
+        destination: &str,
+        id: u32,
+        action_key: &str,
+    ) -> anyhow::Result<()> {
+        self.emit_notification_signal(
+            Some(destination),
+            &OrgFreedesktopNotificationsActionInvoked {
+                id,
+                action_key: action_key.to_string(),
+            },
+        )
+    }
+
+    pub fn notify_reply(&self, destination: &str, id: u32, text: &str) -> anyhow::Result<()> {
+        self.emit_notification_signal(
+            Some(destination),
+            &OrgFreedesktopNotificationsNotificationReplied {
+                id,
+                text: text.to_string(),
+            },
+        )
+    }
+
+    /// Broadcasts `NotificationClosed`. Only meaningful while we're the notification server.
+    pub fn notify_closed(&self, id: u32, reason: u32) -> anyhow::Result<()> {
+        if !self.owns_notifications() {
+            return Ok(());
+        }
+        self.emit_notification_signal(
+            None,
+            &OrgFreedesktopNotificationsNotificationClosed { id, reason },
+        )
+    }
+
+    /// Sends a notification signal from the connection owning the notification server name.
+    /// Signals are sent to a single client if `destination` is given.
+    fn emit_notification_signal<S: SignalArgs + AppendAll>(
+        &self,
+        destination: Option<&str>,
+        signal: &S,
+    ) -> anyhow::Result<()> {
+        let path = dbus::Path::new(NOTIFICATIONS_PATH).map_err(anyhow::Error::msg)?;
+
+        let mut msg = signal.to_emit_message(&path);
+        if let Some(destination) = destination {
+            msg.set_destination(Some(BusName::new(destination).map_err(anyhow::Error::msg)?));
+        }
+
+        // clients only trust signals coming from the server's own connection
+        let connection = self
+            .notification_server
+            .as_ref()
+            .context("Not the notification server")?;
+
+        connection
+            .send(msg)
+            .map_err(|()| anyhow::anyhow!("Could not send D-Bus signal"))?;
+        connection.channel().flush();
+        Ok(())
+    }
 }
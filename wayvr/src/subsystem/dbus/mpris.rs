@@ -0,0 +1,246 @@
+use std::{
+    sync::{Arc, Mutex, mpsc},
+    time::{Duration, Instant},
+};
+
+use dbus::{
+    arg::{PropMap, RefArg, prop_cast},
+    blocking::{Connection, stdintf::org_freedesktop_dbus::Properties},
+};
+
+const MPRIS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+const MPRIS_PATH: &str = "/org/mpris/MediaPlayer2";
+const PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const CALL_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlaybackStatus {
+    Playing,
+    Paused,
+    #[default]
+    Stopped,
+}
+
+impl PlaybackStatus {
+    fn parse(s: &str) -> Self {
+        match s {
+            "Playing" => Self::Playing,
+            "Paused" => Self::Paused,
+            _ => Self::Stopped,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MediaInfo {
+    /// Bus name without the `org.mpris.MediaPlayer2.` prefix
+    pub player: String,
+    pub status: PlaybackStatus,
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub art_url: Option<String>,
+    pub length: Option<Duration>,
+    position: Duration,
+    polled_at: Instant,
+}
+
+impl MediaInfo {
+    /// Playback position, extrapolated since the last poll while playing.
+    pub fn position(&self) -> Duration {
+        let mut position = self.position;
+        if self.status == PlaybackStatus::Playing {
+            position += self.polled_at.elapsed();
+        }
+        self.length.map_or(position, |length| position.min(length))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum MediaCommand {
+    PlayPause,
+    Next,
+    Previous,
+    /// Relative seek in microseconds
+    Seek(i64),
+}
+
+/// Tracks the active MPRIS media player on a background thread.
+pub struct MprisClient {
+    info: Arc<Mutex<Option<MediaInfo>>>,
+    commands: mpsc::Sender<MediaCommand>,
+}
+
+impl MprisClient {
+    pub fn start() -> Self {
+        let info = Arc::new(Mutex::new(None));
+        let (commands, receiver) = mpsc::channel();
+
+        std::thread::spawn({
+            let info = info.clone();
+            move || {
+                let connection = match Connection::new_session() {
+                    Ok(c) => c,
+                    Err(e) => {
+                        log::warn!("MPRIS: could not connect to session bus: {e}");
+                        return;
+                    }
+                };
+                run_mpris(&connection, &receiver, &info);
+            }
+        });
+
+        Self { info, commands }
+    }
+
+    pub fn info(&self) -> Option<MediaInfo> {
+        self.info.lock().ok().and_then(|i| i.clone())
+    }
+
+    pub fn send(&self, command: MediaCommand) {
+        let _ = self.commands.send(command);
+    }
+}
+
+fn run_mpris(
+    connection: &Connection,
+    receiver: &mpsc::Receiver<MediaCommand>,
+    info: &Mutex<Option<MediaInfo>>,
+) {
+    let mut active: Option<String> = None;
+
+    loop {
+        match receiver.recv_timeout(POLL_INTERVAL) {
+            Ok(command) => {
+                if let Some(player) = active.as_deref() {
+                    let _ = send_command(connection, player, command)
+                        .inspect_err(|e| log::warn!("MPRIS: {command:?} failed: {e}"));
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => return,
+        }
+
+        active = find_active_player(connection, active.as_deref());
+
+        let new_info = active
+            .as_deref()
+            .and_then(|player| read_player(connection, player).ok());
+
+        if let Ok(mut info) = info.lock() {
+            *info = new_info;
+        }
+    }
+}
+
+fn send_command(
+    connection: &Connection,
+    player: &str,
+    command: MediaCommand,
+) -> Result<(), dbus::Error> {
+    let proxy = connection.with_proxy(player, MPRIS_PATH, CALL_TIMEOUT);
+    match command {
+        MediaCommand::PlayPause => proxy.method_call(PLAYER_INTERFACE, "PlayPause", ()),
+        MediaCommand::Next => proxy.method_call(PLAYER_INTERFACE, "Next", ()),
+        MediaCommand::Previous => proxy.method_call(PLAYER_INTERFACE, "Previous", ()),
+        MediaCommand::Seek(offset) => proxy.method_call(PLAYER_INTERFACE, "Seek", (offset,)),
+    }
+}
+
+/// Prefers the current player while it's playing, then any playing player,
+/// then the current player, then any paused player.
+fn find_active_player(connection: &Connection, current: Option<&str>) -> Option<String> {
+    let proxy = connection.with_proxy(
+        "org.freedesktop.DBus",
+        "/org/freedesktop/DBus",
+        CALL_TIMEOUT,
+    );
+    let (names,): (Vec<String>,) = proxy
+        .method_call("org.freedesktop.DBus", "ListNames", ())
+        .ok()?;
+
+    let players: Vec<(String, PlaybackStatus)> = names
+        .into_iter()
+        .filter(|n| n.starts_with(MPRIS_PREFIX))
+        .map(|name| {
+            let status: String = connection
+                .with_proxy(&name, MPRIS_PATH, CALL_TIMEOUT)
+                .get(PLAYER_INTERFACE, "PlaybackStatus")
+                .unwrap_or_default();
+            (name, PlaybackStatus::parse(&status))
+        })
+        .collect();
+
+    let with_status = |status| {
+        players
+            .iter()
+            .find(|(_, s)| *s == status)
+            .map(|(n, _)| n.clone())
+    };
+    let current = current.and_then(|c| players.iter().find(|(n, _)| n == c));
+
+    if let Some((name, PlaybackStatus::Playing)) = current {
+        return Some(name.clone());
+    }
+    with_status(PlaybackStatus::Playing)
+        .or_else(|| current.map(|(n, _)| n.clone()))
+        .or_else(|| with_status(PlaybackStatus::Paused))
+        .or_else(|| players.first().map(|(n, _)| n.clone()))
+}
+
+fn read_player(connection: &Connection, player: &str) -> Result<MediaInfo, dbus::Error> {
+    let props = connection
+        .with_proxy(player, MPRIS_PATH, CALL_TIMEOUT)
+        .get_all(PLAYER_INTERFACE)?;
+
+    let status = prop_cast::<String>(&props, "PlaybackStatus")
+        .map_or(PlaybackStatus::Stopped, |s| PlaybackStatus::parse(s));
+    let position = props
+        .get("Position")
+        .and_then(|p| p.as_i64())
+        .map_or(Duration::ZERO, micros_to_duration);
+
+    let empty = PropMap::new();
+    let metadata = prop_cast::<PropMap>(&props, "Metadata").unwrap_or(&empty);
+
+    let string = |key: &str| {
+        metadata
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    // xesam:artist is a list of strings
+    let artist = metadata
+        .get("xesam:artist")
+        .and_then(|v| v.as_iter())
+        .map(|i| i.filter_map(|a| a.as_str()).collect::<Vec<_>>().join(", "))
+        .unwrap_or_default();
+
+    let length = metadata
+        .get("mpris:length")
+        .and_then(|v| v.as_i64().or_else(|| v.as_u64().map(|l| l as i64)))
+        .map(micros_to_duration);
+
+    Ok(MediaInfo {
+        player: player
+            .strip_prefix(MPRIS_PREFIX)
+            .unwrap_or(player)
+            .to_string(),
+        status,
+        title: string("xesam:title"),
+        artist,
+        album: string("xesam:album"),
+        art_url: Some(string("mpris:artUrl")).filter(|s| !s.is_empty()),
+        length,
+        position,
+        polled_at: Instant::now(),
+    })
+}
+
+fn micros_to_duration(micros: i64) -> Duration {
+    Duration::from_micros(micros.max(0) as u64)
+}
@@ -7,6 +7,7 @@ use input_linux::{
 };
 use libc::{input_event, timeval};
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::mem::transmute;
 use std::sync::LazyLock;
 use std::{fs::File, sync::atomic::AtomicBool};
@@ -612,6 +613,24 @@ impl XkbKeymap {
         state.key_get_utf8(xkb::Keycode::from(key as u32))
     }
 
+    /// Maps each character to the key and modifier that type it.
+    /// Only the base, Shift and AltGr levels are considered, lower levels win.
+    pub fn text_keys(&self) -> HashMap<char, (VirtualKey, KeyModifier)> {
+        let mut map = HashMap::new();
+        for modifier in [0, SHIFT, META] {
+            for key in VirtualKey::iter() {
+                let label = self.label_for_key(key, modifier);
+                let mut chars = label.chars();
+                if let (Some(c), None) = (chars.next(), chars.next())
+                    && !c.is_control()
+                {
+                    map.entry(c).or_insert((key, modifier));
+                }
+            }
+        }
+        map
+    }
+
     pub fn has_altgr(&self) -> bool {
         let state0 = xkb::State::new(&self.inner);
         let mut state1 = xkb::State::new(&self.inner);
@@ -0,0 +1,238 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    ffi::{CStr, CString},
+    os::{
+        fd::{AsRawFd, FromRawFd, OwnedFd},
+        unix::ffi::OsStrExt,
+    },
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use wlx_common::{
+    config::GeneralConfig,
+    config_io::{self, get_config_file_path},
+    overlays::ToastTopic,
+};
+
+use crate::{
+    backend::task::{OverlayTask, PanelReload, TaskType},
+    config::try_load_general_config,
+    overlays::toast::Toast,
+    state::{AppState, theme_defaults},
+};
+
+/// Editors tend to write a file in several steps; wait for them to finish.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+const WATCH_MASK: u32 = libc::IN_CLOSE_WRITE | libc::IN_MOVED_TO | libc::IN_DELETE;
+
+#[derive(Clone, Copy)]
+enum WatchedDir {
+    /// `~/.config/wayvr`
+    Root,
+    /// `~/.config/wayvr/conf.d`
+    ConfD,
+    /// `{theme_path}/gui`
+    Gui,
+}
+
+#[derive(Default)]
+struct PendingChanges {
+    /// Names of changed `gui/*.xml` files, without extension
+    gui_files: BTreeSet<Arc<str>>,
+    config: bool,
+    last_event: Option<Instant>,
+}
+
+/// Watches the config directory with inotify and rebuilds panels when their files change.
+pub struct HotReload {
+    fd: OwnedFd,
+    watches: HashMap<i32, WatchedDir>,
+    pending: PendingChanges,
+}
+
+impl HotReload {
+    pub fn new(config: &GeneralConfig) -> anyhow::Result<Self> {
+        // SAFETY: no pointers involved; the returned fd is checked below
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        if fd < 0 {
+            anyhow::bail!("inotify_init1: {}", std::io::Error::last_os_error());
+        }
+
+        let mut me = Self {
+            // SAFETY: fd is a freshly created descriptor that nothing else owns
+            fd: unsafe { OwnedFd::from_raw_fd(fd) },
+            watches: HashMap::new(),
+            pending: PendingChanges::default(),
+        };
+
+        let gui_dir = get_config_file_path(&config.theme_path).join("gui");
+        // so that newly created panels are picked up as well
+        let _ = std::fs::create_dir_all(&gui_dir);
+
+        me.add_watch(&config_io::get_config_root(), WatchedDir::Root)?;
+        me.add_watch(
+            &config_io::ConfigRoot::Generic.get_conf_d_path(),
+            WatchedDir::ConfD,
+        )?;
+        me.add_watch(&gui_dir, WatchedDir::Gui)?;
+
+        Ok(me)
+    }
+
+    fn add_watch(&mut self, path: &Path, dir: WatchedDir) -> anyhow::Result<()> {
+        let c_path = CString::new(path.as_os_str().as_bytes())?;
+
+        // SAFETY: c_path is a valid nul-terminated string
+        let wd =
+            unsafe { libc::inotify_add_watch(self.fd.as_raw_fd(), c_path.as_ptr(), WATCH_MASK) };
+        if wd < 0 {
+            anyhow::bail!(
+                "inotify_add_watch {}: {}",
+                path.display(),
+                std::io::Error::last_os_error()
+            );
+        }
+
+        log::debug!("Watching {} for changes", path.display());
+        self.watches.insert(wd, dir);
+        Ok(())
+    }
+
+    /// Call once per frame.
+    pub fn poll(&mut self, app: &mut AppState) {
+        self.read_events();
+
+        if self
+            .pending
+            .last_event
+            .is_none_or(|last| last.elapsed() < DEBOUNCE)
+        {
+            return;
+        }
+
+        let changes = std::mem::take(&mut self.pending);
+
+        let reload = if changes.config && reload_theme_colors(app) {
+            PanelReload::All
+        } else if !changes.gui_files.is_empty() {
+            PanelReload::Named(changes.gui_files.into_iter().collect())
+        } else {
+            return;
+        };
+
+        app.tasks
+            .enqueue(TaskType::Overlay(OverlayTask::ReloadPanels(reload)));
+    }
+
+    fn read_events(&mut self) {
+        const EVENT_SIZE: usize = std::mem::size_of::<libc::inotify_event>();
+
+        let mut buf = [0u8; 4096];
+        loop {
+            // SAFETY: buf is valid for writes of buf.len() bytes
+            let len =
+                unsafe { libc::read(self.fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len()) };
+            if len <= 0 {
+                // EAGAIN: no more events
+                return;
+            }
+
+            let len = len as usize;
+            let mut offset = 0;
+            while offset + EVENT_SIZE <= len {
+                // SAFETY: the kernel only writes whole events; bounds are checked above
+                let event: libc::inotify_event =
+                    unsafe { std::ptr::read_unaligned(buf[offset..].as_ptr().cast()) };
+
+                let name_start = offset + EVENT_SIZE;
+                let name_end = (name_start + event.len as usize).min(len);
+                offset = name_end;
+
+                let name = CStr::from_bytes_until_nul(&buf[name_start..name_end])
+                    .ok()
+                    .and_then(|n| n.to_str().ok())
+                    .unwrap_or_default();
+
+                if let Some(dir) = self.watches.get(&event.wd).copied() {
+                    self.on_file_changed(dir, name);
+                }
+            }
+        }
+    }
+
+    fn on_file_changed(&mut self, dir: WatchedDir, name: &str) {
+        let pending = &mut self.pending;
+        match (dir, name) {
+            (WatchedDir::Root, "config.yaml") | (WatchedDir::ConfD, _) => pending.config = true,
+            // the keyboard layout is rebuilt along with keyboard.xml
+            (WatchedDir::Root, "keyboard.yaml") => {
+                pending.gui_files.insert("keyboard".into());
+            }
+            (WatchedDir::Gui, name) => {
+                let Some(stem) = name.strip_suffix(".xml") else {
+                    return;
+                };
+                pending.gui_files.insert(stem.into());
+            }
+            _ => return,
+        }
+        log::debug!("Config file changed: {name}");
+        pending.last_event = Some(Instant::now());
+    }
+}
+
+const fn theme_colors(config: &mut GeneralConfig) -> [&mut Option<String>; 5] {
+    [
+        &mut config.color_text,
+        &mut config.color_accent,
+        &mut config.color_danger,
+        &mut config.color_faded,
+        &mut config.color_background,
+    ]
+}
+
+/// Re-reads the theme colors from the config files. Returns true if any changed.
+fn reload_theme_colors(app: &mut AppState) -> bool {
+    let mut config = match try_load_general_config() {
+        Ok(config) => config,
+        Err(e) => {
+            reload_error_toast(app, "config.yaml", &e);
+            return false;
+        }
+    };
+
+    let mut changed = false;
+    for (current, new) in theme_colors(&mut app.session.config)
+        .into_iter()
+        .zip(theme_colors(&mut config))
+    {
+        if current != new {
+            *current = new.take();
+            changed = true;
+        }
+    }
+
+    if changed {
+        log::info!("Theme colors changed, reloading panels");
+        app.wgui_globals.get().defaults = theme_defaults(&app.session.config);
+    }
+    changed
+}
+
+/// Shows why a file could not be reloaded. The previous version stays in use.
+pub fn reload_error_toast(app: &mut AppState, file: &str, err: &anyhow::Error) {
+    log::warn!("Could not reload {file}: {err:?}");
+
+    // the alternate format includes the cause, which has the line and column
+    Toast::new(
+        ToastTopic::Error,
+        "TOAST.RELOAD_FAILED".into(),
+        format!("{file}: {err:#}"),
+    )
+    .with_timeout(10.)
+    .with_sound(true)
+    .submit(app);
+}
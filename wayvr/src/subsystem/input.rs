@@ -1,17 +1,35 @@
-use super::hid::{self, HidProvider, VirtualKey};
+use std::collections::HashMap;
 
-use crate::{backend::wayvr::WvrServerState, subsystem::hid::XkbKeymap};
+use super::hid::{self, HidProvider, KeyModifier, META, SHIFT, VirtualKey};
+
+use crate::{backend::wayvr::WvrServerState, subsystem::hid::XkbKeymap, windowing::OverlayID};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum KeyboardFocus {
     PhysicalScreen,
     WayVR, // (wayland window id data is handled internally),
+    Panel, // text fields inside of our own GUI panels
+}
+
+/// A key or modifier change held back by `HidWrapper::hold`.
+#[derive(Debug, Copy, Clone)]
+pub enum HeldInput {
+    Key(VirtualKey, bool),
+    Modifiers(KeyModifier),
 }
 
 pub struct HidWrapper {
     pub keyboard_focus: KeyboardFocus,
     pub inner: Box<dyn HidProvider>,
     pub keymap: Option<XkbKeymap>,
+    /// Which key and modifier type each character in `keymap`
+    text_keys: HashMap<char, (VirtualKey, KeyModifier)>,
+    /// The overlay that receives text while focus is `KeyboardFocus::Panel`
+    pub panel_focus: Option<OverlayID>,
+    panel_modifiers: KeyModifier,
+    panel_text: String,
+    /// Keys and modifier changes since `hold`, in order
+    held: Option<Vec<HeldInput>>,
 }
 
 impl HidWrapper {
@@ -20,15 +38,58 @@ impl HidWrapper {
             keyboard_focus: KeyboardFocus::PhysicalScreen,
             inner: hid::initialize(),
             keymap: None,
+            text_keys: HashMap::new(),
+            panel_focus: None,
+            panel_modifiers: 0,
+            panel_text: String::new(),
+            held: None,
+        }
+    }
+
+    /// Holds back keys and modifier changes until `release`, so they can be sent after
+    /// something that isn't done yet, such as a paste.
+    pub fn hold(&mut self) {
+        self.held.get_or_insert_default();
+    }
+
+    /// Takes what was held back so far, but keeps holding.
+    pub fn take_held(&mut self) -> Vec<HeldInput> {
+        self.held.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Stops holding back, returning what was held back.
+    pub fn release(&mut self) -> Vec<HeldInput> {
+        self.held.take().unwrap_or_default()
+    }
+
+    /// Sends `inputs` right away, even while holding.
+    pub fn replay(
+        &mut self,
+        mut wvr_server: Option<&mut WvrServerState>,
+        inputs: impl IntoIterator<Item = HeldInput>,
+    ) {
+        for input in inputs {
+            match input {
+                HeldInput::Key(key, down) => self.send_key(wvr_server.as_deref_mut(), key, down),
+                HeldInput::Modifiers(mods) => self.set_modifiers(wvr_server.as_deref_mut(), mods),
+            }
         }
     }
 
     pub fn send_key_routed(
-        &self,
+        &mut self,
         wvr_server: Option<&mut WvrServerState>,
         key: VirtualKey,
         down: bool,
     ) {
+        if let Some(held) = self.held.as_mut() {
+            held.push(HeldInput::Key(key, down));
+            return;
+        }
+        self.send_key(wvr_server, key, down);
+    }
+
+    fn send_key(&mut self, wvr_server: Option<&mut WvrServerState>, key: VirtualKey, down: bool) {
         match self.keyboard_focus {
             KeyboardFocus::PhysicalScreen => self.inner.send_key(key, down),
             KeyboardFocus::WayVR => {
@@ -36,6 +97,11 @@ impl HidWrapper {
                     wvr_server.send_key(key as u32, down);
                 }
             }
+            KeyboardFocus::Panel => {
+                if down {
+                    self.push_panel_key(key);
+                }
+            }
         }
     }
 
@@ -44,9 +110,10 @@ impl HidWrapper {
             let _ = wvr_server
                 .set_keymap(&keymap.inner)
                 .inspect_err(|e| log::error!("Could not set WayVR keymap: {e:?}"));
-        } else {
-            self.keymap = Some(keymap.clone());
         }
+        // also used to translate keys for panel text input
+        self.keymap = Some(keymap.clone());
+        self.text_keys = keymap.text_keys();
 
         log::info!(
             "Keymap changed: {}",
@@ -55,6 +122,14 @@ impl HidWrapper {
     }
 
     pub fn set_modifiers_routed(&mut self, wvr_server: Option<&mut WvrServerState>, mods: u8) {
+        if let Some(held) = self.held.as_mut() {
+            held.push(HeldInput::Modifiers(mods));
+            return;
+        }
+        self.set_modifiers(wvr_server, mods);
+    }
+
+    fn set_modifiers(&mut self, wvr_server: Option<&mut WvrServerState>, mods: u8) {
         match self.keyboard_focus {
             KeyboardFocus::PhysicalScreen => self.inner.set_modifiers(mods),
             KeyboardFocus::WayVR => {
@@ -62,6 +137,90 @@ impl HidWrapper {
                     wvr_server.set_modifiers(mods);
                 }
             }
+            KeyboardFocus::Panel => self.panel_modifiers = mods,
         }
     }
+
+    /// Types text by pressing the keys that produce it in the current keymap.
+    /// Characters the keymap can't produce are skipped. Modifiers are left released.
+    pub fn type_text_routed(&mut self, mut wvr_server: Option<&mut WvrServerState>, text: &str) {
+        if self.keyboard_focus == KeyboardFocus::Panel {
+            self.panel_text
+                .extend(text.chars().filter(|c| !c.is_control()));
+            return;
+        }
+
+        for c in text.chars() {
+            let Some(&(key, mods)) = self.text_keys.get(&c) else {
+                log::debug!("No key types {c:?} in the current keymap, skipping.");
+                continue;
+            };
+            self.set_modifiers_routed(wvr_server.as_deref_mut(), mods);
+            self.send_key_routed(wvr_server.as_deref_mut(), key, true);
+            self.send_key_routed(wvr_server.as_deref_mut(), key, false);
+        }
+        self.set_modifiers_routed(wvr_server, 0);
+    }
+
+    /// Whether `type_text_routed` can type all of `text`.
+    pub fn can_type(&self, text: &str) -> bool {
+        self.keyboard_focus == KeyboardFocus::Panel
+            || text.chars().all(|c| self.text_keys.contains_key(&c))
+    }
+
+    /// Presses Backspace `count` times. Modifiers are left released.
+    pub fn erase_routed(&mut self, mut wvr_server: Option<&mut WvrServerState>, count: usize) {
+        self.set_modifiers_routed(wvr_server.as_deref_mut(), 0);
+        for _ in 0..count {
+            self.send_key_routed(wvr_server.as_deref_mut(), VirtualKey::BackSpace, true);
+            self.send_key_routed(wvr_server.as_deref_mut(), VirtualKey::BackSpace, false);
+        }
+    }
+
+    /// Takes the text typed into the given panel overlay since the last call.
+    /// Backspace is represented as `'\x08'`, Enter as `'\r'`.
+    pub fn take_panel_text(&mut self, overlay: OverlayID) -> Option<String> {
+        if self.keyboard_focus != KeyboardFocus::Panel
+            || self.panel_focus != Some(overlay)
+            || self.panel_text.is_empty()
+        {
+            return None;
+        }
+        Some(std::mem::take(&mut self.panel_text))
+    }
+
+    fn push_panel_key(&mut self, key: VirtualKey) {
+        if key == VirtualKey::BackSpace {
+            self.panel_text.push('\x08');
+            return;
+        }
+        if matches!(key, VirtualKey::Return | VirtualKey::KP_Enter) {
+            self.panel_text.push('\r');
+            return;
+        }
+
+        if self.keymap.is_none() {
+            log::debug!("No keymap, can't type into panel.");
+            return;
+        }
+
+        if let Some(text) = self.text_for_key(key, self.panel_modifiers) {
+            self.panel_text.push_str(&text);
+        }
+    }
+
+    /// The text a key types in the current keymap, if it types any.
+    pub fn text_for_key(&self, key: VirtualKey, modifiers: KeyModifier) -> Option<String> {
+        let keymap = self.keymap.as_ref()?;
+
+        // label_for_key only supports a single modifier
+        let modifier = if modifiers & META != 0 {
+            META
+        } else {
+            modifiers & SHIFT
+        };
+
+        let text = keymap.label_for_key(key, modifier);
+        (!text.is_empty() && !text.chars().any(char::is_control)).then_some(text)
+    }
 }
@@ -1,7 +1,15 @@
+pub mod alarms;
+pub mod battery;
+pub mod calendar;
+pub mod clipboard;
 pub mod dbus;
 pub mod hid;
+pub mod hot_reload;
 pub mod input;
 pub mod notifications;
+pub mod screenshot;
+pub mod sysmon;
+pub mod websocket;
 
 #[cfg(feature = "osc")]
 pub mod osc;
@@ -1,62 +1,199 @@
 use anyhow::Context;
-use dbus::message::MatchRule;
+use base64::Engine;
+use chrono::{DateTime, Local, NaiveTime};
+use dbus::{
+    Message,
+    arg::{PropMap, RefArg},
+    blocking::Connection,
+    channel::Sender,
+    message::{MatchRule, SignalArgs},
+    strings::ErrorName,
+};
+use regex::Regex;
 use serde::Deserialize;
 use std::{
+    collections::VecDeque,
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
     sync::{
         Arc,
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicUsize, Ordering},
         mpsc,
     },
-    time::Duration,
+    time::{Duration, Instant},
+};
+use wlx_common::{
+    config::{GeneralConfig, NotificationRule},
+    config_io,
+    overlays::{NotificationUrgency, ToastDisplayMethod, ToastTopic},
+};
+
+use crate::{
+    overlays::{
+        notifications::notify_history_changed,
+        toast::{Toast, ToastIcon, dismiss_toast},
+    },
+    state::AppState,
+    subsystem::{
+        dbus::{DbusConnector, OrgFreedesktopNotificationsNotificationClosed},
+        websocket::WebSocket,
+    },
 };
-use wlx_common::overlays::ToastTopic;
 
-use crate::{overlays::toast::Toast, state::AppState, subsystem::dbus::DbusConnector};
+const MAX_HISTORY: usize = 50;
+
+/// OVR Toolkit clients connected at once, any further ones are turned away.
+const MAX_OVRT_CLIENTS: usize = 8;
+
+/// Action key used by clients that accept inline replies.
+pub const INLINE_REPLY_KEY: &str = "inline-reply";
+
+/// Reasons for `NotificationClosed`, as defined by the notification spec.
+const CLOSED_EXPIRED: u32 = 1;
+const CLOSED_BY_USER: u32 = 2;
+const CLOSED_BY_CALL: u32 = 3;
+
+const SERVER_CAPABILITIES: [&str; 5] = [
+    "actions",
+    "body",
+    "icon-static",
+    "inline-reply",
+    "persistence",
+];
+
+#[derive(Clone, Debug)]
+pub struct NotificationAction {
+    pub key: Arc<str>,
+    pub label: Arc<str>,
+}
+
+/// Where to send `ActionInvoked` and `NotificationReplied` signals to.
+#[derive(Clone, Debug)]
+pub struct DbusNotificationOrigin {
+    pub sender: Arc<str>,
+    pub id: u32,
+}
 
 pub struct NotificationManager {
     rx_toast: mpsc::Receiver<Toast>,
     tx_toast: mpsc::SyncSender<Toast>,
+    /// IDs given to `CloseNotification` while we're the notification server
+    rx_closed: mpsc::Receiver<u32>,
+    tx_closed: mpsc::SyncSender<u32>,
     running: Arc<AtomicBool>,
+    filter: NotificationFilter,
 }
 
 impl NotificationManager {
-    pub fn new() -> Self {
+    pub fn new(config: &GeneralConfig) -> Self {
         let (tx_toast, rx_toast) = mpsc::sync_channel(10);
+        let (tx_closed, rx_closed) = mpsc::sync_channel(10);
         Self {
             rx_toast,
             tx_toast,
+            rx_closed,
+            tx_closed,
             running: Arc::new(AtomicBool::new(true)),
+            filter: NotificationFilter::new(config),
         }
     }
 
-    pub fn submit_pending(&self, app: &mut AppState) {
+    pub fn submit_pending(&mut self, app: &mut AppState) {
+        if app.dbus.take_server_lost() {
+            log::info!("Another notification server took over, listening to it instead.");
+            self.run_dbus(&mut app.dbus, false);
+        }
+
         if app.session.config.notifications_enabled {
-            self.rx_toast.try_iter().for_each(|toast| {
-                toast.submit(app);
+            let dnd = self.filter.is_dnd(&app.session.config);
+            let mut history_changed = false;
+            self.rx_toast.try_iter().for_each(|mut toast| {
+                self.filter.apply(&mut toast);
+                let history_id = match app.notification_history.replace(&toast) {
+                    Some(id) => {
+                        // an update to what's on screen shouldn't alert again
+                        if app.windowing.toast_history_id == Some(id) {
+                            toast.sound = false;
+                            toast.haptics = false;
+                        }
+                        id
+                    }
+                    None => app.notification_history.push(&toast),
+                };
+                toast.history_id = Some(history_id);
+                history_changed = true;
+                if !dnd || toast.always_show {
+                    toast.submit(app);
+                }
             });
+            if history_changed {
+                notify_history_changed(app);
+            }
         } else {
             // consume without submitting
             self.rx_toast.try_iter().last();
         }
+
+        let mut history_changed = false;
+        for id in self.rx_closed.try_iter() {
+            let Some(entry) = app.notification_history.remove_closed(id) else {
+                continue;
+            };
+            dismiss_toast(app, entry.id);
+            history_changed = true;
+        }
+        for id in app.notification_history.take_expired(Instant::now()) {
+            let _ = app
+                .dbus
+                .notify_closed(id, CLOSED_EXPIRED)
+                .inspect_err(|e| log::warn!("Could not send NotificationClosed: {e:?}"));
+            history_changed = true;
+        }
+        if history_changed {
+            notify_history_changed(app);
+        }
     }
 
-    pub fn run_dbus(&mut self, dbus: &mut DbusConnector) {
-        let rule = MatchRule::new_method_call()
+    pub fn run_dbus(&mut self, dbus: &mut DbusConnector, act_as_server: bool) {
+        if act_as_server {
+            let mut server = NotifyServer {
+                sender: self.tx_toast.clone(),
+                closed: self.tx_closed.clone(),
+                next_id: 0,
+            };
+            match dbus.serve_notifications(Box::new(move |msg, conn| {
+                server.handle(&msg, conn);
+                true
+            })) {
+                Ok(true) => {
+                    log::info!("No notification server running, serving D-Bus notifications.");
+                    return;
+                }
+                Ok(false) => {
+                    log::info!("Another notification server is running, listening to it instead.");
+                }
+                Err(e) => log::warn!("Could not register notification server: {e:?}"),
+            }
+        }
+
+        let rule_notify = MatchRule::new_method_call()
             .with_member("Notify")
             .with_interface("org.freedesktop.Notifications")
             .with_path("/org/freedesktop/Notifications");
 
-        let sender = self.tx_toast.clone();
+        let sniffer = NotifySniffer {
+            sender: self.tx_toast.clone(),
+        };
+
         if dbus
             .become_monitor(
-                rule.clone(),
-                Box::new(move |msg, _| {
-                    if let Ok(toast) = parse_dbus(&msg) {
-                        let _ = sender
-                            .try_send(toast)
-                            .inspect_err(|e| log::error!("Failed to send notification: {e:?}"));
+                std::slice::from_ref(&rule_notify),
+                Box::new({
+                    let sniffer = sniffer.clone();
+                    move |msg, _| {
+                        sniffer.handle(&msg);
+                        true
                     }
-                    true
                 }),
             )
             .context("Could not register BecomeMonitor")
@@ -67,16 +204,11 @@ impl NotificationManager {
             return;
         }
 
-        let sender = self.tx_toast.clone();
         let _ = dbus
             .add_match(
-                rule.with_eavesdrop(),
+                rule_notify.with_eavesdrop(),
                 Box::new(move |(), _, msg| {
-                    if let Ok(toast) = parse_dbus(msg) {
-                        let _ = sender
-                            .try_send(toast)
-                            .inspect_err(|e| log::error!("Failed to send notification: {e:?}"));
-                    }
+                    sniffer.handle(msg);
                     true
                 }),
             )
@@ -100,7 +232,7 @@ impl NotificationManager {
                 log::error!("Failed to set read timeout: {err:?}");
             }
 
-            let mut buf = [0u8; 1024 * 16]; // vrcx embeds icons as b64
+            let mut buf = vec![0u8; 65536]; // vrcx embeds icons as b64
 
             while running.load(Ordering::Relaxed) {
                 if let Ok((num_bytes, _)) = socket.recv_from(&mut buf) {
@@ -119,17 +251,9 @@ impl NotificationManager {
                         }
                     };
 
-                    if msg.messageType != 1 {
+                    let Some(toast) = xso_toast(msg) else {
                         continue;
-                    }
-
-                    let toast = Toast::new(
-                        ToastTopic::XSNotification,
-                        msg.title,
-                        msg.content.unwrap_or(String::new()),
-                    )
-                    .with_timeout(msg.timeout.unwrap_or(5.))
-                    .with_sound(msg.volume.unwrap_or(-1.) >= 0.); // XSOverlay still plays at 0,
+                    };
 
                     match sender.try_send(toast) {
                         Ok(()) => {}
@@ -142,6 +266,57 @@ impl NotificationManager {
             log::info!("Notification listener stopped.");
         });
     }
+
+    /// Accepts the notification messages of OVR Toolkit's WebSocket API.
+    pub fn run_ovrt(&mut self) {
+        let sender = self.tx_toast.clone();
+        let running = self.running.clone();
+        let _ = std::thread::spawn(move || {
+            let num_clients = Arc::new(AtomicUsize::new(0));
+            let addr = "127.0.0.1:11450";
+            let listener = match TcpListener::bind(addr) {
+                Ok(l) => l,
+                Err(e) => {
+                    log::error!("Failed to bind notification socket @ {addr}: {e:?}");
+                    return;
+                }
+            };
+            if let Err(err) = listener.set_nonblocking(true) {
+                log::error!("Failed to set non-blocking: {err:?}");
+                return;
+            }
+
+            while running.load(Ordering::Relaxed) {
+                let stream = match listener.accept() {
+                    Ok((stream, _)) => stream,
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                        std::thread::sleep(Duration::from_millis(200));
+                        continue;
+                    }
+                    Err(e) => {
+                        log::error!("Failed to accept notification client: {e:?}");
+                        continue;
+                    }
+                };
+
+                if num_clients.fetch_add(1, Ordering::Relaxed) >= MAX_OVRT_CLIENTS {
+                    num_clients.fetch_sub(1, Ordering::Relaxed);
+                    log::warn!("Too many OVR Toolkit clients, refusing connection.");
+                    continue;
+                }
+
+                let sender = sender.clone();
+                let num_clients = num_clients.clone();
+                let _ = std::thread::spawn(move || {
+                    if let Err(e) = handle_ovrt_client(stream, &sender) {
+                        log::debug!("OVR Toolkit client disconnected: {e:?}");
+                    }
+                    num_clients.fetch_sub(1, Ordering::Relaxed);
+                });
+            }
+            log::info!("OVR Toolkit notification listener stopped.");
+        });
+    }
 }
 
 impl Drop for NotificationManager {
@@ -150,31 +325,505 @@ impl Drop for NotificationManager {
     }
 }
 
+/// Only sees the notifications, the server they're meant for handles actions and replies.
+#[derive(Clone)]
+struct NotifySniffer {
+    sender: mpsc::SyncSender<Toast>,
+}
+
+impl NotifySniffer {
+    fn handle(&self, msg: &Message) {
+        if let Ok(toast) = parse_dbus(msg) {
+            self.send(toast);
+        }
+    }
+
+    fn send(&self, toast: Toast) {
+        let _ = self
+            .sender
+            .try_send(toast)
+            .inspect_err(|e| log::error!("Failed to send notification: {e:?}"));
+    }
+}
+
+/// Stands in for the notification daemon on sessions that don't have one.
+struct NotifyServer {
+    sender: mpsc::SyncSender<Toast>,
+    closed: mpsc::SyncSender<u32>,
+    next_id: u32,
+}
+
+impl NotifyServer {
+    fn handle(&mut self, msg: &Message, conn: &Connection) {
+        if msg.interface().as_deref() != Some("org.freedesktop.Notifications") {
+            let _ = conn.send(error_reply(
+                msg,
+                "org.freedesktop.DBus.Error.UnknownInterface",
+                c"Unknown interface",
+            ));
+            return;
+        }
+
+        let reply = match msg.member().as_deref() {
+            Some("Notify") => self.notify(msg),
+            Some("CloseNotification") => match msg.read1::<u32>() {
+                Ok(id) => {
+                    let path = msg.path().unwrap_or_default();
+                    let signal = OrgFreedesktopNotificationsNotificationClosed {
+                        id,
+                        reason: CLOSED_BY_CALL,
+                    };
+                    let _ = conn.send(signal.to_emit_message(&path));
+                    let _ = self
+                        .closed
+                        .try_send(id)
+                        .inspect_err(|e| log::error!("Failed to close notification: {e:?}"));
+                    msg.method_return()
+                }
+                Err(_) => error_reply(
+                    msg,
+                    "org.freedesktop.DBus.Error.InvalidArgs",
+                    c"Expected notification ID",
+                ),
+            },
+            Some("GetCapabilities") => msg.method_return().append1(SERVER_CAPABILITIES.to_vec()),
+            Some("GetServerInformation") => msg
+                .method_return()
+                .append2("WayVR", "WayVR")
+                .append2(env!("CARGO_PKG_VERSION"), "1.2"),
+            _ => error_reply(
+                msg,
+                "org.freedesktop.DBus.Error.UnknownMethod",
+                c"Unknown method",
+            ),
+        };
+
+        if conn.send(reply).is_err() {
+            log::warn!("Could not reply to D-Bus notification call.");
+        }
+    }
+
+    fn notify(&mut self, msg: &Message) -> Message {
+        let (Ok(mut toast), Ok((_, replaces_id))) = (parse_dbus(msg), msg.read2::<&str, u32>())
+        else {
+            return error_reply(
+                msg,
+                "org.freedesktop.DBus.Error.InvalidArgs",
+                c"Malformed notification",
+            );
+        };
+
+        let id = if replaces_id == 0 {
+            self.next_id = self.next_id.wrapping_add(1).max(1);
+            self.next_id
+        } else {
+            replaces_id
+        };
+
+        if let Some(sender) = msg.sender() {
+            toast.origin = Some(DbusNotificationOrigin {
+                sender: sender.to_string().into(),
+                id,
+            });
+        }
+
+        let _ = self
+            .sender
+            .try_send(toast)
+            .inspect_err(|e| log::error!("Failed to send notification: {e:?}"));
+
+        msg.method_return().append1(id)
+    }
+}
+
+fn error_reply(msg: &Message, name: &'static str, text: &std::ffi::CStr) -> Message {
+    // names above are all valid
+    msg.error(&ErrorName::from(name), text)
+}
+
 fn parse_dbus(msg: &dbus::Message) -> anyhow::Result<Toast> {
     let mut args = msg.iter_init();
     let app_name: String = args.read()?;
     let _replaces_id: u32 = args.read()?;
-    let _app_icon: String = args.read()?;
+    let app_icon: String = args.read()?;
     let summary: String = args.read()?;
     let body: String = args.read()?;
+    let actions: Vec<String> = args.read()?;
+    let hints: PropMap = args.read()?;
+    let expire_timeout: i32 = args.read().unwrap_or(-1);
 
     let title = if summary.is_empty() {
-        app_name
+        app_name.clone()
     } else {
         summary
     };
 
+    // actions come as a flat list of key, label pairs
+    let actions = actions
+        .chunks_exact(2)
+        .map(|pair| NotificationAction {
+            key: pair[0].as_str().into(),
+            label: pair[1].as_str().into(),
+        })
+        .collect();
+
+    let urgency = hints
+        .get("urgency")
+        .and_then(|v| v.0.as_u64())
+        .map(|u| match u {
+            0 => NotificationUrgency::Low,
+            2 => NotificationUrgency::Critical,
+            _ => NotificationUrgency::Normal,
+        });
+
+    // in milliseconds, -1 leaves it up to us and 0 never expires
+    let timeout = match expire_timeout {
+        0 => f32::INFINITY,
+        ms if ms > 0 => ms as f32 / 1000.,
+        _ => 5.0,
+    };
+
     Ok(Toast::new(ToastTopic::DesktopNotification, title, body)
-        .with_timeout(5.0)
-        .with_opacity(1.0))
+        .with_app_name((!app_name.is_empty()).then_some(app_name))
+        .with_urgency(urgency)
+        .with_timeout(timeout)
+        .with_opacity(1.0)
+        .with_icon(parse_dbus_icon(app_icon, &hints))
+        .with_actions(actions))
     // leave the audio part to the desktop env
 }
 
+/// Picks the icon in the order of precedence given by the notification spec.
+fn parse_dbus_icon(app_icon: String, hints: &PropMap) -> Option<ToastIcon> {
+    // older spec versions use the other names
+    for key in ["image-data", "image_data", "icon_data"] {
+        if let Some(variant) = hints.get(key)
+            && let Some(icon) = parse_image_data(&*variant.0)
+        {
+            return Some(icon);
+        }
+    }
+
+    for key in ["image-path", "image_path"] {
+        if let Some(path) = hints.get(key).and_then(|v| v.0.as_str())
+            && !path.is_empty()
+        {
+            return Some(ToastIcon::Named(path.to_string()));
+        }
+    }
+
+    (!app_icon.is_empty()).then_some(ToastIcon::Named(app_icon))
+}
+
+/// Decodes the `(iiibiiay)` image struct: width, height, rowstride, has_alpha,
+/// bits_per_sample, channels, data.
+fn parse_image_data(arg: &(dyn RefArg + 'static)) -> Option<ToastIcon> {
+    let int = |idx| arg.as_static_inner(idx).and_then(RefArg::as_i64);
+
+    let width = usize::try_from(int(0)?).ok()?;
+    let height = usize::try_from(int(1)?).ok()?;
+    let rowstride = usize::try_from(int(2)?).ok()?;
+    let has_alpha = int(3)? != 0;
+    let bits_per_sample = int(4)?;
+    let channels = usize::try_from(int(5)?).ok()?;
+    let data = arg
+        .as_static_inner(6)
+        .and_then(|a| dbus::arg::cast::<Vec<u8>>(a))?;
+
+    let expected_channels = if has_alpha { 4 } else { 3 };
+    if bits_per_sample != 8 || channels != expected_channels || rowstride < width * channels {
+        log::debug!("Unsupported notification image format: {bits_per_sample}bpp x{channels}");
+        return None;
+    }
+
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for row in data.chunks(rowstride).take(height) {
+        for px in row.get(..width * channels)?.chunks_exact(channels) {
+            let alpha = if has_alpha { px[3] } else { u8::MAX };
+            rgba.extend_from_slice(&[px[0], px[1], px[2], alpha]);
+        }
+    }
+
+    if rgba.len() != width * height * 4 {
+        log::debug!("Notification image is truncated.");
+        return None;
+    }
+
+    Some(ToastIcon::Rgba {
+        width: width as _,
+        height: height as _,
+        data: rgba,
+    })
+}
+
+struct CompiledRule {
+    app_name: Option<Regex>,
+    summary: Option<Regex>,
+    body: Option<Regex>,
+    urgency: Option<NotificationUrgency>,
+    display: Option<ToastDisplayMethod>,
+    sound: Option<bool>,
+    haptics: Option<bool>,
+}
+
+impl CompiledRule {
+    fn new(rule: &NotificationRule) -> anyhow::Result<Self> {
+        let compile = |re: &Option<String>| re.as_deref().map(Regex::new).transpose();
+        Ok(Self {
+            app_name: compile(&rule.app_name).context("app_name")?,
+            summary: compile(&rule.summary).context("summary")?,
+            body: compile(&rule.body).context("body")?,
+            urgency: rule.urgency,
+            display: rule.display,
+            sound: rule.sound,
+            haptics: rule.haptics,
+        })
+    }
+
+    fn matches(&self, toast: &Toast) -> bool {
+        let test = |re: &Option<Regex>, text: Option<&str>| {
+            re.as_ref()
+                .is_none_or(|re| text.is_some_and(|t| re.is_match(t)))
+        };
+
+        test(&self.app_name, toast.app_name.as_deref())
+            && test(&self.summary, Some(&toast.title))
+            && test(&self.body, Some(&toast.body))
+            && self.urgency.is_none_or(|u| toast.urgency == Some(u))
+    }
+}
+
+/// User-defined notification rules and do-not-disturb settings.
+struct NotificationFilter {
+    rules: Vec<CompiledRule>,
+    dnd_schedule: Option<(NaiveTime, NaiveTime)>,
+}
+
+impl NotificationFilter {
+    fn new(config: &GeneralConfig) -> Self {
+        let rules = config
+            .notification_rules
+            .iter()
+            .enumerate()
+            .filter_map(|(i, rule)| {
+                CompiledRule::new(rule)
+                    .inspect_err(|e| log::warn!("Skipping invalid notification rule #{i}: {e:?}"))
+                    .ok()
+            })
+            .collect();
+
+        let dnd_schedule = config.notifications_dnd_schedule.as_ref().and_then(|s| {
+            let parse = |t: &str| NaiveTime::parse_from_str(t, "%H:%M");
+            parse(&s.start)
+                .and_then(|start| parse(&s.end).map(|end| (start, end)))
+                .inspect_err(|e| log::warn!("Invalid DND schedule, expected HH:MM: {e:?}"))
+                .ok()
+        });
+
+        Self {
+            rules,
+            dnd_schedule,
+        }
+    }
+
+    fn apply(&self, toast: &mut Toast) {
+        let Some(rule) = self.rules.iter().find(|r| r.matches(toast)) else {
+            return;
+        };
+        if let Some(display) = rule.display {
+            toast.display = Some(display);
+        }
+        if let Some(sound) = rule.sound {
+            toast.sound = sound;
+        }
+        if let Some(haptics) = rule.haptics {
+            toast.haptics = haptics;
+        }
+    }
+
+    fn is_dnd(&self, config: &GeneralConfig) -> bool {
+        if config.notifications_dnd {
+            return true;
+        }
+        let Some((start, end)) = self.dnd_schedule else {
+            return false;
+        };
+        let now = Local::now().time();
+        if start <= end {
+            start <= now && now < end
+        } else {
+            // spans midnight
+            now >= start || now < end
+        }
+    }
+}
+
+pub struct NotificationEntry {
+    pub id: usize,
+    pub title: String,
+    pub body: String,
+    pub received: DateTime<Local>,
+    pub actions: Vec<NotificationAction>,
+    pub origin: Option<DbusNotificationOrigin>,
+    /// When to tell the sender that its notification expired
+    expires_at: Option<Instant>,
+}
+
+impl NotificationEntry {
+    /// Actions only reach the sender while we own the notification server name.
+    pub fn can_invoke(&self, dbus: &DbusConnector) -> bool {
+        self.origin.is_some() && dbus.owns_notifications()
+    }
+
+    pub fn can_reply(&self, dbus: &DbusConnector) -> bool {
+        self.can_invoke(dbus) && self.actions.iter().any(|a| &*a.key == INLINE_REPLY_KEY)
+    }
+}
+
+/// Recently received notifications, newest first.
+#[derive(Default)]
+pub struct NotificationHistory {
+    entries: VecDeque<NotificationEntry>,
+    next_id: usize,
+}
+
+impl NotificationHistory {
+    pub fn push(&mut self, toast: &Toast) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.entries.push_front(NotificationEntry {
+            id,
+            title: toast.title.clone(),
+            body: toast.body.clone(),
+            received: Local::now(),
+            actions: toast.actions.clone(),
+            origin: toast.origin.clone(),
+            expires_at: expiry(toast),
+        });
+        self.entries.truncate(MAX_HISTORY);
+        id
+    }
+
+    /// Updates the notification that `toast` replaces, if it's from the same sender and still
+    /// in the history. Returns the ID of the updated entry.
+    fn replace(&mut self, toast: &Toast) -> Option<usize> {
+        let origin = toast.origin.as_ref()?;
+        let entry = self.entries.iter_mut().find(|e| {
+            e.origin
+                .as_ref()
+                .is_some_and(|o| o.id == origin.id && o.sender == origin.sender)
+        })?;
+
+        entry.title.clone_from(&toast.title);
+        entry.body.clone_from(&toast.body);
+        entry.received = Local::now();
+        entry.actions.clone_from(&toast.actions);
+        entry.expires_at = expiry(toast);
+        Some(entry.id)
+    }
+
+    /// Forgets the senders of notifications that timed out, returning their IDs.
+    /// Their entries stay, but without actions.
+    fn take_expired(&mut self, now: Instant) -> Vec<u32> {
+        self.entries
+            .iter_mut()
+            .filter(|e| e.expires_at.is_some_and(|t| t <= now))
+            .filter_map(|e| {
+                e.expires_at = None;
+                e.origin.take().map(|o| o.id)
+            })
+            .collect()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &NotificationEntry> {
+        self.entries.iter()
+    }
+
+    pub fn get(&self, id: usize) -> Option<&NotificationEntry> {
+        self.entries.iter().find(|e| e.id == id)
+    }
+
+    pub fn remove(&mut self, id: usize) -> Option<NotificationEntry> {
+        let idx = self.entries.iter().position(|e| e.id == id)?;
+        self.entries.remove(idx)
+    }
+
+    /// Removes a notification that its sender closed, by the ID we gave it as the server.
+    fn remove_closed(&mut self, dbus_id: u32) -> Option<NotificationEntry> {
+        let idx = self
+            .entries
+            .iter()
+            .position(|e| e.origin.as_ref().is_some_and(|o| o.id == dbus_id))?;
+        self.entries.remove(idx)
+    }
+
+    /// Removes a notification, letting its sender know if we're the notification server.
+    pub fn dismiss(&mut self, dbus: &DbusConnector, id: usize) {
+        if let Some(entry) = self.remove(id) {
+            Self::close(dbus, &entry);
+        }
+    }
+
+    pub fn clear(&mut self, dbus: &DbusConnector) {
+        for entry in self.entries.drain(..) {
+            Self::close(dbus, &entry);
+        }
+    }
+
+    /// Tell the sending application that the user picked an action.
+    /// The notification is considered handled afterwards.
+    pub fn invoke_action(
+        &mut self,
+        dbus: &DbusConnector,
+        id: usize,
+        action_key: &str,
+    ) -> anyhow::Result<()> {
+        let entry = self.get(id).context("No such notification")?;
+        if !entry.can_invoke(dbus) {
+            anyhow::bail!("Notification does not support actions");
+        }
+        let origin = entry.origin.as_ref().unwrap(); // checked by can_invoke
+
+        dbus.notify_action_invoked(&origin.sender, origin.id, action_key)?;
+        self.dismiss(dbus, id);
+        Ok(())
+    }
+
+    pub fn reply(&mut self, dbus: &DbusConnector, id: usize, text: &str) -> anyhow::Result<()> {
+        let entry = self.get(id).context("No such notification")?;
+        if !entry.can_reply(dbus) {
+            anyhow::bail!("Notification does not support inline replies");
+        }
+        let origin = entry.origin.as_ref().unwrap(); // checked by can_reply
+
+        dbus.notify_reply(&origin.sender, origin.id, text)?;
+        self.dismiss(dbus, id);
+        Ok(())
+    }
+
+    fn close(dbus: &DbusConnector, entry: &NotificationEntry) {
+        if let Some(origin) = entry.origin.as_ref() {
+            let _ = dbus
+                .notify_closed(origin.id, CLOSED_BY_USER)
+                .inspect_err(|e| log::warn!("Could not send NotificationClosed: {e:?}"));
+        }
+    }
+}
+
+/// Only notifications we serve expire, and only if their sender gave them a timeout.
+fn expiry(toast: &Toast) -> Option<Instant> {
+    toast.origin.as_ref()?;
+    Instant::now().checked_add(Duration::try_from_secs_f32(toast.timeout).ok()?)
+}
+
 #[allow(dead_code)]
 #[allow(non_snake_case)]
 #[derive(Debug, Deserialize)]
 struct XsoMessage {
     messageType: i32,
+    /// Picks the media player icon on XSOverlay's wrist.
+    /// Unsupported, like the media player messages it's meant for.
     index: Option<i32>,
     volume: Option<f32>,
     audioPath: Option<String>,
@@ -188,3 +837,144 @@ struct XsoMessage {
     sourceApp: Option<String>,
     alwaysShow: Option<bool>,
 }
+
+fn xso_toast(msg: XsoMessage) -> Option<Toast> {
+    // 2 is media player info, which only changes the icon on XSOverlay's wrist
+    if msg.messageType != 1 {
+        return None;
+    }
+
+    let icon = parse_xso_icon(&msg);
+    let volume = msg.volume.unwrap_or(-1.);
+
+    Some(
+        Toast::new(
+            ToastTopic::XSNotification,
+            msg.title,
+            msg.content.unwrap_or_default(),
+        )
+        .with_app_name(msg.sourceApp)
+        .with_icon(icon)
+        .with_timeout(msg.timeout.unwrap_or(5.))
+        .with_opacity(msg.opacity.unwrap_or(1.))
+        .with_height(msg.height.filter(|h| *h > 0.))
+        .with_always_show(msg.alwaysShow.unwrap_or(false))
+        .with_sound(volume >= 0.)
+        .with_volume(volume.clamp(0., 1.))
+        .with_sound_file(parse_xso_audio_path(msg.audioPath.as_deref())),
+    )
+}
+
+fn parse_xso_audio_path(audio_path: Option<&str>) -> Option<PathBuf> {
+    let audio_path = audio_path.filter(|p| !p.is_empty())?;
+
+    // XSOverlay's built-in sounds; we just play our own toast sound for these
+    if matches!(audio_path, "default" | "error" | "warning") {
+        return None;
+    }
+    Some(config_io::get_config_root().join(audio_path))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OvrtMessage {
+    message_type: String,
+    /// The actual payload, as a JSON string
+    json: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OvrtNotification {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    body: String,
+    #[serde(default)]
+    icon: Option<OvrtIcon>,
+}
+
+/// Some clients send the image as base64, others as a plain byte array
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OvrtIcon {
+    Base64(String),
+    Bytes(Vec<u8>),
+}
+
+fn handle_ovrt_client(stream: TcpStream, sender: &mpsc::SyncSender<Toast>) -> anyhow::Result<()> {
+    stream.set_nonblocking(false)?;
+    let mut socket = WebSocket::accept(stream)?;
+
+    while let Some(data) = socket.read_message()? {
+        let msg = match serde_json::from_slice::<OvrtMessage>(&data) {
+            Ok(m) => m,
+            Err(e) => {
+                log::error!("Failed to parse notification message: {e:?}");
+                continue;
+            }
+        };
+        let Some(toast) = ovrt_toast(&msg)
+            .inspect_err(|e| log::error!("Failed to parse notification message: {e:?}"))
+            .ok()
+            .flatten()
+        else {
+            continue;
+        };
+        let _ = sender
+            .try_send(toast)
+            .inspect_err(|e| log::error!("Failed to send notification: {e:?}"));
+    }
+    Ok(())
+}
+
+fn ovrt_toast(msg: &OvrtMessage) -> anyhow::Result<Option<Toast>> {
+    let display = match msg.message_type.as_str() {
+        "SendNotification" => None,
+        "SendWristNotification" => Some(ToastDisplayMethod::Watch),
+        other => {
+            log::debug!("Ignoring OVR Toolkit message: {other}");
+            return Ok(None);
+        }
+    };
+
+    let notification = serde_json::from_str::<OvrtNotification>(&msg.json)?;
+    let icon = match notification.icon {
+        Some(OvrtIcon::Base64(b64)) if !b64.is_empty() => base64::engine::general_purpose::STANDARD
+            .decode(b64.trim())
+            .inspect_err(|e| log::warn!("Could not decode notification icon: {e:?}"))
+            .ok()
+            .map(ToastIcon::Encoded),
+        Some(OvrtIcon::Bytes(bytes)) if !bytes.is_empty() => Some(ToastIcon::Encoded(bytes)),
+        _ => None,
+    };
+
+    // same topic as XSOverlay, both are for VR-specific notifications
+    let mut toast = Toast::new(
+        ToastTopic::XSNotification,
+        notification.title,
+        notification.body,
+    )
+    .with_icon(icon)
+    .with_timeout(5.)
+    .with_sound(true);
+    toast.display = display;
+    Ok(Some(toast))
+}
+
+fn parse_xso_icon(msg: &XsoMessage) -> Option<ToastIcon> {
+    let icon = msg.icon.as_deref().filter(|i| !i.is_empty())?;
+
+    if msg.useBase64Icon.unwrap_or(false) {
+        return base64::engine::general_purpose::STANDARD
+            .decode(icon.trim())
+            .inspect_err(|e| log::warn!("Could not decode notification icon: {e:?}"))
+            .ok()
+            .map(ToastIcon::Encoded);
+    }
+
+    // XSOverlay's built-in icons; we just show our own bell for these
+    if matches!(icon, "default" | "error" | "warning") {
+        return None;
+    }
+    Some(ToastIcon::Named(icon.to_string()))
+}
@@ -8,8 +8,8 @@ use rosc::{OscMessage, OscPacket, OscType};
 
 use crate::{
     backend::input::TrackedDevice,
-    overlays::{keyboard::KEYBOARD_NAME, watch::WATCH_NAME},
-    windowing::manager::OverlayWindowManager,
+    overlays::watch::WATCH_NAME,
+    windowing::{manager::OverlayWindowManager, window::OverlayCategory},
 };
 
 use crate::backend::input::TrackedDeviceRole;
@@ -85,7 +85,7 @@ impl OscSender {
 
                 match o.config.name.as_ref() {
                     WATCH_NAME => has_wrist = true,
-                    KEYBOARD_NAME => has_keyboard = true,
+                    _ if o.config.category == OverlayCategory::Keyboard => has_keyboard = true,
                     _ => {
                         if state.interactable {
                             num_overlays += 1;
@@ -0,0 +1,250 @@
+use std::{path::PathBuf, sync::mpsc};
+
+use anyhow::Context;
+use chrono::Local;
+use image::RgbaImage;
+use smallvec::smallvec;
+use vulkano::{
+    command_buffer::CommandBufferUsage,
+    format::Format,
+    image::{ImageUsage, view::ImageView},
+    sync::GpuFuture,
+};
+use wlx_common::{config_io::resolve_path, overlays::ToastTopic};
+
+use crate::{
+    graphics::GpuFutures,
+    overlays::toast::{Toast, ToastIcon},
+    state::AppState,
+    windowing::{
+        OverlaySelector,
+        backend::{RenderResources, RenderTarget},
+        manager::OverlayWindowManager,
+        window::{OverlayCategory, OverlayWindowData},
+    },
+};
+
+/// Space between overlays when capturing the working set
+const STITCH_GAP: u32 = 16;
+
+/// Must match the width of `toast_preview` in toast.xml
+const PREVIEW_WIDTH: u32 = 436;
+
+struct Saved {
+    path: PathBuf,
+    preview: RgbaImage,
+}
+
+/// Screenshots are encoded and written on a separate thread,
+/// the result is picked up by `tick` to show the toast.
+pub struct Screenshots {
+    sender: mpsc::Sender<anyhow::Result<Saved>>,
+    receiver: mpsc::Receiver<anyhow::Result<Saved>>,
+}
+
+impl Default for Screenshots {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self { sender, receiver }
+    }
+}
+
+/// Call once per frame.
+pub fn tick(app: &mut AppState) {
+    while let Ok(result) = app.screenshots.receiver.try_recv() {
+        match result {
+            Ok(saved) => {
+                let file_name = saved
+                    .path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                log::info!("Saved screenshot to {}", saved.path.display());
+
+                let preview = ToastIcon::Rgba {
+                    width: saved.preview.width(),
+                    height: saved.preview.height(),
+                    data: saved.preview.into_raw(),
+                };
+                Toast::new(ToastTopic::Screenshot, "SCREENSHOT.SAVED".into(), file_name)
+                    .with_preview(Some(preview))
+                    .with_timeout(4.)
+                    .with_sound(true)
+                    .submit(app);
+            }
+            Err(e) => error_toast(app, &e),
+        }
+    }
+}
+
+/// Captures the selected overlay, or all visible overlays side by side if `selector` is `None`.
+pub fn take<T>(
+    app: &mut AppState,
+    overlays: &mut OverlayWindowManager<T>,
+    selector: Option<&OverlaySelector>,
+) {
+    let result = if let Some(selector) = selector {
+        overlays
+            .mut_by_selector(selector)
+            .with_context(|| format!("no overlay matches {selector:?}"))
+            .and_then(|o| {
+                let name = o.config.name.clone();
+                capture_overlay(app, o).map(|image| (image, name.to_string()))
+            })
+    } else {
+        capture_working_set(app, overlays).map(|image| (image, "working_set".to_string()))
+    };
+
+    match result {
+        Ok((image, name)) => save(app, image, &name),
+        Err(e) => error_toast(app, &e),
+    }
+}
+
+fn capture_working_set<T>(
+    app: &mut AppState,
+    overlays: &mut OverlayWindowManager<T>,
+) -> anyhow::Result<RgbaImage> {
+    let hmd_inverse = app.input_state.hmd.inverse();
+
+    // left to right, as seen from the headset
+    let mut visible: Vec<_> = overlays
+        .values_mut()
+        .filter_map(|o| {
+            let state = o.config.active_state.as_ref()?;
+            if state.alpha < 0.01 || matches!(o.config.category, OverlayCategory::Internal) {
+                return None;
+            }
+            let x = hmd_inverse.transform_point3a(state.transform.translation).x;
+            Some((x, o))
+        })
+        .collect();
+    visible.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+    let mut images = Vec::with_capacity(visible.len());
+    for (_, o) in visible {
+        match capture_overlay(app, o) {
+            Ok(image) => images.push(image),
+            Err(e) => log::debug!("{}: skipped in screenshot: {e:?}", o.config.name),
+        }
+    }
+    anyhow::ensure!(!images.is_empty(), "no visible overlays to capture");
+
+    Ok(stitch(&images))
+}
+
+/// Renders the current frame of an overlay into a fresh image and reads it back.
+fn capture_overlay<T>(
+    app: &mut AppState,
+    overlay: &mut OverlayWindowData<T>,
+) -> anyhow::Result<RgbaImage> {
+    anyhow::ensure!(
+        overlay.config.active_state.is_some(),
+        "{} is not visible",
+        overlay.config.name
+    );
+    let meta = overlay
+        .frame_meta()
+        .with_context(|| format!("{} has nothing to show yet", overlay.config.name))?;
+    let [width, height] = meta.extent;
+
+    let format = app.gfx.surface_format;
+    let bgra = match format {
+        Format::R8G8B8A8_SRGB | Format::R8G8B8A8_UNORM => false,
+        Format::B8G8R8A8_SRGB | Format::B8G8R8A8_UNORM => true,
+        _ => anyhow::bail!("unsupported surface format {format:?}"),
+    };
+
+    let image = app.gfx.new_image(
+        width,
+        height,
+        format,
+        ImageUsage::TRANSFER_SRC | ImageUsage::COLOR_ATTACHMENT,
+    )?;
+    let tgt = RenderTarget {
+        views: smallvec![ImageView::new_default(image.clone())?],
+    };
+
+    let mut rdr = RenderResources::new(app.gfx.clone(), tgt, &meta)?;
+    overlay.render(app, &mut rdr)?;
+    let mut futures = GpuFutures::default();
+    futures.execute_results(rdr.end()?)?;
+    futures.wait()?;
+
+    let mut cmd_xfer = app
+        .gfx
+        .create_xfer_command_buffer(CommandBufferUsage::OneTimeSubmit)?;
+    let buffer = cmd_xfer.download_image(&image)?;
+    cmd_xfer
+        .build_and_execute()?
+        .then_signal_fence_and_flush()?
+        .wait(None)?;
+
+    let mut pixels = buffer.read()?.to_vec();
+    if bgra {
+        pixels.chunks_exact_mut(4).for_each(|px| px.swap(0, 2));
+    }
+    RgbaImage::from_raw(width, height, pixels).context("pixel data does not match dimensions")
+}
+
+/// Places images next to each other, centered vertically.
+fn stitch(images: &[RgbaImage]) -> RgbaImage {
+    let width = images.iter().map(RgbaImage::width).sum::<u32>()
+        + STITCH_GAP * (images.len() as u32).saturating_sub(1);
+    let height = images.iter().map(RgbaImage::height).max().unwrap_or(0);
+
+    let mut canvas = RgbaImage::new(width, height);
+    let mut x = 0;
+    for image in images {
+        let y = (height - image.height()) / 2;
+        image::imageops::replace(&mut canvas, image, i64::from(x), i64::from(y));
+        x += image.width() + STITCH_GAP;
+    }
+    canvas
+}
+
+fn save(app: &AppState, image: RgbaImage, name: &str) {
+    let dir = resolve_path(&app.session.config.screenshot_path);
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    let path = dir.join(format!(
+        "{name}_{}.png",
+        // milliseconds keep screenshots taken in quick succession apart
+        Local::now().format("%Y-%m-%d_%H-%M-%S-%3f")
+    ));
+
+    let sender = app.screenshots.sender.clone();
+    std::thread::spawn(move || {
+        let result = std::fs::create_dir_all(&dir)
+            .with_context(|| format!("could not create {}", dir.display()))
+            .and_then(|()| {
+                image
+                    .save_with_format(&path, image::ImageFormat::Png)
+                    .with_context(|| format!("could not write {}", path.display()))
+            })
+            .map(|()| {
+                // fit into a square, the toast doesn't get taller than wide
+                let scale = PREVIEW_WIDTH as f32 / image.width().max(image.height()) as f32;
+                let preview = image::imageops::thumbnail(
+                    &image,
+                    ((image.width() as f32 * scale) as u32).max(1),
+                    ((image.height() as f32 * scale) as u32).max(1),
+                );
+                Saved { path, preview }
+            });
+        let _ = sender.send(result);
+    });
+}
+
+fn error_toast(app: &mut AppState, error: &anyhow::Error) {
+    log::warn!("Screenshot failed: {error:?}");
+    Toast::new(
+        ToastTopic::Error,
+        "SCREENSHOT.FAILED".into(),
+        format!("{error:#}"),
+    )
+    .with_timeout(4.)
+    .submit(app);
+}
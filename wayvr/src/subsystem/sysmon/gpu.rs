@@ -0,0 +1,141 @@
+use std::path::{Path, PathBuf};
+
+use super::nvml::Nvml;
+
+const VENDOR_AMD: &str = "0x1002";
+const VENDOR_INTEL: &str = "0x8086";
+const VENDOR_NVIDIA: &str = "0x10de";
+
+#[derive(Clone, Copy, Default)]
+pub struct GpuStats {
+    /// Percent
+    pub usage: Option<f32>,
+    pub vram_used: Option<u64>,
+    pub vram_total: Option<u64>,
+    /// Degrees Celsius
+    pub temperature: Option<f32>,
+    pub freq_mhz: Option<u32>,
+}
+
+enum GpuKind {
+    Amd,
+    Intel,
+    Nvidia { nvml_index: u32 },
+    Other,
+}
+
+struct Gpu {
+    /// `/sys/class/drm/cardN`
+    card: PathBuf,
+    kind: GpuKind,
+}
+
+/// GPUs in the order of their DRM card number.
+pub struct Gpus {
+    gpus: Vec<Gpu>,
+    nvml: Option<Nvml>,
+}
+
+impl Gpus {
+    pub fn new() -> Self {
+        let mut cards: Vec<(u32, PathBuf)> = std::fs::read_dir("/sys/class/drm")
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                // skip connectors such as card0-DP-1
+                let name = entry.file_name();
+                let num = name.to_str()?.strip_prefix("card")?.parse().ok()?;
+                Some((num, entry.path()))
+            })
+            .collect();
+        cards.sort_by_key(|(num, _)| *num);
+
+        let mut num_nvidia = 0;
+        let gpus: Vec<_> = cards
+            .into_iter()
+            .map(|(_, card)| {
+                let vendor = read_string(&card.join("device/vendor")).unwrap_or_default();
+                let kind = match vendor.as_str() {
+                    VENDOR_AMD => GpuKind::Amd,
+                    VENDOR_INTEL => GpuKind::Intel,
+                    VENDOR_NVIDIA => {
+                        // NVML enumerates by PCI bus, same as DRM in practice
+                        num_nvidia += 1;
+                        GpuKind::Nvidia {
+                            nvml_index: num_nvidia - 1,
+                        }
+                    }
+                    _ => GpuKind::Other,
+                };
+                Gpu { card, kind }
+            })
+            .collect();
+
+        let nvml = if num_nvidia > 0 {
+            Nvml::load()
+                .inspect_err(|e| log::info!("NVIDIA GPU stats unavailable: {e:?}"))
+                .ok()
+        } else {
+            None
+        };
+
+        Self { gpus, nvml }
+    }
+
+    pub fn stats(&self, index: usize) -> Option<GpuStats> {
+        let gpu = self.gpus.get(index)?;
+        let device = gpu.card.join("device");
+        let hwmon = find_hwmon(&device);
+
+        let hwmon_temp = || {
+            hwmon
+                .as_ref()
+                .and_then(|h| read_num::<f32>(&h.join("temp1_input")))
+                .map(|t| t / 1000.)
+        };
+
+        match gpu.kind {
+            GpuKind::Amd => Some(GpuStats {
+                usage: read_num(&device.join("gpu_busy_percent")),
+                vram_used: read_num(&device.join("mem_info_vram_used")),
+                vram_total: read_num(&device.join("mem_info_vram_total")),
+                temperature: hwmon_temp(),
+                freq_mhz: hwmon
+                    .as_ref()
+                    .and_then(|h| read_num::<u64>(&h.join("freq1_input")))
+                    .map(|hz| (hz / 1_000_000) as u32),
+            }),
+            GpuKind::Intel => Some(GpuStats {
+                temperature: hwmon_temp(),
+                // i915 and xe respectively
+                freq_mhz: read_num(&gpu.card.join("gt_act_freq_mhz"))
+                    .or_else(|| read_num(&device.join("tile0/gt0/freq0/act_freq"))),
+                ..GpuStats::default()
+            }),
+            GpuKind::Nvidia { nvml_index } => self.nvml.as_ref()?.stats(nvml_index),
+            GpuKind::Other => Some(GpuStats {
+                temperature: hwmon_temp(),
+                ..GpuStats::default()
+            }),
+        }
+    }
+}
+
+fn find_hwmon(device: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(device.join("hwmon"))
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .next()
+}
+
+fn read_string(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+fn read_num<T: std::str::FromStr>(path: &Path) -> Option<T> {
+    read_string(path)?.parse().ok()
+}
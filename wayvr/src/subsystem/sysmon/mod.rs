@@ -0,0 +1,354 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use sysinfo::{Components, Disks, Networks, System};
+
+use gpu::{GpuStats, Gpus};
+
+mod gpu;
+mod nvml;
+
+/// Stats are refreshed at most this often, no matter how many labels ask for them.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Metric {
+    CpuUsage,
+    RamUsed,
+    RamTotal,
+    RamPercent,
+    GpuUsage(usize),
+    GpuVramUsed(usize),
+    GpuVramTotal(usize),
+    GpuVramPercent(usize),
+    GpuTemperature(usize),
+    GpuFrequency(usize),
+    /// All interfaces if none given
+    NetDown(Option<String>),
+    NetUp(Option<String>),
+    DiskUsed(PathBuf),
+    DiskFree(PathBuf),
+    DiskTotal(PathBuf),
+    DiskPercent(PathBuf),
+    /// Hottest sensor if no label given
+    Temperature(Option<String>),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum MetricValue {
+    Percent(f32),
+    Bytes(u64),
+    BytesPerSec(f64),
+    Celsius(f32),
+    MegaHertz(u32),
+}
+
+impl MetricValue {
+    pub const fn as_f64(self) -> f64 {
+        match self {
+            Self::Percent(v) | Self::Celsius(v) => v as f64,
+            Self::Bytes(v) => v as f64,
+            Self::BytesPerSec(v) => v,
+            Self::MegaHertz(v) => v as f64,
+        }
+    }
+}
+
+/// System resource usage, for `cpu`, `ram`, `gpu`, `net`, `disk` and `temp` labels.
+/// Reading disks, sysfs and NVML can block, so the stats are polled on a background thread.
+/// Values lag behind by up to `REFRESH_INTERVAL`.
+#[derive(Default)]
+pub struct SystemMonitor {
+    worker: Option<Worker>,
+    /// Read since the last request to the worker
+    wanted: HashSet<Metric>,
+    values: HashMap<Metric, MetricValue>,
+    next_request: Option<Instant>,
+}
+
+impl SystemMonitor {
+    /// The last polled value of `metric`. It starts being polled on the first read.
+    pub fn read(&mut self, metric: &Metric) -> Option<MetricValue> {
+        if !self.wanted.contains(metric) {
+            self.wanted.insert(metric.clone());
+        }
+        self.update();
+        self.values.get(metric).copied()
+    }
+
+    fn update(&mut self) {
+        let now = Instant::now();
+        if self.next_request.is_some_and(|next| now < next) {
+            return;
+        }
+        let worker = self.worker.get_or_insert_with(Worker::spawn);
+
+        if worker.busy {
+            match worker.results.try_recv() {
+                Ok(results) => {
+                    worker.busy = false;
+                    for (metric, value) in results {
+                        match value {
+                            Some(value) => self.values.insert(metric, value),
+                            None => self.values.remove(&metric),
+                        };
+                    }
+                }
+                Err(mpsc::TryRecvError::Empty) => return,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    log::warn!("System monitor stopped unexpectedly, restarting.");
+                    self.worker = None;
+                    self.next_request = Some(now + REFRESH_INTERVAL);
+                    return;
+                }
+            }
+        }
+
+        let metrics = self.wanted.drain().collect();
+        if worker.requests.send(metrics).is_ok() {
+            worker.busy = true;
+        }
+        self.next_request = Some(now + REFRESH_INTERVAL);
+    }
+}
+
+struct Worker {
+    requests: mpsc::Sender<Vec<Metric>>,
+    results: mpsc::Receiver<Vec<(Metric, Option<MetricValue>)>>,
+    /// Waiting for the results of a request
+    busy: bool,
+}
+
+impl Worker {
+    /// Stops once the `SystemMonitor` is dropped.
+    fn spawn() -> Self {
+        let (requests, rx_requests) = mpsc::channel::<Vec<Metric>>();
+        let (tx_results, results) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            let mut poller = Poller::default();
+            while let Ok(metrics) = rx_requests.recv() {
+                poller.poll += 1;
+                let results = metrics
+                    .into_iter()
+                    .map(|metric| {
+                        let value = poller.read(&metric);
+                        (metric, value)
+                    })
+                    .collect();
+                if tx_results.send(results).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            requests,
+            results,
+            busy: false,
+        }
+    }
+}
+
+/// Last refresh of a single kind of stat.
+#[derive(Default)]
+struct Refresh(Option<(u64, Instant)>);
+
+impl Refresh {
+    /// Returns the time since the previous refresh if there was none during `poll` yet.
+    fn due(&mut self, poll: u64) -> Option<Duration> {
+        let now = Instant::now();
+        match self.0 {
+            Some((last_poll, _)) if last_poll == poll => None,
+            last => {
+                self.0 = Some((poll, now));
+                Some(last.map_or(Duration::ZERO, |(_, last)| now - last))
+            }
+        }
+    }
+}
+
+/// Only the kinds of stats that are actually displayed get refreshed, once per poll.
+#[derive(Default)]
+struct Poller {
+    /// Counts requests from the `SystemMonitor`
+    poll: u64,
+    system: System,
+    cpu_refresh: Refresh,
+    ram_refresh: Refresh,
+
+    networks: Option<Networks>,
+    net_refresh: Refresh,
+    /// bytes per second down, up
+    net_rates: HashMap<String, (f64, f64)>,
+
+    disks: Option<Disks>,
+    disk_refresh: Refresh,
+
+    components: Option<Components>,
+    temp_refresh: Refresh,
+
+    gpus: Option<Gpus>,
+    gpu_refresh: Refresh,
+    gpu_stats: HashMap<usize, GpuStats>,
+}
+
+impl Poller {
+    fn read(&mut self, metric: &Metric) -> Option<MetricValue> {
+        match metric {
+            Metric::CpuUsage => {
+                if self.cpu_refresh.due(self.poll).is_some() {
+                    self.system.refresh_cpu_usage();
+                }
+                Some(MetricValue::Percent(self.system.global_cpu_usage()))
+            }
+            Metric::RamUsed | Metric::RamTotal | Metric::RamPercent => {
+                if self.ram_refresh.due(self.poll).is_some() {
+                    self.system.refresh_memory();
+                }
+                let used = self.system.used_memory();
+                let total = self.system.total_memory();
+                Some(match metric {
+                    Metric::RamUsed => MetricValue::Bytes(used),
+                    Metric::RamTotal => MetricValue::Bytes(total),
+                    _ => MetricValue::Percent(percent(used, total)?),
+                })
+            }
+            Metric::GpuUsage(idx)
+            | Metric::GpuVramUsed(idx)
+            | Metric::GpuVramTotal(idx)
+            | Metric::GpuVramPercent(idx)
+            | Metric::GpuTemperature(idx)
+            | Metric::GpuFrequency(idx) => {
+                let stats = self.gpu_stats(*idx)?;
+                match metric {
+                    Metric::GpuUsage(_) => stats.usage.map(MetricValue::Percent),
+                    Metric::GpuVramUsed(_) => stats.vram_used.map(MetricValue::Bytes),
+                    Metric::GpuVramTotal(_) => stats.vram_total.map(MetricValue::Bytes),
+                    Metric::GpuVramPercent(_) => {
+                        percent(stats.vram_used?, stats.vram_total?).map(MetricValue::Percent)
+                    }
+                    Metric::GpuTemperature(_) => stats.temperature.map(MetricValue::Celsius),
+                    _ => stats.freq_mhz.map(MetricValue::MegaHertz),
+                }
+            }
+            Metric::NetDown(iface) | Metric::NetUp(iface) => {
+                self.refresh_networks();
+                let (down, up) = match iface {
+                    Some(iface) => *self.net_rates.get(iface)?,
+                    None => self
+                        .net_rates
+                        .values()
+                        .fold((0., 0.), |acc, (d, u)| (acc.0 + d, acc.1 + u)),
+                };
+                Some(MetricValue::BytesPerSec(
+                    if matches!(metric, Metric::NetDown(_)) {
+                        down
+                    } else {
+                        up
+                    },
+                ))
+            }
+            Metric::DiskUsed(mount)
+            | Metric::DiskFree(mount)
+            | Metric::DiskTotal(mount)
+            | Metric::DiskPercent(mount) => {
+                let (free, total) = self.disk_space(mount)?;
+                let used = total.saturating_sub(free);
+                Some(match metric {
+                    Metric::DiskUsed(_) => MetricValue::Bytes(used),
+                    Metric::DiskFree(_) => MetricValue::Bytes(free),
+                    Metric::DiskTotal(_) => MetricValue::Bytes(total),
+                    _ => MetricValue::Percent(percent(used, total)?),
+                })
+            }
+            Metric::Temperature(label) => {
+                let components = self.components.get_or_insert_with(|| {
+                    self.temp_refresh.due(self.poll);
+                    Components::new_with_refreshed_list()
+                });
+                if self.temp_refresh.due(self.poll).is_some() {
+                    components.refresh(false);
+                }
+
+                components
+                    .iter()
+                    .filter(|c| {
+                        label
+                            .as_ref()
+                            .is_none_or(|l| c.label().contains(l.as_str()))
+                    })
+                    .filter_map(sysinfo::Component::temperature)
+                    .reduce(f32::max)
+                    .map(MetricValue::Celsius)
+            }
+        }
+    }
+
+    fn gpu_stats(&mut self, idx: usize) -> Option<GpuStats> {
+        let gpus = self.gpus.get_or_insert_with(Gpus::new);
+        if self.gpu_refresh.due(self.poll).is_some() {
+            self.gpu_stats.clear();
+        }
+        if let Some(stats) = self.gpu_stats.get(&idx) {
+            return Some(*stats);
+        }
+        let stats = gpus.stats(idx)?;
+        self.gpu_stats.insert(idx, stats);
+        Some(stats)
+    }
+
+    fn refresh_networks(&mut self) {
+        let networks = self
+            .networks
+            .get_or_insert_with(Networks::new_with_refreshed_list);
+        let Some(elapsed) = self.net_refresh.due(self.poll) else {
+            return;
+        };
+        networks.refresh(true);
+
+        // the first refresh has nothing to compare against
+        let secs = elapsed.as_secs_f64();
+        if secs == 0. {
+            return;
+        }
+
+        self.net_rates = networks
+            .iter()
+            .filter(|(name, _)| name.as_str() != "lo")
+            .map(|(name, data)| {
+                (
+                    name.clone(),
+                    (
+                        data.received() as f64 / secs,
+                        data.transmitted() as f64 / secs,
+                    ),
+                )
+            })
+            .collect();
+    }
+
+    /// Returns (available, total) bytes of the disk mounted at `mount`.
+    fn disk_space(&mut self, mount: &Path) -> Option<(u64, u64)> {
+        let disks = self.disks.get_or_insert_with(|| {
+            self.disk_refresh.due(self.poll);
+            Disks::new_with_refreshed_list()
+        });
+        if self.disk_refresh.due(self.poll).is_some() {
+            disks.refresh(false);
+        }
+
+        disks
+            .iter()
+            .find(|d| d.mount_point() == mount)
+            .map(|d| (d.available_space(), d.total_space()))
+    }
+}
+
+fn percent(part: u64, total: u64) -> Option<f32> {
+    (total > 0).then(|| (part as f64 / total as f64 * 100.) as f32)
+}
@@ -0,0 +1,150 @@
+//! Minimal NVML bindings, loaded at runtime so that we don't depend on the NVIDIA driver.
+
+use std::ffi::{CStr, c_uint, c_void};
+
+use anyhow::bail;
+
+use super::gpu::GpuStats;
+
+type NvmlReturn = c_uint;
+type NvmlDevice = *mut c_void;
+
+const NVML_SUCCESS: NvmlReturn = 0;
+const NVML_TEMPERATURE_GPU: c_uint = 0;
+const NVML_CLOCK_GRAPHICS: c_uint = 0;
+
+#[repr(C)]
+#[derive(Default)]
+struct NvmlUtilization {
+    gpu: c_uint,
+    memory: c_uint,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct NvmlMemory {
+    total: u64,
+    free: u64,
+    used: u64,
+}
+
+struct NvmlFns {
+    init: unsafe extern "C" fn() -> NvmlReturn,
+    shutdown: unsafe extern "C" fn() -> NvmlReturn,
+    handle_by_index: unsafe extern "C" fn(c_uint, *mut NvmlDevice) -> NvmlReturn,
+    utilization: unsafe extern "C" fn(NvmlDevice, *mut NvmlUtilization) -> NvmlReturn,
+    memory: unsafe extern "C" fn(NvmlDevice, *mut NvmlMemory) -> NvmlReturn,
+    temperature: unsafe extern "C" fn(NvmlDevice, c_uint, *mut c_uint) -> NvmlReturn,
+    clock: unsafe extern "C" fn(NvmlDevice, c_uint, *mut c_uint) -> NvmlReturn,
+}
+
+pub struct Nvml {
+    lib: *mut c_void,
+    fns: NvmlFns,
+}
+
+impl Nvml {
+    pub fn load() -> anyhow::Result<Self> {
+        // SAFETY: the library name is a valid C string and we check the result
+        let lib = unsafe { libc::dlopen(c"libnvidia-ml.so.1".as_ptr(), libc::RTLD_NOW) };
+        if lib.is_null() {
+            bail!("libnvidia-ml.so.1 not found");
+        }
+
+        // SAFETY: the signatures above match nvml.h
+        let fns = unsafe {
+            let sym = |name: &CStr| {
+                let ptr = libc::dlsym(lib, name.as_ptr());
+                (!ptr.is_null()).then_some(ptr)
+            };
+            let (
+                Some(init),
+                Some(shutdown),
+                Some(handle_by_index),
+                Some(utilization),
+                Some(memory),
+                Some(temperature),
+                Some(clock),
+            ) = (
+                sym(c"nvmlInit_v2"),
+                sym(c"nvmlShutdown"),
+                sym(c"nvmlDeviceGetHandleByIndex_v2"),
+                sym(c"nvmlDeviceGetUtilizationRates"),
+                sym(c"nvmlDeviceGetMemoryInfo"),
+                sym(c"nvmlDeviceGetTemperature"),
+                sym(c"nvmlDeviceGetClockInfo"),
+            )
+            else {
+                libc::dlclose(lib);
+                bail!("libnvidia-ml.so.1 is missing symbols");
+            };
+            NvmlFns {
+                init: std::mem::transmute::<*mut c_void, _>(init),
+                shutdown: std::mem::transmute::<*mut c_void, _>(shutdown),
+                handle_by_index: std::mem::transmute::<*mut c_void, _>(handle_by_index),
+                utilization: std::mem::transmute::<*mut c_void, _>(utilization),
+                memory: std::mem::transmute::<*mut c_void, _>(memory),
+                temperature: std::mem::transmute::<*mut c_void, _>(temperature),
+                clock: std::mem::transmute::<*mut c_void, _>(clock),
+            }
+        };
+
+        // SAFETY: no arguments
+        let ret = unsafe { (fns.init)() };
+        if ret != NVML_SUCCESS {
+            // SAFETY: lib is a valid handle from dlopen
+            unsafe { libc::dlclose(lib) };
+            bail!("nvmlInit failed: {ret}");
+        }
+
+        Ok(Self { lib, fns })
+    }
+
+    pub fn stats(&self, index: u32) -> Option<GpuStats> {
+        let ok = |ret: NvmlReturn| ret == NVML_SUCCESS;
+
+        // SAFETY: all out-pointers point to valid locals, NVML was initialized in load()
+        unsafe {
+            let mut device: NvmlDevice = std::ptr::null_mut();
+            if !ok((self.fns.handle_by_index)(index, &raw mut device)) {
+                return None;
+            }
+
+            let mut util = NvmlUtilization::default();
+            let mut mem = NvmlMemory::default();
+            let mut temp: c_uint = 0;
+            let mut clock: c_uint = 0;
+
+            let has_util = ok((self.fns.utilization)(device, &raw mut util));
+            let has_mem = ok((self.fns.memory)(device, &raw mut mem));
+            let has_temp = ok((self.fns.temperature)(
+                device,
+                NVML_TEMPERATURE_GPU,
+                &raw mut temp,
+            ));
+            let has_clock = ok((self.fns.clock)(
+                device,
+                NVML_CLOCK_GRAPHICS,
+                &raw mut clock,
+            ));
+
+            Some(GpuStats {
+                usage: has_util.then_some(util.gpu as f32),
+                vram_used: has_mem.then_some(mem.used),
+                vram_total: has_mem.then_some(mem.total),
+                temperature: has_temp.then_some(temp as f32),
+                freq_mhz: has_clock.then_some(clock),
+            })
+        }
+    }
+}
+
+impl Drop for Nvml {
+    fn drop(&mut self) {
+        // SAFETY: initialized in load(), not used after this
+        unsafe {
+            (self.fns.shutdown)();
+            libc::dlclose(self.lib);
+        }
+    }
+}
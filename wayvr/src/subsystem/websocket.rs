@@ -0,0 +1,160 @@
+//! Just enough of RFC 6455 to receive messages from local clients.
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpStream,
+};
+
+use anyhow::{Context, bail};
+use base64::Engine;
+use sha1::{Digest, Sha1};
+
+const ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Clients send icons inline, so this needs to be somewhat generous
+const MAX_MESSAGE_LEN: usize = 4 * 1024 * 1024;
+
+const OP_CONTINUATION: u8 = 0x0;
+const OP_TEXT: u8 = 0x1;
+const OP_BINARY: u8 = 0x2;
+const OP_CLOSE: u8 = 0x8;
+const OP_PING: u8 = 0x9;
+const OP_PONG: u8 = 0xA;
+
+pub struct WebSocket {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl WebSocket {
+    /// Performs the server side of the opening handshake.
+    /// Requests from web pages are refused, only local applications may connect.
+    pub fn accept(stream: TcpStream) -> anyhow::Result<Self> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut writer = stream;
+
+        let mut key = None;
+        let mut origin = None;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                bail!("Connection closed during handshake");
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            let Some((name, value)) = line.split_once(':') else {
+                continue;
+            };
+            let name = name.trim();
+            if name.eq_ignore_ascii_case("sec-websocket-key") {
+                key = Some(value.trim().to_string());
+            } else if name.eq_ignore_ascii_case("origin") {
+                origin = Some(value.trim().to_string());
+            }
+        }
+        let key = key.context("Not a WebSocket request")?;
+
+        // browsers always send the origin, native clients don't
+        if let Some(origin) = origin {
+            write!(
+                writer,
+                "HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\n\r\n"
+            )?;
+            writer.flush()?;
+            bail!("Refused WebSocket connection from {origin}");
+        }
+
+        let mut hasher = Sha1::new();
+        hasher.update(key.as_bytes());
+        hasher.update(ACCEPT_GUID.as_bytes());
+        let accept = base64::engine::general_purpose::STANDARD.encode(hasher.finalize());
+
+        write!(
+            writer,
+            "HTTP/1.1 101 Switching Protocols\r\n\
+             Upgrade: websocket\r\n\
+             Connection: Upgrade\r\n\
+             Sec-WebSocket-Accept: {accept}\r\n\r\n"
+        )?;
+        writer.flush()?;
+
+        Ok(Self { reader, writer })
+    }
+
+    /// Blocks until a full text or binary message arrives.
+    /// Returns `None` once the client closes the connection.
+    pub fn read_message(&mut self) -> anyhow::Result<Option<Vec<u8>>> {
+        let mut message = Vec::new();
+        loop {
+            let mut header = [0u8; 2];
+            self.reader.read_exact(&mut header)?;
+
+            let fin = header[0] & 0x80 != 0;
+            let opcode = header[0] & 0x0F;
+            let masked = header[1] & 0x80 != 0;
+
+            let len = match header[1] & 0x7F {
+                126 => {
+                    let mut len = [0u8; 2];
+                    self.reader.read_exact(&mut len)?;
+                    u64::from(u16::from_be_bytes(len))
+                }
+                127 => {
+                    let mut len = [0u8; 8];
+                    self.reader.read_exact(&mut len)?;
+                    u64::from_be_bytes(len)
+                }
+                len => u64::from(len),
+            };
+            let len = usize::try_from(len)
+                .ok()
+                .filter(|len| message.len() + len <= MAX_MESSAGE_LEN)
+                .context("Message too long")?;
+
+            let mut mask = [0u8; 4];
+            if masked {
+                self.reader.read_exact(&mut mask)?;
+            }
+
+            let mut payload = vec![0u8; len];
+            self.reader.read_exact(&mut payload)?;
+            if masked {
+                for (i, b) in payload.iter_mut().enumerate() {
+                    *b ^= mask[i % 4];
+                }
+            }
+
+            match opcode {
+                OP_TEXT | OP_BINARY | OP_CONTINUATION => {
+                    message.extend_from_slice(&payload);
+                    if fin {
+                        return Ok(Some(message));
+                    }
+                }
+                OP_CLOSE => {
+                    let _ = self.write_frame(OP_CLOSE, &payload);
+                    return Ok(None);
+                }
+                OP_PING => self.write_frame(OP_PONG, &payload)?,
+                OP_PONG => {}
+                _ => bail!("Unknown opcode {opcode}"),
+            }
+        }
+    }
+
+    fn write_frame(&mut self, opcode: u8, payload: &[u8]) -> anyhow::Result<()> {
+        let mut frame = vec![0x80 | opcode];
+        // control frames are always short
+        let len = u8::try_from(payload.len())
+            .ok()
+            .filter(|len| *len <= 125)
+            .context("Payload too long")?;
+        frame.push(len);
+        frame.extend_from_slice(payload);
+        self.writer.write_all(&frame)?;
+        Ok(())
+    }
+}
@@ -121,6 +121,8 @@ pub enum OverlayEventData {
     VisibleOverlaysChanged(Rc<[OverlayID]>),
     DevicesChanged,
     SettingsChanged,
+    NotificationsChanged,
+    ClipboardChanged,
     OverlayGrabbed {
         name: Arc<str>,
         pos: Positioning,
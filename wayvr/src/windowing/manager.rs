@@ -1,7 +1,7 @@
 use std::{
     collections::{HashMap, VecDeque},
     rc::Rc,
-    sync::atomic::Ordering,
+    sync::{Arc, atomic::Ordering},
 };
 
 use anyhow::Context;
@@ -12,26 +12,31 @@ use wlx_common::{
     astr_containers::{AStrMap, AStrMapExt},
     config::SerializedWindowSet,
     overlays::{BackendAttrib, BackendAttribValue, ToastTopic},
+    windowing::{OverlayWindowState, Positioning},
 };
 
 use crate::{
     FRAME_COUNTER,
-    backend::task::{OverlayTask, ToggleMode},
+    backend::task::{OverlayTask, PanelReload, ToggleMode},
     config::save_state,
     overlays::{
         anchor::{create_anchor, create_grab_help},
-        custom::create_custom,
+        clipboard::create_clipboard,
+        custom::{create_custom, try_create_custom},
         dashboard::{DASH_NAME, create_dash_frontend},
         edit::EditWrapperManager,
-        keyboard::create_keyboard,
+        keyboard::{KEYBOARD_NAME, create_keyboard},
+        notifications::create_notifications,
+        quick_settings::create_quick_settings,
         screen::create_screens,
         toast::Toast,
-        watch::{WATCH_NAME, create_watch},
+        watch::{WATCH_NAME, create_watch, watch_positioning},
     },
     state::AppState,
+    subsystem::{hot_reload::reload_error_toast, screenshot},
     windowing::{
         OverlayID, OverlaySelector,
-        backend::{OverlayEventData, OverlayMeta},
+        backend::{OverlayBackend, OverlayEventData, OverlayMeta},
         set::OverlayWindowSet,
         snap_upright,
         window::{OverlayCategory, OverlayWindowData},
@@ -53,7 +58,11 @@ pub struct OverlayWindowManager<T> {
     anchor_local: Affine3A,
     watch_id: OverlayID,
     keyboard_id: OverlayID,
+    /// The right half of a split keyboard, shown along with `keyboard_id`
+    keyboard_right_id: Option<OverlayID>,
     edit_mode: bool,
+    /// Whether the keyboard was created for a Wayland desktop
+    wayland: bool,
     dropped_overlays: VecDeque<OverlayWindowData<T>>,
     initialized: bool,
 }
@@ -73,7 +82,9 @@ where
             anchor_local: Affine3A::from_translation(Vec3::NEG_Z),
             watch_id: OverlayID::null(),    // set down below
             keyboard_id: OverlayID::null(), // set down below
+            keyboard_right_id: None,
             edit_mode: false,
+            wayland: false,
             dropped_overlays: VecDeque::with_capacity(8),
             initialized: false,
         };
@@ -106,9 +117,16 @@ where
             }
         }
 
-        let mut keyboard = OverlayWindowData::from_config(create_keyboard(app, wayland)?);
+        me.wayland = wayland;
+        let (keyboard, keyboard_right) =
+            create_keyboard(app, wayland, app.session.config.keyboard_split)?;
+        let mut keyboard = OverlayWindowData::from_config(keyboard);
         keyboard.config.show_on_spawn = true;
         me.keyboard_id = me.add(keyboard, app);
+        if let Some(keyboard_right) = keyboard_right {
+            let keyboard_right = OverlayWindowData::from_config(keyboard_right);
+            me.keyboard_right_id = Some(me.add(keyboard_right, app));
+        }
 
         // is this needed?
         me.switch_to_set(app, None, false);
@@ -136,6 +154,15 @@ where
         let grab_help = OverlayWindowData::from_config(create_grab_help(app)?);
         me.add(grab_help, app);
 
+        let notifications = OverlayWindowData::from_config(create_notifications(app)?);
+        me.add(notifications, app);
+
+        let quick_settings = OverlayWindowData::from_config(create_quick_settings(app)?);
+        me.add(quick_settings, app);
+
+        let clipboard = OverlayWindowData::from_config(create_clipboard(app)?);
+        me.add(clipboard, app);
+
         let custom_panels = app.session.config.custom_panels.clone();
         for name in custom_panels {
             let Some(panel) = create_custom(app, name) else {
@@ -288,6 +315,14 @@ where
                 self.sets_changed(app);
             }
             OverlayTask::SettingsChanged => {
+                app.session.config_revision += 1;
+                if let Some(wvr_server) = app.wvr_server.as_mut() {
+                    let config = &app.session.config;
+                    wvr_server.set_repeat_info(
+                        config.keyboard_repeat_delay_ms,
+                        config.keyboard_repeat_rate,
+                    );
+                }
                 for o in self.overlays.values_mut() {
                     let _ = o
                         .config
@@ -356,6 +391,8 @@ where
                     self.dropped_overlays.push_back(o);
                 }
             }
+            OverlayTask::ReloadPanels(reload) => self.reload_panels(app, &reload),
+            OverlayTask::Screenshot(sel) => screenshot::take(app, self, sel.as_ref()),
             OverlayTask::ModifyPanel(task) => {
                 if let Some(oid) = self.lookup(&task.overlay)
                     && let Some(o) = self.mut_by_id(oid)
@@ -484,6 +521,18 @@ impl<T> OverlayWindowManager<T> {
         self.sets.clear();
         self.sets.reserve(app.session.config.sets.len());
 
+        // a keyboard saved before toggling keyboard_split would end up in the wrong place
+        let kbd_default = self.overlays[self.keyboard_id].config.default_state.clone();
+        let kbd_state = |name: &str, state: &OverlayWindowState| {
+            let follows_hand =
+                |s: &OverlayWindowState| matches!(s.positioning, Positioning::FollowHand { .. });
+            if name == KEYBOARD_NAME && follows_hand(state) != follows_hand(&kbd_default) {
+                kbd_default.clone()
+            } else {
+                state.clone()
+            }
+        };
+
         for (i, s) in app.session.config.sets.iter().enumerate() {
             let mut overlays = SecondaryMap::new();
             let mut inactive_overlays = AStrMap::new();
@@ -491,7 +540,7 @@ impl<T> OverlayWindowManager<T> {
             for (name, o) in &s.overlays {
                 if let Some(id) = self.lookup(name) {
                     log::debug!("set {i}: loaded state for {name}");
-                    overlays.insert(id, o.clone());
+                    overlays.insert(id, kbd_state(name, o));
                 } else {
                     log::debug!(
                         "set {i} has saved state for {name} which doesn't exist. will apply state once added."
@@ -503,7 +552,7 @@ impl<T> OverlayWindowManager<T> {
             let hidden_overlays: AStrMap<_> = s
                 .hidden_overlays
                 .iter()
-                .map(|(k, v)| (k.clone(), v.clone()))
+                .map(|(k, v)| (k.clone(), kbd_state(k, v)))
                 .collect();
 
             self.sets.push(OverlayWindowSet {
@@ -518,9 +567,17 @@ impl<T> OverlayWindowManager<T> {
         for (name, ows) in app.session.config.global_set.clone().into_iter() {
             let mut ows = ows.clone();
 
-            // fix angle_fade missing on watch if loading older state
             if name.as_ref() == WATCH_NAME {
+                // fix angle_fade missing on watch if loading older state
                 ows.angle_fade = true;
+
+                // the configured hand wins over the saved one
+                let hand = app.session.config.watch_hand;
+                if !matches!(ows.positioning, Positioning::FollowHand { hand: h, .. } if h == hand)
+                {
+                    ows.positioning = watch_positioning(hand);
+                    ows.saved_transform = None;
+                }
             }
 
             if let Some(oid) = self.lookup(&*name)
@@ -575,6 +632,7 @@ impl<T> OverlayWindowManager<T> {
     pub fn set_edit_mode(&mut self, enabled: bool, app: &mut AppState) -> anyhow::Result<()> {
         let changed = enabled != self.edit_mode;
         self.edit_mode = enabled;
+        app.windowing.edit_mode = enabled;
         if !enabled {
             for o in self.overlays.values_mut() {
                 self.wrappers.unwrap_edit_mode(&mut o.config, app)?;
@@ -887,6 +945,13 @@ impl<T> OverlayWindowManager<T> {
     }
 
     fn visible_overlays_changed(&mut self, app: &mut AppState) -> anyhow::Result<()> {
+        app.windowing.active_set = self.current_set;
+        app.windowing.keyboard_visible = self
+            .overlays
+            .get(self.keyboard_id)
+            .is_some_and(|o| o.config.is_active());
+        self.sync_keyboard_right(app);
+
         let mut vis = Vec::with_capacity(self.overlays.len());
 
         for (id, data) in &self.overlays {
@@ -910,6 +975,31 @@ impl<T> OverlayWindowManager<T> {
         Ok(())
     }
 
+    /// The right half of a split keyboard is global, so it follows the left half by hand.
+    fn sync_keyboard_right(&mut self, app: &mut AppState) {
+        let Some(o) = self
+            .keyboard_right_id
+            .and_then(|id| self.overlays.get_mut(id))
+        else {
+            return;
+        };
+        if o.config.is_active() == app.windowing.keyboard_visible {
+            return;
+        }
+
+        if let Some(state) = o.config.active_state.take() {
+            self.global_set
+                .hidden_overlays
+                .arc_set(o.config.name.clone(), state);
+        } else if let Some(state) = self.global_set.hidden_overlays.arc_rm(&o.config.name) {
+            o.config.dirty = true;
+            o.config.active_state = Some(state);
+            o.config.reset(app, false);
+        } else {
+            o.config.activate(app);
+        }
+    }
+
     fn sets_changed(&mut self, app: &mut AppState) {
         let len = self.sets.len();
         for id in [self.watch_id, self.keyboard_id] {
@@ -923,12 +1013,148 @@ impl<T> OverlayWindowManager<T> {
         }
     }
 
+    /// Rebuilds panels from their XML files, keeping each overlay's placement and visibility.
+    fn reload_panels(&mut self, app: &mut AppState, reload: &PanelReload) {
+        let wants = |file: &str| match reload {
+            PanelReload::Named(files) => files.iter().any(|f| &**f == file),
+            PanelReload::All => true,
+        };
+
+        // (overlay, name of its xml file)
+        let mut targets: Vec<(OverlayID, Arc<str>)> = vec![];
+        if wants("watch") {
+            targets.push((self.watch_id, "watch".into()));
+        }
+        if wants("keyboard") {
+            targets.push((self.keyboard_id, "keyboard".into()));
+        }
+        for (id, o) in &self.overlays {
+            if matches!(o.config.category, OverlayCategory::Panel) && wants(&o.config.name) {
+                targets.push((id, o.config.name.clone()));
+            }
+        }
+
+        let mut keyboard_right = None;
+        let mut watch_or_kbd = false;
+        for (id, file) in targets {
+            let Some(o) = self.overlays.get(id) else {
+                continue;
+            };
+            if o.config.editing {
+                log::warn!(
+                    "Not reloading {file}.xml while {} is being edited",
+                    o.config.name
+                );
+                continue;
+            }
+
+            let result = if id == self.watch_id {
+                create_watch(app)
+            } else if id == self.keyboard_id {
+                // keyboard_split only changes on restart
+                let split = self.keyboard_right_id.is_some();
+                create_keyboard(app, self.wayland, split).map(|(keyboard, right)| {
+                    keyboard_right = right;
+                    keyboard
+                })
+            } else {
+                try_create_custom(app, o.config.name.clone())
+            };
+
+            let new_config = match result {
+                Ok(c) => c,
+                Err(e) => {
+                    reload_error_toast(app, &format!("gui/{file}.xml"), &e);
+                    continue;
+                }
+            };
+
+            self.swap_backend(app, id, new_config.backend);
+            log::info!("Reloaded {} from {file}.xml", self.overlays[id].config.name);
+            watch_or_kbd |= id == self.watch_id || id == self.keyboard_id;
+        }
+
+        // both halves share their state, so they're rebuilt together
+        if let Some(right) = keyboard_right
+            && let Some(id) = self.keyboard_right_id
+        {
+            self.swap_backend(app, id, right.backend);
+        }
+
+        // custom panels that failed to load on startup
+        if let PanelReload::Named(files) = reload {
+            let missing: Vec<_> = app
+                .session
+                .config
+                .custom_panels
+                .iter()
+                .filter(|name| files.contains(name) && self.lookup(name).is_none())
+                .cloned()
+                .collect();
+
+            for name in missing {
+                match try_create_custom(app, name.clone()) {
+                    Ok(config) => {
+                        log::info!("Loaded custom panel '{name}'");
+                        self.add(OverlayWindowData::from_config(config), app);
+                    }
+                    Err(e) => reload_error_toast(app, &format!("gui/{name}.xml"), &e),
+                }
+            }
+        }
+
+        if watch_or_kbd {
+            // same as on startup
+            for id in [self.watch_id, self.keyboard_id] {
+                for ev in [
+                    OverlayEventData::NumSetsChanged(self.sets.len()),
+                    OverlayEventData::ActiveSetChanged(self.current_set),
+                    OverlayEventData::EditModeChanged(self.edit_mode),
+                    OverlayEventData::DevicesChanged,
+                ] {
+                    if let Some(o) = self.mut_by_id(id) {
+                        let _ = o.config.backend.notify(app, ev).log_err("Could not notify");
+                    }
+                }
+            }
+        }
+
+        // also refreshes custom panel icons on the watch
+        let _ = self.overlays_changed(app);
+        let _ = self.visible_overlays_changed(app);
+    }
+
+    /// Only the backend is swapped, so transform, visibility and set membership stay.
+    fn swap_backend(
+        &mut self,
+        app: &mut AppState,
+        id: OverlayID,
+        backend: Box<dyn OverlayBackend>,
+    ) {
+        // panel init is idempotent, so it's fine to init even if the backend would do so later.
+        let o = &mut self.overlays[id];
+        o.config.backend = backend;
+        o.config.dirty = true;
+        let _ = o
+            .config
+            .backend
+            .init(app)
+            .and_then(|()| {
+                o.config
+                    .backend
+                    .notify(app, OverlayEventData::IdAssigned(id))
+            })
+            .log_err("Could not initialize reloaded panel");
+    }
+
     pub fn devices_changed(&mut self, app: &mut AppState) -> anyhow::Result<()> {
-        if let Some(watch) = self.mut_by_id(self.watch_id) {
-            let _ = watch
+        // custom panels may list devices, too
+        for o in self.overlays.values_mut() {
+            let _ = o
                 .config
                 .backend
-                .notify(app, OverlayEventData::DevicesChanged);
+                .notify(app, OverlayEventData::DevicesChanged)
+                .log_err("Could not notify DevicesChanged");
         }
 
         Ok(())
@@ -18,6 +18,16 @@ pub enum OverlaySelector {
     Nothing,
 }
 
+/// Overlay manager state that panels can bind to.
+#[derive(Clone, Copy, Default)]
+pub struct WindowingStatus {
+    pub edit_mode: bool,
+    pub active_set: Option<usize>,
+    pub keyboard_visible: bool,
+    /// History entry of the notification that the last toast was created for
+    pub toast_history_id: Option<usize>,
+}
+
 pub const Z_ORDER_TOAST: u32 = 71;
 pub const Z_ORDER_HELP: u32 = 70;
 pub const Z_ORDER_LINES: u32 = 69;
@@ -1,7 +1,10 @@
 use glam::{Affine3A, Mat3A, Quat, Vec3, Vec3A};
 use idmap_derive::IntegerId;
 use std::{f32::consts::PI, sync::Arc};
-use wlx_common::windowing::{OverlayWindowState, Positioning};
+use wlx_common::{
+    common::LeftRight,
+    windowing::{OverlayWindowState, Positioning},
+};
 
 use crate::{
     state::AppState,
@@ -206,8 +209,22 @@ impl OverlayWindowConfig {
         let watch_normal = state.transform.transform_vector3a(Vec3A::NEG_Z).normalize();
         let dot = to_hmd.dot(watch_normal);
 
-        state.alpha = (dot - app.session.config.watch_view_angle_min)
-            / (app.session.config.watch_view_angle_max - app.session.config.watch_view_angle_min);
+        let config = &app.session.config;
+        let (angle_min, angle_max) = match state.positioning {
+            Positioning::FollowHand {
+                hand: LeftRight::Right,
+                ..
+            } => (
+                config.watch_view_angle_min_right,
+                config.watch_view_angle_max_right,
+            ),
+            _ => (
+                config.watch_view_angle_min_left,
+                config.watch_view_angle_max_left,
+            ),
+        };
+
+        state.alpha = (dot - angle_min) / (angle_max - angle_min);
         state.alpha += 0.1;
         state.alpha = state.alpha.clamp(0., 1.);
     }
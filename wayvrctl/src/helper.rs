@@ -192,6 +192,56 @@ pub async fn wlx_panel_modify(
     )
 }
 
+pub async fn wlx_panel_get_value(state: &mut WayVRClientState, overlay: String, element: String) {
+    handle_result(
+        state.pretty_print,
+        WayVRClient::fn_wlx_panel_get_value(
+            state.wayvr_client.clone(),
+            state.serial_generator.increment_get(),
+            packet_client::WlxPanelGetValueParams { overlay, element },
+        )
+        .await
+        .context("failed to get panel value"),
+    )
+}
+
+pub async fn wlx_alarm_add(
+    state: &mut WayVRClientState,
+    when: packet_client::WlxAlarmWhen,
+    label: String,
+) {
+    handle_result(
+        state.pretty_print,
+        WayVRClient::fn_wlx_alarm_add(
+            state.wayvr_client.clone(),
+            state.serial_generator.increment_get(),
+            packet_client::WlxAlarmAddParams { when, label },
+        )
+        .await
+        .context("failed to add alarm"),
+    )
+}
+
+pub async fn wlx_alarm_list(state: &mut WayVRClientState) {
+    handle_result(
+        state.pretty_print,
+        WayVRClient::fn_wlx_alarm_list(
+            state.wayvr_client.clone(),
+            state.serial_generator.increment_get(),
+        )
+        .await
+        .context("failed to list alarms"),
+    )
+}
+
+pub async fn wlx_alarm_remove(state: &mut WayVRClientState, id: u32) {
+    handle_empty_result(
+        WayVRClient::fn_wlx_alarm_remove(state.wayvr_client.clone(), id)
+            .await
+            .context("failed to remove alarm"),
+    )
+}
+
 pub async fn wlx_input_state(state: &mut WayVRClientState) {
     handle_result(
         state.pretty_print,
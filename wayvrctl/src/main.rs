@@ -14,9 +14,10 @@ use wayvr_ipc::{
 };
 
 use crate::helper::{
-    WayVRClientState, wlx_device_haptics, wlx_input_state, wlx_panel_modify, wlx_show_hide,
-    wlx_switch_set, wvr_process_get, wvr_process_launch, wvr_process_list, wvr_process_terminate,
-    wvr_window_list, wvr_window_set_visible,
+    WayVRClientState, wlx_alarm_add, wlx_alarm_list, wlx_alarm_remove, wlx_device_haptics,
+    wlx_input_state, wlx_panel_get_value, wlx_panel_modify, wlx_show_hide, wlx_switch_set,
+    wvr_process_get, wvr_process_launch, wvr_process_list, wvr_process_terminate, wvr_window_list,
+    wvr_window_set_visible,
 };
 
 mod helper;
@@ -187,10 +188,50 @@ async fn run_once(state: &mut WayVRClientState, args: Args) -> anyhow::Result<()
                 SubcommandPanelModify::SetStickyState {
                     sticky_state_0_or_1,
                 } => packet_client::WlxModifyPanelCommand::SetStickyState(sticky_state_0_or_1 != 0),
+                SubcommandPanelModify::SetValue { value } => {
+                    packet_client::WlxModifyPanelCommand::SetValue(value)
+                }
             };
 
             wlx_panel_modify(state, overlay, element, command).await;
         }
+        Subcommands::PanelGetValue { overlay, element } => {
+            wlx_panel_get_value(state, overlay, element).await;
+        }
+        Subcommands::AlarmAdd { when } => {
+            let minutes_to_secs = |minutes: f32| -> anyhow::Result<u32> {
+                if minutes <= 0. {
+                    anyhow::bail!("Minutes must be positive");
+                }
+                Ok((minutes * 60.).round() as u32)
+            };
+
+            let (when, label) = match when {
+                SubcommandAlarmAdd::In { minutes, label } => (
+                    packet_client::WlxAlarmWhen::In(minutes_to_secs(minutes)?),
+                    label,
+                ),
+                SubcommandAlarmAdd::At { hh_mm, label } => {
+                    let (hour, minute) = hh_mm
+                        .split_once(':')
+                        .and_then(|(h, m)| Some((h.parse().ok()?, m.parse().ok()?)))
+                        .context("Invalid time format. Expecting <hours>:<minutes>, for example: 7:30, 18:00")?;
+                    (packet_client::WlxAlarmWhen::At { hour, minute }, label)
+                }
+                SubcommandAlarmAdd::Every { minutes, label } => (
+                    packet_client::WlxAlarmWhen::Every(minutes_to_secs(minutes)?),
+                    label,
+                ),
+            };
+
+            wlx_alarm_add(state, when, label.join(" ")).await;
+        }
+        Subcommands::AlarmList => {
+            wlx_alarm_list(state).await;
+        }
+        Subcommands::AlarmRemove { id } => {
+            wlx_alarm_remove(state, id).await;
+        }
         Subcommands::SwitchSet { set_or_0: set } => {
             let set = if set <= 0 { None } else { Some((set - 1) as _) };
             wlx_switch_set(state, set).await;
@@ -287,6 +328,25 @@ enum Subcommands {
         #[command(subcommand)]
         command: SubcommandPanelModify,
     },
+    /// Get the value of a <CheckBox>, <Slider>, <RadioGroup> or <EditBox>
+    PanelGetValue {
+        /// The name of the overlay (XML file name without extension)
+        overlay: String,
+        /// The id of the element, as set in the XML
+        element: String,
+    },
+    /// Show a toast with a sound and haptics at a later time
+    AlarmAdd {
+        #[command(subcommand)]
+        when: SubcommandAlarmAdd,
+    },
+    /// List pending countdowns, alarms and reminders
+    AlarmList,
+    /// Cancel a countdown, alarm or reminder
+    AlarmRemove {
+        /// The id returned by AlarmAdd or AlarmList
+        id: u32,
+    },
     SwitchSet {
         /// Set number to switch to, 0 to hide all sets
         set_or_0: usize,
@@ -322,4 +382,32 @@ enum SubcommandPanelModify {
     SetVisible { visible_0_or_1: u8 },
     /// Set the sticky state of a <Button>. Intended for buttons without `sticky="1"`.
     SetStickyState { sticky_state_0_or_1: u8 },
+    /// Set the value of a <CheckBox> (0 or 1), <Slider>, <RadioGroup> or <EditBox>
+    SetValue { value: String },
+}
+
+#[derive(clap::Parser, Debug)]
+enum SubcommandAlarmAdd {
+    /// Once, after the given number of minutes
+    In {
+        minutes: f32,
+        /// Text to show in the toast
+        #[arg(num_args = 0.., action = clap::ArgAction::Append)]
+        label: Vec<String>,
+    },
+    /// Once, at the next occurrence of the given local time
+    At {
+        /// Time of day in 24h format, for example: 18:30
+        hh_mm: String,
+        /// Text to show in the toast
+        #[arg(num_args = 0.., action = clap::ArgAction::Append)]
+        label: Vec<String>,
+    },
+    /// Repeatedly, every given number of minutes (at least 1)
+    Every {
+        minutes: f32,
+        /// Text to show in the toast
+        #[arg(num_args = 0.., action = clap::ArgAction::Append)]
+        label: Vec<String>,
+    },
 }
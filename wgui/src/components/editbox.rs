@@ -29,8 +29,17 @@ pub struct Params {
 	pub initial_text: String,
 }
 
+pub struct EditBoxSubmitEvent {
+	pub text: String,
+}
+
+pub type EditBoxSubmitCallback = Box<dyn Fn(&mut CallbackDataCommon, EditBoxSubmitEvent) -> anyhow::Result<()>>;
+
 struct State {
 	text: String,
+	/// Text at the time of the last submit, to skip unchanged submits on focus loss
+	submitted_text: String,
+	on_submit: Option<EditBoxSubmitCallback>,
 	hovered: bool,
 	focused: bool,
 	focused_prev: bool,
@@ -156,10 +165,30 @@ impl ComponentTrait for ComponentEditBox {
 	fn on_focus_change(&self, data: &mut FocusChangeData) {
 		let mut state = self.state.borrow_mut();
 		state.focused = data.focused;
+		if !data.focused {
+			submit(data.common, &mut state);
+		}
 		data.common.alterables.refresh_component_once(&state.self_ref);
 	}
 }
 
+fn submit(common: &mut CallbackDataCommon, state: &mut State) {
+	if state.text == state.submitted_text {
+		return;
+	}
+	state.submitted_text.clone_from(&state.text);
+
+	if let Some(on_submit) = &state.on_submit
+		&& let Err(e) = on_submit(
+			common,
+			EditBoxSubmitEvent {
+				text: state.text.clone(),
+			},
+		) {
+		log::error!("{e:?}");
+	}
+}
+
 fn update_text(common: &mut CallbackDataCommon, state: &mut State, data: &Data, text: String) {
 	let Some(mut label) = common.state.widgets.get_as::<WidgetLabel>(data.id_label) else {
 		return;
@@ -175,12 +204,18 @@ fn update_text(common: &mut CallbackDataCommon, state: &mut State, data: &Data,
 impl ComponentEditBox {
 	pub fn set_text(&self, common: &mut CallbackDataCommon, text: &str) {
 		let mut state = self.state.borrow_mut();
+		state.submitted_text = String::from(text);
 		update_text(common, &mut state, &self.data, String::from(text));
 	}
 
 	pub fn get_text(&self) -> Ref<'_, String> {
 		Ref::map(self.state.borrow(), |x| &x.text)
 	}
+
+	/// Called when Enter is pressed or the edit box loses focus, if the text has changed.
+	pub fn on_submit(&self, func: EditBoxSubmitCallback) {
+		self.state.borrow_mut().on_submit = Some(func);
+	}
 }
 
 fn register_event_text_input(
@@ -208,6 +243,11 @@ fn register_event_text_input(
 				return Ok(EventResult::Pass); // ???
 			};
 
+			if ch == '\r' || ch == '\n' {
+				submit(common, &mut state);
+				return Ok(EventResult::Consumed);
+			}
+
 			let mut new_text = std::mem::take(&mut state.text);
 
 			if ch == '\x08' {
@@ -393,7 +433,9 @@ pub fn construct(
 
 	let state = Rc::new(RefCell::new(State {
 		self_ref: Weak::new(),
+		submitted_text: params.initial_text.clone(),
 		text: params.initial_text,
+		on_submit: None,
 		hovered: false,
 		focused: false,
 		focused_prev: false,
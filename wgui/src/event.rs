@@ -97,6 +97,7 @@ pub enum StyleSetRequest {
 	Margin(taffy::Rect<taffy::LengthPercentageAuto>),
 	Width(taffy::Dimension),
 	Height(taffy::Dimension),
+	MinHeight(taffy::Dimension),
 	Size(taffy::Size<taffy::Dimension>),
 }
 
@@ -5,7 +5,8 @@ use vulkano::{
 	buffer::{Buffer, BufferCreateInfo, BufferUsage, Subbuffer},
 	command_buffer::{
 		AutoCommandBufferBuilder, ClearColorImageInfo, CommandBufferExecFuture, CopyBufferToImageInfo, CopyImageInfo,
-		PrimaryAutoCommandBuffer, PrimaryCommandBufferAbstract, RenderingAttachmentInfo, RenderingInfo, SubpassContents,
+		CopyImageToBufferInfo, PrimaryAutoCommandBuffer, PrimaryCommandBufferAbstract, RenderingAttachmentInfo,
+		RenderingInfo, SubpassContents,
 	},
 	device::Queue,
 	format::{ClearColorValue, ClearValue, Format},
@@ -201,4 +202,29 @@ impl WCommandBuffer<CmdBufXfer> {
 		self.command_buffer.copy_image(copy_info)?;
 		Ok(())
 	}
+
+	/// Copies the whole image into a host-readable buffer.
+	/// The buffer can be read once the command buffer has finished executing.
+	pub fn download_image(&mut self, image: &Arc<Image>) -> anyhow::Result<Subbuffer<[u8]>> {
+		let [width, height, depth] = image.extent();
+		let texel_size = image.format().block_size();
+
+		let buffer: Subbuffer<[u8]> = Buffer::new_slice(
+			self.graphics.memory_allocator.clone(),
+			BufferCreateInfo {
+				usage: BufferUsage::TRANSFER_DST,
+				..Default::default()
+			},
+			AllocationCreateInfo {
+				memory_type_filter: MemoryTypeFilter::PREFER_HOST | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+				..Default::default()
+			},
+			u64::from(width) * u64::from(height) * u64::from(depth) * texel_size,
+		)?;
+
+		self
+			.command_buffer
+			.copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(image.clone(), buffer.clone()))?;
+		Ok(buffer)
+	}
 }
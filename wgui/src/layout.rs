@@ -793,6 +793,9 @@ impl Layout {
 			event::StyleSetRequest::Height(val) => {
 				cur_style.size.height = *val;
 			}
+			event::StyleSetRequest::MinHeight(val) => {
+				cur_style.min_size.height = *val;
+			}
 			event::StyleSetRequest::Size(size) => {
 				cur_style.size = *size;
 			}
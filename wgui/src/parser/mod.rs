@@ -19,7 +19,6 @@ use crate::{
 	globals::WguiGlobals,
 	i18n::Translation,
 	layout::{Layout, LayoutParams, LayoutState, Widget, WidgetID, WidgetMap, WidgetPair},
-	log::LogErr,
 	parser::{
 		component_button::parse_component_button,
 		component_checkbox::{CheckboxKind, parse_component_checkbox},
@@ -1247,16 +1246,15 @@ fn get_doc_from_asset_path(
 	let data = ctx.layout.state.globals.get_asset(asset_path)?;
 	let xml = String::from_utf8(data)?;
 
-	let document = Rc::new(XmlDocument::new(xml, |xml| {
+	let document = Rc::new(XmlDocument::try_new(xml, |xml| {
 		let opt = roxmltree::ParsingOptions {
 			allow_dtd: true,
 			..Default::default()
 		};
+		// roxmltree errors carry the line and column
 		roxmltree::Document::parse_with_options(xml, opt)
-			.context("Unable to parse XML")
-			.log_err_with(&asset_path)
-			.unwrap()
-	}));
+			.with_context(|| format!("Unable to parse XML {}", asset_path.get_str()))
+	})?);
 
 	let root = document.borrow_doc().root();
 	let tag_layout = require_tag_by_name(&root, "layout")?;
@@ -8,6 +8,7 @@ use std::{
 	},
 };
 
+use anyhow::Context;
 use cosmic_text::SubpixelBin;
 use image::RgbaImage;
 use resvg::usvg::{Options, Tree};
@@ -165,6 +166,12 @@ impl CustomGlyphData {
 		}
 	}
 
+	/// Raw RGBA8 pixels, not cached.
+	pub fn from_rgba(width: u32, height: u32, data: Vec<u8>) -> anyhow::Result<Self> {
+		let image = RgbaImage::from_raw(width, height, data).context("pixel data does not match dimensions")?;
+		Ok(Self::new(CustomGlyphContent::Image(image)))
+	}
+
 	pub fn from_bytes_svg(globals: &WguiGlobals, path: &str, data: &[u8]) -> anyhow::Result<Self> {
 		let globals_borrow = &mut globals.get();
 		match globals_borrow.custom_glyph_cache.get(path, data) {
@@ -111,6 +111,14 @@ impl WidgetLabel {
 			common.mark_widget_dirty(self.id);
 		}
 	}
+
+	pub const fn get_text(&self) -> &Translation {
+		&self.params.content
+	}
+
+	pub const fn get_color(&self) -> Option<drawing::Color> {
+		self.params.style.color
+	}
 }
 
 impl WidgetObj for WidgetLabel {
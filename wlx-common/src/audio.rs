@@ -1,4 +1,4 @@
-use std::{collections::HashMap, io::Cursor, rc::Rc};
+use std::{collections::HashMap, io::Cursor, path::Path, rc::Rc};
 
 use rodio::Source;
 use wgui::{assets::AssetProvider, sound::WguiSoundType};
@@ -71,12 +71,16 @@ impl SamplePlayer {
 	}
 
 	pub fn play_sample(&mut self, system: &mut AudioSystem, sample_name: &str) {
+		self.play_sample_volume(system, sample_name, 1.0);
+	}
+
+	pub fn play_sample_volume(&mut self, system: &mut AudioSystem, sample_name: &str, volume: f32) {
 		let Some(sample) = self.samples.get(sample_name) else {
 			log::error!("Failed to play sample by name '{}'", sample_name);
 			return;
 		};
 
-		system.play_sample(sample);
+		system.play_sample_volume(sample, volume);
 	}
 
 	pub fn play_wgui_samples(&mut self, system: &mut AudioSystem, samples: Vec<WguiSoundType>) {
@@ -114,10 +118,25 @@ impl AudioSystem {
 	}
 
 	pub fn play_sample(&mut self, sample: &AudioSample) -> Option<()> {
+		self.play_sample_volume(sample, 1.0)
+	}
+
+	pub fn play_sample_volume(&mut self, sample: &AudioSample, volume: f32) -> Option<()> {
 		let handle = self.get_handle()?;
-		handle.mixer().add(sample.buffer.clone());
+		handle.mixer().add(sample.buffer.clone().amplify(volume));
 		Some(())
 	}
+
+	/// Plays any format supported by the enabled rodio features (mp3, wav).
+	/// Only the header is read here, the rest is decoded on the audio thread while playing.
+	pub fn play_file(&mut self, path: &Path, volume: f32) -> anyhow::Result<()> {
+		let file = std::fs::File::open(path)?;
+		let decoder = rodio::Decoder::new(std::io::BufReader::new(file))?;
+		if let Some(handle) = self.get_handle() {
+			handle.mixer().add(decoder.amplify(volume));
+		}
+		Ok(())
+	}
 }
 
 impl Default for AudioSystem {
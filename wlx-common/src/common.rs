@@ -1,11 +1,16 @@
 use std::fmt::Debug;
 
 use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumProperty, EnumString, VariantArray};
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+#[derive(
+	Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, AsRefStr, EnumString, EnumProperty, VariantArray,
+)]
 #[repr(u8)]
 pub enum LeftRight {
 	#[default]
+	#[strum(props(Translation = "APP_SETTINGS.OPTION.LEFT"))]
 	Left,
+	#[strum(props(Translation = "APP_SETTINGS.OPTION.RIGHT"))]
 	Right,
 }
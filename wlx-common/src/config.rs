@@ -7,7 +7,11 @@ use strum::{AsRefStr, EnumProperty, EnumString, VariantArray};
 use wayvr_ipc::packet_client::WvrProcessLaunchParams;
 
 use crate::{
-	astr_containers::{AStrMap, AStrSet}, locale::{self}, overlays::{BackendAttribValue, ToastDisplayMethod, ToastTopic}, windowing::OverlayWindowState
+	astr_containers::{AStrMap, AStrSet},
+	common::LeftRight,
+	locale::{self},
+	overlays::{BackendAttribValue, NotificationUrgency, ToastDisplayMethod, ToastTopic},
+	windowing::OverlayWindowState,
 };
 
 pub type PwTokenMap = AStrMap<String>;
@@ -74,6 +78,39 @@ pub struct SerializedWindowSet {
 	pub hidden_overlays: SerializedWindowStates,
 }
 
+/// Changes how matching notifications are presented. The first matching rule wins.
+/// All given conditions must match; regexes only need to match part of the text.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationRule {
+	#[serde(default)]
+	pub app_name: Option<String>,
+
+	#[serde(default)]
+	pub summary: Option<String>,
+
+	#[serde(default)]
+	pub body: Option<String>,
+
+	#[serde(default)]
+	pub urgency: Option<NotificationUrgency>,
+
+	#[serde(default)]
+	pub display: Option<ToastDisplayMethod>,
+
+	#[serde(default)]
+	pub sound: Option<bool>,
+
+	#[serde(default)]
+	pub haptics: Option<bool>,
+}
+
+/// Daily do-not-disturb window in local time, e.g. `start: "22:00"`, `end: "07:30"`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DndSchedule {
+	pub start: String,
+	pub end: String,
+}
+
 pub const fn def_pw_tokens() -> PwTokenMap {
 	AStrMap::new()
 }
@@ -86,6 +123,14 @@ const fn def_click_freeze_time_ms() -> i32 {
 	300
 }
 
+const fn def_keyboard_repeat_delay_ms() -> u32 {
+	400
+}
+
+const fn def_keyboard_repeat_rate() -> u32 {
+	25
+}
+
 const fn def_true() -> bool {
 	true
 }
@@ -114,6 +159,26 @@ const fn def_osc_port() -> u16 {
 	9000
 }
 
+const fn def_calendar_toast_minutes() -> u32 {
+	10
+}
+
+fn def_battery_warn_levels() -> Vec<u32> {
+	vec![20, 10, 5]
+}
+
+const fn def_battery_warn_repeat_minutes() -> u32 {
+	5
+}
+
+const fn def_clipboard_history_size() -> u32 {
+	25
+}
+
+fn def_screenshot_path() -> String {
+	"~/Pictures/WayVR".into()
+}
+
 fn def_timezones() -> Vec<String> {
 	const EMEA: i32 = -60 * 60; // UTC-1
 	const APAC: i32 = 5 * 60 * 60; // UTC+5
@@ -138,8 +203,6 @@ const fn def_max_height() -> u16 {
 	1440
 }
 
-
-
 #[derive(Deserialize, Serialize)]
 pub struct GeneralConfig {
 	#[serde(default = "def_theme_path")]
@@ -166,6 +229,9 @@ pub struct GeneralConfig {
 
 	pub default_keymap: Option<String>,
 
+	/// Language of the swipe and suggestion dictionaries as an ISO 639-1 code, picked from the keymap if unset
+	pub swipe_language: Option<String>,
+
 	#[serde(default)]
 	pub attribs: AStrMap<Vec<BackendAttribValue>>,
 
@@ -193,23 +259,91 @@ pub struct GeneralConfig {
 	#[serde(default = "def_true")]
 	pub notifications_sound_enabled: bool,
 
+	/// Act as the notification server when none is running
+	#[serde(default = "def_false")]
+	pub notifications_server: bool,
+
 	#[serde(default)]
 	pub notification_topics: IdMap<ToastTopic, ToastDisplayMethod>,
 
+	#[serde(default)]
+	pub notification_rules: Vec<NotificationRule>,
+
+	/// Do not disturb: notifications only go to the history
+	#[serde(default = "def_false")]
+	pub notifications_dnd: bool,
+
+	#[serde(default)]
+	pub notifications_dnd_schedule: Option<DndSchedule>,
+
 	#[serde(default = "def_true")]
 	pub keyboard_sound_enabled: bool,
 
+	/// Suggest completions and next words above the keyboard
+	#[serde(default = "def_true")]
+	pub keyboard_suggestions: bool,
+
+	/// Remember typed words to improve suggestions
+	#[serde(default = "def_false")]
+	pub keyboard_learn_words: bool,
+
+	/// Replace unknown words with the closest known one when pressing space
+	#[serde(default = "def_false")]
+	pub keyboard_autocorrect: bool,
+
 	#[serde(default = "def_one")]
 	pub keyboard_scale: f32,
 
+	/// How long a key is held before it starts repeating
+	#[serde(default = "def_keyboard_repeat_delay_ms")]
+	pub keyboard_repeat_delay_ms: u32,
+
+	/// Repeats per second of a held key, 0 to disable
+	#[serde(default = "def_keyboard_repeat_rate")]
+	pub keyboard_repeat_rate: u32,
+
+	/// Split the keyboard into halves attached to the controllers
+	#[serde(default = "def_false")]
+	pub keyboard_split: bool,
+
 	#[serde(default = "def_one")]
 	pub desktop_view_scale: f32,
 
+	/// Which hand the watch is attached to
+	#[serde(default)]
+	pub watch_hand: LeftRight,
+
+	/// Added to the default watch position, in meters.
+	/// Given for the left hand, mirrored for the right.
+	#[serde(default)]
+	pub watch_offset: [f32; 3],
+
+	/// Applied on top of the default watch orientation, in degrees around X, Y and Z.
+	/// Given for the left hand, mirrored for the right.
+	#[serde(default)]
+	pub watch_rotation: [f32; 3],
+
+	/// The watch starts fading in when looked at from within this angle (as a dot product)
 	#[serde(default = "def_half")]
-	pub watch_view_angle_min: f32,
+	pub watch_view_angle_min_left: f32,
 
+	/// The watch is fully visible when looked at from within this angle (as a dot product)
 	#[serde(default = "def_point7")]
-	pub watch_view_angle_max: f32,
+	pub watch_view_angle_max_left: f32,
+
+	#[serde(default = "def_half")]
+	pub watch_view_angle_min_right: f32,
+
+	#[serde(default = "def_point7")]
+	pub watch_view_angle_max_right: f32,
+
+	/// Older name of `watch_view_angle_min_<hand>`, for the hand the watch is on
+	#[serde(default, skip_serializing)]
+	pub watch_view_angle_min: Option<f32>,
+
+	/// Older name of `watch_view_angle_max_<hand>`, for the hand the watch is on
+	#[serde(default, skip_serializing)]
+	pub watch_view_angle_max: Option<f32>,
 
 	#[serde(default = "def_osc_port")]
 	pub osc_out_port: u16,
@@ -295,6 +429,34 @@ pub struct GeneralConfig {
 	#[serde(default = "def_false")]
 	pub clock_12h: bool,
 
+	/// Minutes between break reminders, 0 to disable
+	#[serde(default)]
+	pub break_reminder_minutes: u32,
+
+	/// iCalendar files, or directories to search for `.ics` files
+	#[serde(default)]
+	pub calendar_paths: Vec<String>,
+
+	/// Minutes before a calendar event starts to show a toast, 0 to disable
+	#[serde(default = "def_calendar_toast_minutes")]
+	pub calendar_toast_minutes: u32,
+
+	/// Battery levels in percent to warn at, for devices that are not charging
+	#[serde(default = "def_battery_warn_levels")]
+	pub battery_warn_levels: Vec<u32>,
+
+	/// Minutes between repeated warnings while below the same level, 0 to warn once per level
+	#[serde(default = "def_battery_warn_repeat_minutes")]
+	pub battery_warn_repeat_minutes: u32,
+
+	/// Number of clipboard entries to remember, 0 to disable the clipboard history
+	#[serde(default = "def_clipboard_history_size")]
+	pub clipboard_history_size: u32,
+
+	/// Directory to save screenshots to
+	#[serde(default = "def_screenshot_path")]
+	pub screenshot_path: String,
+
 	#[serde(default)]
 	pub sets: Vec<SerializedWindowSet>,
 
@@ -43,6 +43,16 @@ pub fn get_config_file_path(filename: &str) -> PathBuf {
 	get_config_root().join(filename)
 }
 
+/// `~/` is the home directory, relative paths start at the config directory.
+pub fn resolve_path(path: &str) -> PathBuf {
+	if let Some(rest) = path.strip_prefix("~/")
+		&& let Some(home) = std::env::var_os("HOME")
+	{
+		return PathBuf::from(home).join(rest);
+	}
+	get_config_file_path(path)
+}
+
 pub fn load(filename: &str) -> Option<String> {
 	let path = get_config_file_path(filename);
 	log::info!("Loading config: {}", path.to_string_lossy());
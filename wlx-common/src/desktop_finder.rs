@@ -330,6 +330,11 @@ impl DesktopFinder {
 		}
 	}
 
+	/// Look up an icon theme name (or absolute path) the same way desktop entry icons are resolved.
+	pub fn find_icon_path(&self, icon_name: &str) -> Option<String> {
+		Self::find_icon(&self.params, icon_name)
+	}
+
 	pub fn get_cached_entry(&self, app_id: &str) -> Option<&DesktopEntry> {
 		self.entry_cache.get(app_id)
 	}
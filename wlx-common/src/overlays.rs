@@ -10,6 +10,10 @@ pub enum ToastTopic {
 	DesktopNotification,
 	XSNotification,
 	IpdChange,
+	Alarm,
+	Calendar,
+	Battery,
+	Screenshot,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -19,6 +23,14 @@ pub enum ToastDisplayMethod {
 	Watch,
 }
 
+/// As sent in the `urgency` hint of desktop notifications
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NotificationUrgency {
+	Low,
+	Normal,
+	Critical,
+}
+
 #[derive(Debug, Clone, Copy, IntegerId, PartialEq)]
 pub enum BackendAttrib {
 	Stereo,